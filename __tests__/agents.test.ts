@@ -1,5 +1,7 @@
 import { describe, it, expect, vi, beforeEach } from 'vitest';
 import { invoke } from '@tauri-apps/api/core';
+import { createRehydrator, rehydrateValue } from '../app/store/rehydrate';
+import { useVoiceStore, type Redaction } from '../app/store/voiceStore';
 
 vi.mock('@tauri-apps/api/core');
 
@@ -26,14 +28,16 @@ describe('Action Items Agent', () => {
 
     mockInvoke.mockResolvedValueOnce(mockResult);
 
-    const result = await invoke('extract_action_items', {
-      apiKey: 'test-key',
-      transcript: 'John, please schedule a meeting with the team by Friday.',
+    const result = await invoke('run_agent', {
+      agentId: 'action-items',
+      input: { transcript: 'John, please schedule a meeting with the team by Friday.' },
+      options: { api_key: 'test-key' },
     });
 
-    expect(mockInvoke).toHaveBeenCalledWith('extract_action_items', {
-      apiKey: 'test-key',
-      transcript: 'John, please schedule a meeting with the team by Friday.',
+    expect(mockInvoke).toHaveBeenCalledWith('run_agent', {
+      agentId: 'action-items',
+      input: { transcript: 'John, please schedule a meeting with the team by Friday.' },
+      options: { api_key: 'test-key' },
     });
     expect(result).toEqual(mockResult);
     expect(result.items).toHaveLength(1);
@@ -44,9 +48,10 @@ describe('Action Items Agent', () => {
     const mockResult = { items: [], summary: 'No action items found' };
     mockInvoke.mockResolvedValueOnce(mockResult);
 
-    const result = await invoke('extract_action_items', {
-      apiKey: 'test-key',
-      transcript: '',
+    const result = await invoke('run_agent', {
+      agentId: 'action-items',
+      input: { transcript: '' },
+      options: { api_key: 'test-key' },
     });
 
     expect(result.items).toHaveLength(0);
@@ -68,16 +73,16 @@ describe('Tone Shifter Agent', () => {
 
     mockInvoke.mockResolvedValueOnce(mockResult);
 
-    const result = await invoke('shift_tone', {
-      apiKey: 'test-key',
-      text: 'Hey, can you get this done ASAP?',
-      targetTone: 'professional',
+    const result = await invoke('run_agent', {
+      agentId: 'tone-shifter',
+      input: { text: 'Hey, can you get this done ASAP?', target_tone: 'professional' },
+      options: { api_key: 'test-key' },
     });
 
-    expect(mockInvoke).toHaveBeenCalledWith('shift_tone', {
-      apiKey: 'test-key',
-      text: 'Hey, can you get this done ASAP?',
-      targetTone: 'professional',
+    expect(mockInvoke).toHaveBeenCalledWith('run_agent', {
+      agentId: 'tone-shifter',
+      input: { text: 'Hey, can you get this done ASAP?', target_tone: 'professional' },
+      options: { api_key: 'test-key' },
     });
     expect(result.tone).toBe('professional');
     expect(result.shifted).not.toBe(result.original);
@@ -104,6 +109,117 @@ describe('Tone Shifter Agent', () => {
   });
 });
 
+describe('Agent registry', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it('should list the registered agents', async () => {
+    const agents = [
+      {
+        id: 'brain-dump',
+        name: 'Brain Dump',
+        description: 'Sort thoughts into tasks, ideas and notes',
+        event_prefix: 'brain-dump',
+        input_schema: { type: 'object', required: ['transcript'] },
+        output_schema: { type: 'object' },
+      },
+      {
+        id: 'tone-shifter',
+        name: 'Tone Shifter',
+        description: 'Rewrite text in a different tone',
+        event_prefix: 'tone-shift',
+        input_schema: { type: 'object', required: ['target_tone', 'text'] },
+        output_schema: null,
+      },
+    ];
+    mockInvoke.mockResolvedValueOnce(agents);
+
+    const result = await invoke('list_agents');
+
+    expect(result).toHaveLength(2);
+    expect(result[1].output_schema).toBeNull();
+  });
+
+  it('should resolve to null when a run is cancelled', async () => {
+    mockInvoke.mockResolvedValueOnce(null);
+
+    const result = await invoke('run_agent', {
+      agentId: 'brain-dump',
+      input: { transcript: 'Buy milk' },
+      options: { stream: true, run_id: 'run-1' },
+    });
+
+    expect(result).toBeNull();
+  });
+});
+
+describe('PII rehydration', () => {
+  const redactions: Redaction[] = [
+    { placeholder: '[EMAIL_1]', original: 'anna@example.com', kind: 'email' },
+    { placeholder: '[PHONE_1]', original: '+49 170 1234567', kind: 'phone' },
+  ];
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+    mockInvoke.mockImplementation(async (command: string, args?: any) => {
+      if (command === 'process_transcript') {
+        return { text: 'Mail [EMAIL_1] or call [PHONE_1]', redactions };
+      }
+      if (command === 'rehydrate_text') {
+        // Same replacement as the backend
+        return (args.redactions as Redaction[]).reduce(
+          (text, r) => text.split(r.placeholder).join(r.original),
+          args.text as string
+        );
+      }
+      return null;
+    });
+  });
+
+  it('should restore the values process_transcript redacted in agent results', async () => {
+    const processed: any = await invoke('process_transcript', {
+      text: 'Mail anna@example.com or call +49 170 1234567',
+    });
+    useVoiceStore.getState().setRedactions(processed.redactions);
+
+    const result = await rehydrateValue({
+      items: [{ task: 'Email [EMAIL_1] the slides', assignee: null, priority: 'high' }],
+      summary: 'Call [PHONE_1]',
+    });
+
+    expect(result).toEqual({
+      items: [{ task: 'Email anna@example.com the slides', assignee: null, priority: 'high' }],
+      summary: 'Call +49 170 1234567',
+    });
+    expect(mockInvoke).toHaveBeenCalledWith('rehydrate_text', { text: 'Call [PHONE_1]', redactions });
+  });
+
+  it('should restore placeholders split across streamed chunks, in order', async () => {
+    useVoiceStore.getState().setRedactions(redactions);
+    const rehydrator = createRehydrator();
+    let streamed = '';
+    const append = (text: string) => {
+      streamed += text;
+    };
+
+    for (const chunk of ['Write to [EM', 'AIL_1] and', ' call [PHONE_1', '] soon [']) {
+      rehydrator.chunk('brain-dump', chunk, append);
+    }
+    rehydrator.flush('brain-dump', append);
+    await rehydrator.settled();
+
+    expect(streamed).toBe('Write to anna@example.com and call +49 170 1234567 soon [');
+  });
+
+  it('should leave text alone when nothing was redacted', async () => {
+    useVoiceStore.getState().setRedactions([]);
+
+    expect(await rehydrateValue('Mail [EMAIL_1]')).toBe('Mail [EMAIL_1]');
+    expect(mockInvoke).not.toHaveBeenCalled();
+  });
+});
+
 describe('Music Matcher Agent', () => {
   beforeEach(() => {
     vi.clearAllMocks();
@@ -119,9 +235,10 @@ describe('Music Matcher Agent', () => {
 
     mockInvoke.mockResolvedValueOnce(mockMood);
 
-    const result = await invoke('analyze_mood_from_transcript', {
-      openaiKey: 'test-key',
-      transcript: 'I am so excited to start this new project! Let\'s go!',
+    const result = await invoke('run_agent', {
+      agentId: 'music-matcher',
+      input: { transcript: 'I am so excited to start this new project! Let\'s go!' },
+      options: { api_key: 'test-key' },
     });
 
     expect(result.detected_mood).toBe('energetic');
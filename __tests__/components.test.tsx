@@ -85,6 +85,17 @@ describe('TranscriptDisplay', () => {
 
     expect(screen.getByText('Live')).toBeDefined();
   });
+
+  it('offers export only once recording has stopped', () => {
+    useVoiceStore.setState({ transcript: 'Testing' });
+    const { unmount } = render(<TranscriptDisplay />);
+    expect(screen.getByTitle('Export transcript')).toBeDefined();
+    unmount();
+
+    useVoiceStore.setState({ recordingState: 'recording' });
+    render(<TranscriptDisplay />);
+    expect(screen.queryByTitle('Export transcript')).toBeNull();
+  });
 });
 
 describe('AgentSelector', () => {
@@ -131,6 +142,16 @@ describe('AgentSelector', () => {
     const actionItemsButton = screen.getByText('Action Items').closest('button');
     expect(actionItemsButton?.className).toContain('bg-voice-primary');
   });
+
+  it('offers cancel only while processing', () => {
+    useVoiceStore.setState({ transcript: 'Some text' });
+    const { rerender } = render(<AgentSelector />);
+    expect(screen.queryByText('Cancel')).toBeNull();
+
+    useVoiceStore.setState({ isProcessing: true });
+    rerender(<AgentSelector />);
+    expect(screen.getByText('Cancel')).toBeDefined();
+  });
 });
 
 describe('AgentResults', () => {
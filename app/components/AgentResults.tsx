@@ -105,14 +105,14 @@ function useOutputTranslation(originalText: string | null) {
         return;
       }
 
-      const result = await invoke<{ translated: string }>('translate_text', {
-        apiKey: openaiKey,
-        text,
-        sourceLanguage: 'auto',
-        targetLanguage: lang,
+      // Not streamed, so the Translator agent's own view is left alone
+      const result = await invoke<{ translated: string } | null>('run_agent', {
+        agentId: 'translator',
+        input: { text, source_language: 'auto', target_language: lang },
+        options: { api_key: openaiKey },
       });
 
-      setTranslatedText(result.translated);
+      if (result) setTranslatedText(result.translated);
     } catch (err) {
       console.error('Translation error:', err);
     } finally {
@@ -597,8 +597,6 @@ function DevLogDisplay({
   const { isSpeaking, speak, stop } = useTextToSpeech();
   const [expandedSection, setExpandedSection] = useState<string | null>(null);
 
-  const showBlur = isProcessing;
-
   if (!result && !isProcessing) {
     return null;
   }
@@ -628,7 +626,7 @@ function DevLogDisplay({
   };
 
   return (
-    <div className={`transition-all duration-300 ${showBlur ? 'blur-[2px] select-none' : 'blur-0'}`}>
+    <div>
       <div className="flex items-center justify-between mb-4">
         <h3 className="text-xs font-medium text-gray-400 uppercase tracking-wider">
           Dev Documentation
@@ -892,7 +890,8 @@ function BrainDumpDisplay({
     clearTranslation,
   } = useOutputTranslation(originalText);
 
-  const showBlur = isProcessing || isTranslating;
+  // Entries stream in while processing, so only blur while translating
+  const showBlur = isTranslating;
 
   if (!result && !isProcessing) {
     return null;
@@ -1297,7 +1296,8 @@ ${result.the_release}
     clearTranslation,
   } = useOutputTranslation(originalText);
 
-  const showBlur = isProcessing || isTranslating;
+  // Entries stream in while processing, so only blur while translating
+  const showBlur = isTranslating;
 
   // Schedule email for tomorrow (mock)
   const handleScheduleEmail = async () => {
@@ -1,7 +1,7 @@
 'use client';
 
-import { useCallback } from 'react';
-import { useVoiceStore, AgentType } from '../store/voiceStore';
+import { useCallback, useRef } from 'react';
+import { useVoiceStore, AgentType, type Redaction } from '../store/voiceStore';
 
 const agents: Array<{
   id: AgentType;
@@ -55,7 +55,10 @@ const agents: Array<{
 ];
 
 export function AgentSelector() {
-  const { activeAgent, setActiveAgent, transcript, isProcessing, setProcessing, setError } = useVoiceStore();
+  const { activeAgent, setActiveAgent, setRedactions, transcript, isProcessing, setProcessing, setError } =
+    useVoiceStore();
+  // Run ID of the streaming agent in flight, for cancel_agent_run
+  const runIdRef = useRef<string | null>(null);
 
   const runAgent = useCallback(
     async (agentId: AgentType) => {
@@ -66,105 +69,97 @@ export function AgentSelector() {
 
       try {
         const { invoke } = await import('@tauri-apps/api/core');
+        const runId = crypto.randomUUID();
+        runIdRef.current = runId;
 
-        // Get API keys
+        // Get API keys; the backend picks the provider from the agent config
+        // and reports a missing key itself
         const openaiKey = await invoke<string | null>('get_api_key', { keyType: 'openai' });
         const anthropicKey = await invoke<string | null>('get_api_key', { keyType: 'anthropic' });
 
-        switch (agentId) {
-          case 'action-items':
-            if (!openaiKey) {
-              throw new Error('OpenAI API key required for Action Items');
-            }
-            await invoke('extract_action_items', {
-              apiKey: openaiKey,
-              transcript,
-            });
-            break;
+        // The spoken language picks the filler words to drop; when it's mixed or
+        // unknown the backend falls back to the language settings
+        const languages = new Set(
+          useVoiceStore.getState().transcriptSegments.flatMap((s) => (s.language ? [s.language] : []))
+        );
+        const language = languages.size === 1 ? Array.from(languages)[0] : null;
 
+        // Optional clean-up and PII redaction before the transcript leaves the device;
+        // the agent events are re-hydrated with the redactions (see useTauriEvents)
+        const { text: prepared, redactions } = await invoke<{ text: string; redactions: Redaction[] }>(
+          'process_transcript',
+          { text: transcript, language }
+        );
+        setRedactions(redactions);
+
+        // Input of the registered backend agent (see list_agents)
+        let input: Record<string, unknown>;
+        let apiKey = openaiKey;
+        switch (agentId) {
           case 'tone-shifter':
-            if (!anthropicKey) {
-              throw new Error('Anthropic API key required for Tone Shifter');
-            }
             const { selectedTone, toneIntensity } = useVoiceStore.getState();
-            await invoke('shift_tone_streaming', {
-              apiKey: anthropicKey,
-              text: transcript,
-              targetTone: selectedTone,
-              intensity: toneIntensity,
-            });
-            break;
-
-          case 'music-matcher':
-            if (!openaiKey) {
-              throw new Error('OpenAI API key required for Music Matcher');
-            }
-            // First analyze mood, then match music
-            await invoke('analyze_mood_from_transcript', {
-              openaiKey,
-              transcript,
-            });
-            const qrecordsKey = await invoke<string | null>('get_api_key', { keyType: 'qrecords' });
-            if (qrecordsKey) {
-              await invoke('match_music', {
-                apiKey: qrecordsKey,
-                request: { query: transcript },
-              });
-            }
+            input = { text: prepared, target_tone: selectedTone, intensity: toneIntensity };
+            apiKey = anthropicKey;
             break;
 
           case 'translator':
-            if (!openaiKey) {
-              throw new Error('OpenAI API key required for Translator');
-            }
-            const { selectedSourceLanguage, selectedTargetLanguage } = useVoiceStore.getState();
-            await invoke('translate_text_streaming', {
-              apiKey: openaiKey,
-              text: transcript,
-              sourceLanguage: selectedSourceLanguage,
-              targetLanguage: selectedTargetLanguage,
-            });
+            const { selectedSourceLanguage, selectedTargetLanguage, transcriptSegments } =
+              useVoiceStore.getState();
+            // Segment texts are unprocessed, so only use them (to skip parts already
+            // in the target language) when post-processing left the transcript as is
+            const segments =
+              prepared === transcript && transcriptSegments.some((s) => s.language)
+                ? transcriptSegments.map(({ text, language }) => ({ text, language: language ?? null }))
+                : null;
+            input = {
+              text: prepared,
+              source_language: selectedSourceLanguage,
+              target_language: selectedTargetLanguage,
+              segments,
+            };
             break;
 
-          case 'dev-log':
-            if (!openaiKey) {
-              throw new Error('OpenAI API key required for Dev-Log');
-            }
-            await invoke('generate_dev_log_streaming', {
-              apiKey: openaiKey,
-              transcript,
-            });
-            break;
+          default:
+            input = { transcript: prepared };
+        }
 
-          case 'brain-dump':
-            if (!openaiKey) {
-              throw new Error('OpenAI API key required for Brain Dump');
-            }
-            await invoke('process_brain_dump_streaming', {
-              apiKey: openaiKey,
-              transcript,
-            });
-            break;
+        const result = await invoke<unknown>('run_agent', {
+          agentId,
+          input,
+          options: { api_key: apiKey, stream: true, run_id: runId },
+        });
 
-          case 'mental-mirror':
-            if (!openaiKey) {
-              throw new Error('OpenAI API key required for Letter to Myself');
-            }
-            await invoke('generate_mental_mirror_streaming', {
-              apiKey: openaiKey,
-              transcript,
+        // Music Matcher: the mood analysis drives the Q-Records search
+        if (agentId === 'music-matcher' && result) {
+          const qrecordsKey = await invoke<string | null>('get_api_key', { keyType: 'qrecords' });
+          if (qrecordsKey) {
+            await invoke('match_music', {
+              apiKey: qrecordsKey,
+              request: { query: prepared },
             });
-            break;
+          }
         }
       } catch (error) {
         console.error('Agent error:', error);
-        setError(error instanceof Error ? error.message : 'Agent failed');
+        setError(
+          error instanceof Error ? error.message : typeof error === 'string' ? error : 'Agent failed'
+        );
         setProcessing(false);
       }
     },
-    [transcript, isProcessing, setActiveAgent, setProcessing, setError]
+    [transcript, isProcessing, setActiveAgent, setRedactions, setProcessing, setError]
   );
 
+  const cancelAgent = useCallback(async () => {
+    if (!runIdRef.current) return;
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      await invoke('cancel_agent_run', { runId: runIdRef.current });
+    } catch (error) {
+      console.error('Cancel error:', error);
+    }
+  }, []);
+
   return (
     <div className="flex flex-wrap justify-center gap-2">
       {agents.map((agent) => (
@@ -188,6 +183,14 @@ export function AgentSelector() {
           <span>{agent.name}</span>
         </button>
       ))}
+      {isProcessing && (
+        <button
+          onClick={cancelAgent}
+          className="px-3 py-2 rounded-lg text-sm font-medium bg-voice-surface text-gray-300 hover:bg-voice-border hover:text-white transition-all duration-200"
+        >
+          Cancel
+        </button>
+      )}
     </div>
   );
 }
@@ -42,6 +42,7 @@ export function ToneSelector() {
     setError,
     clearToneShiftStreaming,
     setToneShiftResult,
+    setRedactions,
   } = useVoiceStore();
 
   const [presets, setPresets] = useState<TonePreset[]>([]);
@@ -89,21 +90,26 @@ export function ToneSelector() {
       // Clear previous results before starting new request
       clearToneShiftStreaming();
       setToneShiftResult(null);
+      // The transcript goes out as is, so there are no placeholders to restore
+      setRedactions([]);
       setProcessing(true, 'Shifting tone...');
 
-      await invoke('shift_tone_streaming', {
-        apiKey: anthropicKey,
-        text: transcript,
-        targetTone: tone,
-        intensity: intensity,
-        lengthAdjustment: lengthAdjustment,
+      await invoke('run_agent', {
+        agentId: 'tone-shifter',
+        input: {
+          text: transcript,
+          target_tone: tone,
+          intensity,
+          length_adjustment: lengthAdjustment,
+        },
+        options: { api_key: anthropicKey, stream: true },
       });
     } catch (error) {
       console.error('Tone shift error:', error);
       setError(error instanceof Error ? error.message : 'Tone shift failed');
       setProcessing(false);
     }
-  }, [transcript, isProcessing, setProcessing, setError, clearToneShiftStreaming, setToneShiftResult]);
+  }, [transcript, isProcessing, setProcessing, setError, clearToneShiftStreaming, setToneShiftResult, setRedactions]);
 
   // Auto-rerun when tone changes (immediate)
   useEffect(() => {
@@ -3,9 +3,41 @@
 import { useState, useCallback } from 'react';
 import { useVoiceStore } from '../store/voiceStore';
 
+type ExportFormat = 'srt' | 'vtt' | 'txt' | 'md' | 'json';
+
+const EXPORT_FORMATS: { id: ExportFormat; label: string }[] = [
+  { id: 'srt', label: 'SRT' },
+  { id: 'vtt', label: 'WebVTT' },
+  { id: 'txt', label: 'Text' },
+  { id: 'md', label: 'Markdown' },
+  { id: 'json', label: 'JSON' },
+];
+
 export function TranscriptDisplay() {
-  const { transcript, interimTranscript, recordingState } = useVoiceStore();
+  const { transcript, interimTranscript, transcriptSegments, recordingState, setError } = useVoiceStore();
   const [copied, setCopied] = useState(false);
+  const [exportFormat, setExportFormat] = useState<ExportFormat>('srt');
+
+  const exportTranscript = useCallback(async () => {
+    if (!transcript) return;
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      // Without timing (e.g. mobile Whisper), export the text as a single segment
+      const segments = transcriptSegments.length > 0
+        ? transcriptSegments
+        : [{ start_ms: 0, end_ms: 0, text: transcript }];
+      await invoke<string>('export_transcript', {
+        transcript: { segments },
+        format: exportFormat,
+      });
+    } catch (err) {
+      const message = err instanceof Error ? err.message : String(err);
+      if (message !== 'Export cancelled.') {
+        console.error('Failed to export:', err);
+        setError(message);
+      }
+    }
+  }, [transcript, transcriptSegments, exportFormat, setError]);
 
   const copyToClipboard = useCallback(async () => {
     if (!transcript) return;
@@ -39,6 +71,29 @@ export function TranscriptDisplay() {
                 <span className="text-xs text-red-400">Live</span>
               </span>
             )}
+            {transcript && !isRecording && (
+              <>
+                <select
+                  value={exportFormat}
+                  onChange={(e) => setExportFormat(e.target.value as ExportFormat)}
+                  className="bg-transparent text-xs text-gray-400 border border-gray-700 rounded px-1 py-0.5"
+                  title="Export format"
+                >
+                  {EXPORT_FORMATS.map((format) => (
+                    <option key={format.id} value={format.id}>
+                      {format.label}
+                    </option>
+                  ))}
+                </select>
+                <button
+                  onClick={exportTranscript}
+                  className="p-1 text-gray-500 hover:text-white transition-colors rounded"
+                  title="Export transcript"
+                >
+                  <DownloadIcon className="w-4 h-4" />
+                </button>
+              </>
+            )}
             {transcript && (
               <button
                 onClick={copyToClipboard}
@@ -73,6 +128,14 @@ function CopyIcon({ className }: { className?: string }) {
   );
 }
 
+function DownloadIcon({ className }: { className?: string }) {
+  return (
+    <svg className={className} fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2}>
+      <path strokeLinecap="round" strokeLinejoin="round" d="M4 16v1a3 3 0 003 3h10a3 3 0 003-3v-1m-4-4l-4 4m0 0l-4-4m4 4V4" />
+    </svg>
+  );
+}
+
 function CheckIcon({ className }: { className?: string }) {
   return (
     <svg className={className} fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2}>
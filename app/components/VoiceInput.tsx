@@ -90,24 +90,20 @@ export function VoiceInput() {
       const { invoke } = await import('@tauri-apps/api/core');
 
       if (isRecording) {
-        // Stop recording and Deepgram stream
+        // Stop recording, then the live stream (may start a re-transcription job)
         await invoke('stop_recording');
-        await invoke('stop_deepgram_stream').catch(() => {});
+        await invoke('stop_hybrid_transcription').catch(() => {});
       } else {
         setRecordingState('recording');
 
-        // Start Deepgram stream first (if API key exists)
+        // Start live transcription: Deepgram when available, local Whisper otherwise
         try {
-          const apiKey = await invoke<string | null>('get_api_key', { keyType: 'deepgram' });
-          if (apiKey) {
-            console.log('[VoiceInput] Starting Deepgram stream...');
-            await invoke('start_deepgram_stream', { apiKey });
-            console.log('[VoiceInput] Deepgram stream started');
-          } else {
-            console.log('[VoiceInput] No Deepgram API key, skipping transcription');
-          }
+          const selection = await invoke<{ provider: string; reason: string }>(
+            'start_hybrid_transcription'
+          );
+          console.log(`[VoiceInput] Transcribing with ${selection.provider} (${selection.reason})`);
         } catch (err) {
-          console.error('[VoiceInput] Failed to start Deepgram:', err);
+          console.error('[VoiceInput] Failed to start transcription:', err);
         }
 
         // Then start audio recording
@@ -1,7 +1,13 @@
 'use client';
 
 import { useEffect } from 'react';
-import { useVoiceStore } from '../store/voiceStore';
+import {
+  useVoiceStore,
+  type BrainDumpItem,
+  type DevLogPart,
+  type TranscriptSegment,
+} from '../store/voiceStore';
+import { createRehydrator } from '../store/rehydrate';
 
 interface TauriEvent<T> {
   payload: T;
@@ -12,6 +18,34 @@ interface TranscriptPayload {
   is_final: boolean;
   confidence: number;
   source: string;
+  low_confidence_spans?: Array<{
+    text: string;
+    start_ms: number;
+    end_ms: number;
+    confidence: number;
+  }>;
+  start_ms?: number | null;
+  end_ms?: number | null;
+  language?: string | null;
+  /** Timed pieces of a batch transcript */
+  segments?: TranscriptSegment[];
+}
+
+function toSegment(payload: TranscriptPayload): TranscriptSegment | null {
+  if (payload.start_ms == null || payload.end_ms == null || !payload.text) return null;
+  return {
+    start_ms: payload.start_ms,
+    end_ms: payload.end_ms,
+    text: payload.text,
+    confidence: payload.confidence,
+    language: payload.language ?? undefined,
+  };
+}
+
+interface TranscriptionProviderPayload {
+  provider: 'deepgram' | 'local_whisper';
+  reason: string;
+  is_fallback: boolean;
 }
 
 interface VadPayload {
@@ -99,6 +133,11 @@ interface MentalMirrorChunkPayload {
   is_complete: boolean;
 }
 
+interface MentalMirrorSectionPayload {
+  section: string;
+  text: string;
+}
+
 interface MentalMirrorResultPayload {
   reflection: string;
   mental_checkin: string;
@@ -135,6 +174,9 @@ export function useTauriEvents() {
     setHasRecording,
     setRecordingDuration,
     appendTranscript,
+    setTranscript,
+    addTranscriptSegment,
+    setTranscriptSegments,
     setVadState,
     setActionItems,
     setToneShiftResult,
@@ -148,12 +190,15 @@ export function useTauriEvents() {
     setDevLogResult,
     appendDevLogStreaming,
     clearDevLogStreaming,
+    addDevLogPart,
     setBrainDumpResult,
     appendBrainDumpStreaming,
     clearBrainDumpStreaming,
+    addBrainDumpItem,
     setMentalMirrorResult,
     appendMentalMirrorStreaming,
     clearMentalMirrorStreaming,
+    addMentalMirrorSection,
     setProcessing,
     setError,
   } = useVoiceStore();
@@ -164,6 +209,8 @@ export function useTauriEvents() {
     async function setupListeners() {
       try {
         const { listen } = await import('@tauri-apps/api/event');
+        // Agents only saw placeholders for redacted PII; put the originals back
+        const rehydrator = createRehydrator();
 
         // Recording events
         const unlistenRecordingStarted = await listen('recording-started', () => {
@@ -204,10 +251,42 @@ export function useTauriEvents() {
           'transcript',
           (event: TauriEvent<TranscriptPayload>) => {
             appendTranscript(event.payload.text, event.payload.is_final);
+            if (event.payload.segments?.length) {
+              event.payload.segments.forEach(addTranscriptSegment);
+              return;
+            }
+            const segment = event.payload.is_final ? toSegment(event.payload) : null;
+            if (segment) addTranscriptSegment(segment);
           }
         );
         listeners.push(unlistenTranscript);
 
+        // Higher-quality pass over the whole recording replaces the live transcript
+        const unlistenRetranscribed = await listen<TranscriptPayload>(
+          'transcript-retranscribed',
+          (event: TauriEvent<TranscriptPayload>) => {
+            setTranscript(event.payload.text);
+            if (event.payload.segments?.length) {
+              setTranscriptSegments(event.payload.segments);
+              return;
+            }
+            const segment = toSegment(event.payload);
+            setTranscriptSegments(segment ? [segment] : []);
+          }
+        );
+        listeners.push(unlistenRetranscribed);
+
+        // Live provider chosen (or switched to) by the hybrid policy
+        const unlistenProvider = await listen<TranscriptionProviderPayload>(
+          'transcription-provider',
+          (event: TauriEvent<TranscriptionProviderPayload>) => {
+            console.log(
+              `Transcribing with ${event.payload.provider}${event.payload.is_fallback ? ' (fallback)' : ''}: ${event.payload.reason}`
+            );
+          }
+        );
+        listeners.push(unlistenProvider);
+
         // VAD events
         const unlistenVad = await listen<VadPayload>(
           'vad-event',
@@ -225,26 +304,28 @@ export function useTauriEvents() {
         listeners.push(unlistenSilence);
 
         // Action items events
-        const unlistenActionItemsProcessing = await listen(
-          'action-items-processing',
+        const unlistenActionItemsStarted = await listen(
+          'action-items-started',
           () => {
             setProcessing(true, 'Extracting action items...');
           }
         );
-        listeners.push(unlistenActionItemsProcessing);
+        listeners.push(unlistenActionItemsStarted);
 
         const unlistenActionItems = await listen<ActionItemsPayload>(
-          'action-items-extracted',
+          'action-items-complete',
           (event: TauriEvent<ActionItemsPayload>) => {
-            setActionItems(event.payload.items);
-            setProcessing(false);
+            rehydrator.value(event.payload.items, (items) => {
+              setActionItems(items);
+              setProcessing(false);
+            });
           }
         );
         listeners.push(unlistenActionItems);
 
         // Tone shift events
         const unlistenToneShiftStarted = await listen('tone-shift-started', () => {
-          clearToneShiftStreaming();
+          rehydrator.reset('tone-shift', clearToneShiftStreaming);
           setProcessing(true, 'Shifting tone...');
         });
         listeners.push(unlistenToneShiftStarted);
@@ -252,8 +333,10 @@ export function useTauriEvents() {
         const unlistenToneShiftChunk = await listen<ToneShiftChunkPayload>(
           'tone-shift-chunk',
           (event: TauriEvent<ToneShiftChunkPayload>) => {
-            if (!event.payload.is_complete) {
-              appendToneShiftStreaming(event.payload.text);
+            if (event.payload.is_complete) {
+              rehydrator.flush('tone-shift', appendToneShiftStreaming);
+            } else {
+              rehydrator.chunk('tone-shift', event.payload.text, appendToneShiftStreaming);
             }
           }
         );
@@ -262,8 +345,10 @@ export function useTauriEvents() {
         const unlistenToneShiftComplete = await listen<ToneShiftResultPayload>(
           'tone-shift-complete',
           (event: TauriEvent<ToneShiftResultPayload>) => {
-            setToneShiftResult(event.payload);
-            setProcessing(false);
+            rehydrator.value(event.payload, (result) => {
+              setToneShiftResult(result);
+              setProcessing(false);
+            });
           }
         );
         listeners.push(unlistenToneShiftComplete);
@@ -285,8 +370,8 @@ export function useTauriEvents() {
           energy_level: number;
           valence: number;
           keywords: string[];
-        }>('mood-analyzed', (event) => {
-          setMoodAnalysis(event.payload);
+        }>('mood-analysis-complete', (event) => {
+          rehydrator.value(event.payload, setMoodAnalysis);
         });
         listeners.push(unlistenMoodAnalyzed);
 
@@ -298,7 +383,7 @@ export function useTauriEvents() {
 
         // Translation events
         const unlistenTranslationStarted = await listen('translation-started', () => {
-          clearTranslationStreaming();
+          rehydrator.reset('translation', clearTranslationStreaming);
           setProcessing(true, 'Translating...');
         });
         listeners.push(unlistenTranslationStarted);
@@ -306,8 +391,10 @@ export function useTauriEvents() {
         const unlistenTranslationChunk = await listen<TranslationChunkPayload>(
           'translation-chunk',
           (event: TauriEvent<TranslationChunkPayload>) => {
-            if (!event.payload.is_complete) {
-              appendTranslationStreaming(event.payload.text);
+            if (event.payload.is_complete) {
+              rehydrator.flush('translation', appendTranslationStreaming);
+            } else {
+              rehydrator.chunk('translation', event.payload.text, appendTranslationStreaming);
             }
           }
         );
@@ -316,15 +403,20 @@ export function useTauriEvents() {
         const unlistenTranslationComplete = await listen<TranslationResultPayload>(
           'translation-complete',
           (event: TauriEvent<TranslationResultPayload>) => {
-            setTranslationResult(event.payload);
-            setProcessing(false);
+            rehydrator.value(event.payload, (result) => {
+              setTranslationResult(result);
+              setProcessing(false);
+            });
           }
         );
         listeners.push(unlistenTranslationComplete);
 
         // Dev-Log events
         const unlistenDevLogStarted = await listen('dev-log-started', () => {
-          clearDevLogStreaming();
+          rehydrator.reset('dev-log', () => {
+            clearDevLogStreaming();
+            setDevLogResult(null);
+          });
           setProcessing(true, 'Generating dev documentation...');
         });
         listeners.push(unlistenDevLogStarted);
@@ -332,25 +424,41 @@ export function useTauriEvents() {
         const unlistenDevLogChunk = await listen<DevLogChunkPayload>(
           'dev-log-chunk',
           (event: TauriEvent<DevLogChunkPayload>) => {
-            if (!event.payload.is_complete) {
-              appendDevLogStreaming(event.payload.text);
+            if (event.payload.is_complete) {
+              rehydrator.flush('dev-log', appendDevLogStreaming);
+            } else {
+              rehydrator.chunk('dev-log', event.payload.text, appendDevLogStreaming);
             }
           }
         );
         listeners.push(unlistenDevLogChunk);
 
+        // Finished outputs, rendered before the whole log is done
+        const unlistenDevLogPart = await listen<DevLogPart>(
+          'dev-log-part',
+          (event: TauriEvent<DevLogPart>) => {
+            rehydrator.value(event.payload, addDevLogPart);
+          }
+        );
+        listeners.push(unlistenDevLogPart);
+
         const unlistenDevLogComplete = await listen<DevLogResultPayload>(
           'dev-log-complete',
           (event: TauriEvent<DevLogResultPayload>) => {
-            setDevLogResult(event.payload);
-            setProcessing(false);
+            rehydrator.value(event.payload, (result) => {
+              setDevLogResult(result);
+              setProcessing(false);
+            });
           }
         );
         listeners.push(unlistenDevLogComplete);
 
         // Brain Dump events
         const unlistenBrainDumpStarted = await listen('brain-dump-started', () => {
-          clearBrainDumpStreaming();
+          rehydrator.reset('brain-dump', () => {
+            clearBrainDumpStreaming();
+            setBrainDumpResult(null);
+          });
           setProcessing(true, 'Processing brain dump...');
         });
         listeners.push(unlistenBrainDumpStarted);
@@ -358,25 +466,41 @@ export function useTauriEvents() {
         const unlistenBrainDumpChunk = await listen<BrainDumpChunkPayload>(
           'brain-dump-chunk',
           (event: TauriEvent<BrainDumpChunkPayload>) => {
-            if (!event.payload.is_complete) {
-              appendBrainDumpStreaming(event.payload.text);
+            if (event.payload.is_complete) {
+              rehydrator.flush('brain-dump', appendBrainDumpStreaming);
+            } else {
+              rehydrator.chunk('brain-dump', event.payload.text, appendBrainDumpStreaming);
             }
           }
         );
         listeners.push(unlistenBrainDumpChunk);
 
+        // Finished entries, rendered before the whole result is done
+        const unlistenBrainDumpItem = await listen<BrainDumpItem>(
+          'brain-dump-item',
+          (event: TauriEvent<BrainDumpItem>) => {
+            rehydrator.value(event.payload, addBrainDumpItem);
+          }
+        );
+        listeners.push(unlistenBrainDumpItem);
+
         const unlistenBrainDumpComplete = await listen<BrainDumpResultPayload>(
           'brain-dump-complete',
           (event: TauriEvent<BrainDumpResultPayload>) => {
-            setBrainDumpResult(event.payload);
-            setProcessing(false);
+            rehydrator.value(event.payload, (result) => {
+              setBrainDumpResult(result);
+              setProcessing(false);
+            });
           }
         );
         listeners.push(unlistenBrainDumpComplete);
 
         // Mental Mirror (Letter to Myself) events
         const unlistenMentalMirrorStarted = await listen('mental-mirror-started', () => {
-          clearMentalMirrorStreaming();
+          rehydrator.reset('mental-mirror', () => {
+            clearMentalMirrorStreaming();
+            setMentalMirrorResult(null);
+          });
           setProcessing(true, 'Writing your letter...');
         });
         listeners.push(unlistenMentalMirrorStarted);
@@ -384,21 +508,50 @@ export function useTauriEvents() {
         const unlistenMentalMirrorChunk = await listen<MentalMirrorChunkPayload>(
           'mental-mirror-chunk',
           (event: TauriEvent<MentalMirrorChunkPayload>) => {
-            if (!event.payload.is_complete) {
-              appendMentalMirrorStreaming(event.payload.text);
+            if (event.payload.is_complete) {
+              rehydrator.flush('mental-mirror', appendMentalMirrorStreaming);
+            } else {
+              rehydrator.chunk('mental-mirror', event.payload.text, appendMentalMirrorStreaming);
             }
           }
         );
         listeners.push(unlistenMentalMirrorChunk);
 
+        // Finished sections, rendered before the whole letter is done
+        const unlistenMentalMirrorSection = await listen<MentalMirrorSectionPayload>(
+          'mental-mirror-section',
+          (event: TauriEvent<MentalMirrorSectionPayload>) => {
+            rehydrator.value(event.payload, ({ section, text }) => addMentalMirrorSection(section, text));
+          }
+        );
+        listeners.push(unlistenMentalMirrorSection);
+
         const unlistenMentalMirrorComplete = await listen<MentalMirrorResultPayload>(
           'mental-mirror-complete',
           (event: TauriEvent<MentalMirrorResultPayload>) => {
-            setMentalMirrorResult(event.payload);
-            setProcessing(false);
+            rehydrator.value(event.payload, (result) => {
+              setMentalMirrorResult(result);
+              setProcessing(false);
+            });
           }
         );
         listeners.push(unlistenMentalMirrorComplete);
+
+        // Cancelled agent runs (cancel_agent_run)
+        for (const prefix of [
+          'action-items',
+          'tone-shift',
+          'mood-analysis',
+          'translation',
+          'dev-log',
+          'brain-dump',
+          'mental-mirror',
+        ]) {
+          const unlistenCancelled = await listen(`${prefix}-cancelled`, () => {
+            setProcessing(false);
+          });
+          listeners.push(unlistenCancelled);
+        }
       } catch (error) {
         // Running outside Tauri (e.g., in browser dev mode)
         console.log('Tauri events not available:', error);
@@ -415,6 +568,9 @@ export function useTauriEvents() {
     setHasRecording,
     setRecordingDuration,
     appendTranscript,
+    setTranscript,
+    addTranscriptSegment,
+    setTranscriptSegments,
     setVadState,
     setActionItems,
     setToneShiftResult,
@@ -428,12 +584,15 @@ export function useTauriEvents() {
     setDevLogResult,
     appendDevLogStreaming,
     clearDevLogStreaming,
+    addDevLogPart,
     setBrainDumpResult,
     appendBrainDumpStreaming,
     clearBrainDumpStreaming,
+    addBrainDumpItem,
     setMentalMirrorResult,
     appendMentalMirrorStreaming,
     clearMentalMirrorStreaming,
+    addMentalMirrorSection,
     setProcessing,
     setError,
   ]);
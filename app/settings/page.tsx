@@ -24,6 +24,12 @@ const API_KEYS: ApiKeyConfig[] = [
     description: 'Fallback transcription service',
     placeholder: 'Enter your AssemblyAI API key',
   },
+  {
+    id: 'openai_compatible',
+    name: 'OpenAI-compatible STT',
+    description: 'Whisper API or a self-hosted compatible server',
+    placeholder: 'Enter the API key for your transcription endpoint',
+  },
   {
     id: 'openai',
     name: 'OpenAI',
@@ -36,6 +42,12 @@ const API_KEYS: ApiKeyConfig[] = [
     description: 'Claude for tone shifting',
     placeholder: 'Enter your Anthropic API key',
   },
+  {
+    id: 'local_llm',
+    name: 'Local LLM',
+    description: 'Only needed if your local server requires an API key',
+    placeholder: 'Enter the API key for your local LLM server',
+  },
   {
     id: 'qrecords',
     name: 'Q-Records',
@@ -0,0 +1,92 @@
+import { useVoiceStore, type Redaction } from './voiceStore';
+
+/** Longest placeholder held back while waiting for its end, e.g. `[EMAIL_12]` */
+const MAX_PLACEHOLDER_LENGTH = 16;
+
+async function rehydrateText(text: string, redactions: Redaction[]): Promise<string> {
+  if (redactions.length === 0 || !text.includes('[')) return text;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<string>('rehydrate_text', { text, redactions });
+}
+
+/** `value` with the original values back in all of its strings */
+export async function rehydrateValue<T>(
+  value: T,
+  redactions: Redaction[] = useVoiceStore.getState().redactions
+): Promise<T> {
+  if (typeof value === 'string') {
+    return (await rehydrateText(value, redactions)) as T;
+  }
+  if (Array.isArray(value)) {
+    return (await Promise.all(value.map((item) => rehydrateValue(item, redactions)))) as T;
+  }
+  if (value && typeof value === 'object') {
+    const entries = await Promise.all(
+      Object.entries(value).map(async ([key, field]) => [key, await rehydrateValue(field, redactions)] as const)
+    );
+    return Object.fromEntries(entries) as T;
+  }
+  return value;
+}
+
+/** Where a placeholder that isn't complete yet starts at the end of `text` */
+export function incompletePlaceholderStart(text: string): number {
+  const open = text.lastIndexOf('[');
+  if (open === -1 || text.includes(']', open) || text.length - open > MAX_PLACEHOLDER_LENGTH) {
+    return text.length;
+  }
+  return open;
+}
+
+/**
+ * Restores redacted values in agent events before they reach the store.
+ * Events are handled in the order they arrived, and the end of a streamed
+ * chunk that may be the start of a placeholder waits for the next chunk.
+ */
+export function createRehydrator() {
+  let queue: Promise<void> = Promise.resolve();
+  const pending = new Map<string, string>();
+
+  const enqueue = (task: () => Promise<void>) => {
+    queue = queue.then(task).catch((error) => console.error('Rehydration error:', error));
+  };
+
+  return {
+    /** Streamed text of `stream` */
+    chunk(stream: string, text: string, apply: (text: string) => void) {
+      enqueue(async () => {
+        const buffered = (pending.get(stream) ?? '') + text;
+        const end = incompletePlaceholderStart(buffered);
+        pending.set(stream, buffered.slice(end));
+        if (end > 0) apply(await rehydrateValue(buffered.slice(0, end)));
+      });
+    },
+
+    /** Apply what `stream` still holds back, at its end */
+    flush(stream: string, apply: (text: string) => void) {
+      enqueue(async () => {
+        const rest = pending.get(stream) ?? '';
+        pending.delete(stream);
+        if (rest) apply(await rehydrateValue(rest));
+      });
+    },
+
+    /** Drop what `stream` holds back when a new run starts, then `apply` */
+    reset(stream: string, apply: () => void) {
+      enqueue(async () => {
+        pending.delete(stream);
+        apply();
+      });
+    },
+
+    /** A result or streamed item */
+    value<T>(value: T, apply: (value: T) => void) {
+      enqueue(async () => apply(await rehydrateValue(value)));
+    },
+
+    /** Resolves once everything queued so far is applied */
+    settled(): Promise<void> {
+      return queue;
+    },
+  };
+}
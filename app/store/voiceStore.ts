@@ -1,5 +1,15 @@
 import { create } from 'zustand';
 
+export interface TranscriptSegment {
+  start_ms: number;
+  end_ms: number;
+  text: string;
+  speaker?: string;
+  confidence?: number;
+  /** ISO 639-1 code of the spoken language */
+  language?: string;
+}
+
 export interface ActionItem {
   task: string;
   assignee: string | null;
@@ -86,6 +96,19 @@ export interface BrainDumpResult {
   summary: string;
 }
 
+/** A brain dump entry streamed before the full result (brain-dump-item) */
+export type BrainDumpItem =
+  | { kind: 'task'; item: BrainDumpTask }
+  | { kind: 'creative_idea'; item: BrainDumpIdea }
+  | { kind: 'note'; item: BrainDumpNote }
+  | { kind: 'summary'; item: string };
+
+/** One dev log output streamed before the full result (dev-log-part) */
+export type DevLogPart =
+  | { kind: 'commit_message'; part: string }
+  | { kind: 'ticket'; part: DevLogResult['ticket'] }
+  | { kind: 'slack_update'; part: string };
+
 export interface MentalMirrorResult {
   reflection: string;
   mental_checkin: string;
@@ -95,6 +118,13 @@ export interface MentalMirrorResult {
   disclaimer: string;
 }
 
+/** A PII placeholder in a processed transcript and the value it stands for */
+export interface Redaction {
+  placeholder: string;
+  original: string;
+  kind: 'email' | 'phone' | 'iban' | 'name';
+}
+
 export type AgentType = 'action-items' | 'tone-shifter' | 'music-matcher' | 'translator' | 'dev-log' | 'brain-dump' | 'mental-mirror' | null;
 export type RecordingState = 'idle' | 'recording' | 'processing';
 export type SyncStatus = 'disconnected' | 'waiting_for_peer' | 'connecting' | 'connected';
@@ -121,6 +151,10 @@ interface VoiceState {
   setInterimTranscript: (text: string) => void;
   appendTranscript: (text: string, isFinal: boolean) => void;
   clearTranscript: () => void;
+  // Final transcript pieces with timing, for subtitle export
+  transcriptSegments: TranscriptSegment[];
+  addTranscriptSegment: (segment: TranscriptSegment) => void;
+  setTranscriptSegments: (segments: TranscriptSegment[]) => void;
 
   // VAD
   isSpeechDetected: boolean;
@@ -130,6 +164,9 @@ interface VoiceState {
   // Active agent
   activeAgent: AgentType;
   setActiveAgent: (agent: AgentType) => void;
+  // Placeholders in the transcript the agents got (process_transcript)
+  redactions: Redaction[];
+  setRedactions: (redactions: Redaction[]) => void;
 
   // Agent results
   actionItems: ActionItem[];
@@ -163,6 +200,7 @@ interface VoiceState {
   setDevLogResult: (result: DevLogResult | null) => void;
   appendDevLogStreaming: (text: string) => void;
   clearDevLogStreaming: () => void;
+  addDevLogPart: (part: DevLogPart) => void;
 
   // Brain Dump
   brainDumpResult: BrainDumpResult | null;
@@ -170,6 +208,7 @@ interface VoiceState {
   setBrainDumpResult: (result: BrainDumpResult | null) => void;
   appendBrainDumpStreaming: (text: string) => void;
   clearBrainDumpStreaming: () => void;
+  addBrainDumpItem: (item: BrainDumpItem) => void;
 
   // Mental Mirror (Letter to Myself)
   mentalMirrorResult: MentalMirrorResult | null;
@@ -177,6 +216,7 @@ interface VoiceState {
   setMentalMirrorResult: (result: MentalMirrorResult | null) => void;
   appendMentalMirrorStreaming: (text: string) => void;
   clearMentalMirrorStreaming: () => void;
+  addMentalMirrorSection: (section: string, text: string) => void;
 
   // Processing state
   isProcessing: boolean;
@@ -217,9 +257,11 @@ const initialState = {
   recordingDuration: 0,
   transcript: '',
   interimTranscript: '',
+  transcriptSegments: [] as TranscriptSegment[],
   isSpeechDetected: false,
   audioEnergy: 0,
   activeAgent: null as AgentType,
+  redactions: [] as Redaction[],
   actionItems: [],
   toneShiftResult: null,
   toneShiftStreaming: '',
@@ -272,13 +314,20 @@ export const useVoiceStore = create<VoiceState>((set, get) => ({
     }
   },
 
-  clearTranscript: () => set({ transcript: '', interimTranscript: '' }),
+  clearTranscript: () => set({ transcript: '', interimTranscript: '', transcriptSegments: [] }),
+
+  addTranscriptSegment: (segment) =>
+    set((state) => ({ transcriptSegments: [...state.transcriptSegments, segment] })),
+
+  setTranscriptSegments: (segments) => set({ transcriptSegments: segments }),
 
   setVadState: (isSpeech, energy) =>
     set({ isSpeechDetected: isSpeech, audioEnergy: energy }),
 
   setActiveAgent: (agent) => set({ activeAgent: agent }),
 
+  setRedactions: (redactions) => set({ redactions }),
+
   setActionItems: (items) => set({ actionItems: items }),
 
   setToneShiftResult: (result) => set({ toneShiftResult: result }),
@@ -310,6 +359,16 @@ export const useVoiceStore = create<VoiceState>((set, get) => ({
 
   clearDevLogStreaming: () => set({ devLogStreaming: '' }),
 
+  addDevLogPart: ({ kind, part }) =>
+    set((state) => {
+      const result = state.devLogResult ?? {
+        commit_message: '',
+        ticket: { title: '', description: '', acceptance_criteria: [] },
+        slack_update: '',
+      };
+      return { devLogResult: { ...result, [kind]: part } };
+    }),
+
   setBrainDumpResult: (result) => set({ brainDumpResult: result }),
 
   appendBrainDumpStreaming: (text) =>
@@ -317,6 +376,21 @@ export const useVoiceStore = create<VoiceState>((set, get) => ({
 
   clearBrainDumpStreaming: () => set({ brainDumpStreaming: '' }),
 
+  addBrainDumpItem: (entry) =>
+    set((state) => {
+      const result = state.brainDumpResult ?? { tasks: [], creative_ideas: [], notes: [], summary: '' };
+      switch (entry.kind) {
+        case 'task':
+          return { brainDumpResult: { ...result, tasks: [...result.tasks, entry.item] } };
+        case 'creative_idea':
+          return { brainDumpResult: { ...result, creative_ideas: [...result.creative_ideas, entry.item] } };
+        case 'note':
+          return { brainDumpResult: { ...result, notes: [...result.notes, entry.item] } };
+        case 'summary':
+          return { brainDumpResult: { ...result, summary: entry.item } };
+      }
+    }),
+
   setMentalMirrorResult: (result) => set({ mentalMirrorResult: result }),
 
   appendMentalMirrorStreaming: (text) =>
@@ -324,6 +398,19 @@ export const useVoiceStore = create<VoiceState>((set, get) => ({
 
   clearMentalMirrorStreaming: () => set({ mentalMirrorStreaming: '' }),
 
+  addMentalMirrorSection: (section, text) =>
+    set((state) => {
+      const result = state.mentalMirrorResult ?? {
+        reflection: '',
+        mental_checkin: '',
+        the_release: '',
+        message_to_tomorrow: '',
+        date: '',
+        disclaimer: '',
+      };
+      return { mentalMirrorResult: { ...result, [section]: text } };
+    }),
+
   setProcessing: (isProcessing, message = '') =>
     set({ isProcessing, processingMessage: message }),
 
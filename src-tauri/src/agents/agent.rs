@@ -0,0 +1,373 @@
+//! The `Agent` trait and the runner shared by every agent.
+//!
+//! An agent only describes its input and output types, how to prompt the
+//! model and how to turn the answer into its result. Running it — schema
+//! enforcement and repair, streaming, progress events — is the same for
+//! all agents and lives here.
+//!
+//! Streaming runs report progress as `{event_prefix}-started`,
+//! `{event_prefix}-chunk` (with a final `is_complete` chunk), any
+//! agent-specific events and `{event_prefix}-complete` with the result.
+
+use futures_util::future::BoxFuture;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::llm::json_stream::{CompletedValue, JsonStream};
+use crate::llm::{self, ChatRequest, LlmProvider, ModelConfig, OutputSchema};
+
+/// Input of the agents that work on a whole transcript
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TranscriptInput {
+    pub transcript: String,
+}
+
+/// Payload of the `*-chunk` events
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentChunk {
+    pub text: String,
+    pub is_complete: bool,
+}
+
+/// One piece of a prompt
+#[derive(Debug, Clone)]
+pub enum PromptPart {
+    /// Sent to the model; its answer goes into the result
+    Ask(ChatRequest),
+    /// Copied into the answer as-is
+    Verbatim(String),
+}
+
+/// What the model answered, before post-processing
+#[derive(Debug)]
+pub enum Answer<T> {
+    /// Answers of all parts, joined with spaces
+    Text(String),
+    /// The answer parsed and validated against the output schema
+    Structured(T),
+}
+
+pub trait Agent: Send + Sync + 'static {
+    type Input: DeserializeOwned + JsonSchema + Send;
+    type Output: Serialize + DeserializeOwned + JsonSchema + Send;
+
+    /// Stable id, also the key of the agent's model config
+    fn id(&self) -> &str;
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    /// Prefix of the events of streaming runs
+    fn event_prefix(&self) -> &str;
+
+    fn input_schema(&self) -> Value {
+        serde_json::to_value(schemars::schema_for!(Self::Input)).unwrap_or(Value::Null)
+    }
+
+    /// Schema the model's answer must match; `None` for agents that
+    /// answer in plain text
+    fn output_schema(&self) -> Option<OutputSchema> {
+        Some(OutputSchema::of::<Self::Output>())
+    }
+
+    /// The prompt for `input`. Agents with an output schema ask exactly
+    /// one question.
+    fn prompt(&self, config: &ModelConfig, input: &Self::Input) -> Result<Vec<PromptPart>, String>;
+
+    /// Turn the answer into the result
+    fn post_process(&self, _input: Self::Input, answer: Answer<Self::Output>) -> Result<Self::Output, String> {
+        match answer {
+            Answer::Structured(output) => Ok(output),
+            Answer::Text(_) => Err(format!("{} did not answer with structured output", self.name())),
+        }
+    }
+
+    /// Values of a streamed structured answer up to this depth are passed
+    /// to `streamed_event`; 0 turns that off
+    fn stream_depth(&self) -> usize {
+        0
+    }
+
+    /// Event name and payload for a value that finished streaming, such as
+    /// a single task, to show it before the whole answer is in
+    fn streamed_event(&self, _value: CompletedValue) -> Option<(&'static str, Value)> {
+        None
+    }
+}
+
+/// Where a streaming run reports its progress
+pub trait RunEvents: Send + Sync {
+    /// `name` is relative to the agent's event prefix, e.g. `chunk`
+    fn emit(&self, name: &str, payload: Value);
+}
+
+/// What clients need to know to present and run an agent
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub event_prefix: String,
+    pub input_schema: Value,
+    /// `None` for agents that answer in plain text
+    pub output_schema: Option<Value>,
+}
+
+/// An agent with its input and output types erased, so agents of all
+/// types can live in one registry
+pub trait AnyAgent: Send + Sync {
+    fn info(&self) -> AgentInfo;
+
+    fn id(&self) -> &str;
+
+    fn event_prefix(&self) -> &str;
+
+    /// Run on JSON input, reporting progress to `events` when streaming
+    fn run<'a>(
+        &'a self,
+        config: &'a ModelConfig,
+        provider: &'a dyn LlmProvider,
+        input: Value,
+        events: Option<&'a dyn RunEvents>,
+    ) -> BoxFuture<'a, Result<Value, String>>;
+}
+
+impl<A: Agent> AnyAgent for A {
+    fn info(&self) -> AgentInfo {
+        AgentInfo {
+            id: Agent::id(self).to_string(),
+            name: self.name().to_string(),
+            description: self.description().to_string(),
+            event_prefix: Agent::event_prefix(self).to_string(),
+            input_schema: self.input_schema(),
+            output_schema: self.output_schema().map(|schema| schema.schema),
+        }
+    }
+
+    fn id(&self) -> &str {
+        Agent::id(self)
+    }
+
+    fn event_prefix(&self) -> &str {
+        Agent::event_prefix(self)
+    }
+
+    fn run<'a>(
+        &'a self,
+        config: &'a ModelConfig,
+        provider: &'a dyn LlmProvider,
+        input: Value,
+        events: Option<&'a dyn RunEvents>,
+    ) -> BoxFuture<'a, Result<Value, String>> {
+        Box::pin(async move {
+            let input: A::Input = serde_json::from_value(input)
+                .map_err(|e| format!("Invalid input for {}: {}", self.name(), e))?;
+            let output = execute(self, config, provider, input, events).await?;
+            serde_json::to_value(output).map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Run `agent` on `input`; with `events` the answer is streamed
+pub async fn execute<A: Agent>(
+    agent: &A,
+    config: &ModelConfig,
+    provider: &dyn LlmProvider,
+    input: A::Input,
+    events: Option<&dyn RunEvents>,
+) -> Result<A::Output, String> {
+    let parts = agent.prompt(config, &input)?;
+    let schema = agent.output_schema();
+
+    if let Some(events) = events {
+        events.emit("started", Value::Null);
+    }
+
+    let answer = match schema {
+        Some(schema) => {
+            let request = match parts.as_slice() {
+                [PromptPart::Ask(request)] => request.clone().with_schema(schema),
+                _ => return Err(format!("{} must ask exactly one question for structured output", agent.name())),
+            };
+            let answer = ask(agent, provider, &request, events).await?;
+            Answer::Structured(llm::finish_structured(provider, request, answer).await?)
+        }
+        None => {
+            let mut text = String::new();
+            for part in parts {
+                if !text.is_empty() {
+                    text.push(' ');
+                    emit_chunk(events, " ");
+                }
+                match part {
+                    PromptPart::Verbatim(verbatim) => {
+                        emit_chunk(events, &verbatim);
+                        text.push_str(&verbatim);
+                    }
+                    PromptPart::Ask(request) => text.push_str(&ask(agent, provider, &request, events).await?),
+                }
+            }
+            Answer::Text(text)
+        }
+    };
+
+    if let Some(events) = events {
+        events.emit("chunk", chunk("", true));
+    }
+
+    let output = agent.post_process(input, answer)?;
+
+    if let Some(events) = events {
+        events.emit("complete", serde_json::to_value(&output).map_err(|e| e.to_string())?);
+    }
+
+    Ok(output)
+}
+
+/// Send one request, streaming the answer when there are `events`
+async fn ask<A: Agent>(
+    agent: &A,
+    provider: &dyn LlmProvider,
+    request: &ChatRequest,
+    events: Option<&dyn RunEvents>,
+) -> Result<String, String> {
+    let Some(events) = events else {
+        return Ok(provider.chat(request).await?);
+    };
+
+    let depth = if request.schema.is_some() { agent.stream_depth() } else { 0 };
+    let mut values = JsonStream::new(depth);
+    let mut on_delta = |content: &str| {
+        events.emit("chunk", chunk(content, false));
+        if depth > 0 {
+            for (name, payload) in values.push(content).into_iter().filter_map(|value| agent.streamed_event(value)) {
+                events.emit(name, payload);
+            }
+        }
+    };
+    Ok(provider.chat_stream(request, &mut on_delta).await?)
+}
+
+fn chunk(text: &str, is_complete: bool) -> Value {
+    serde_json::to_value(AgentChunk {
+        text: text.to_string(),
+        is_complete,
+    })
+    .unwrap_or(Value::Null)
+}
+
+fn emit_chunk(events: Option<&dyn RunEvents>, text: &str) {
+    if let Some(events) = events {
+        events.emit("chunk", chunk(text, false));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LlmError, ProviderKind};
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+    struct Tasks {
+        tasks: Vec<String>,
+    }
+
+    struct TaskAgent;
+
+    impl Agent for TaskAgent {
+        type Input = TranscriptInput;
+        type Output = Tasks;
+
+        fn id(&self) -> &str {
+            "tasks"
+        }
+
+        fn name(&self) -> &str {
+            "Tasks"
+        }
+
+        fn description(&self) -> &str {
+            "Lists tasks"
+        }
+
+        fn event_prefix(&self) -> &str {
+            "tasks"
+        }
+
+        fn prompt(&self, config: &ModelConfig, input: &TranscriptInput) -> Result<Vec<PromptPart>, String> {
+            Ok(vec![PromptPart::Ask(config.request().with_user(&input.transcript))])
+        }
+
+        fn stream_depth(&self) -> usize {
+            2
+        }
+
+        fn streamed_event(&self, value: CompletedValue) -> Option<(&'static str, Value)> {
+            value.is_element_of("tasks").then_some(("task", value.value))
+        }
+    }
+
+    /// Echoes the last user message, streamed in two pieces
+    struct EchoProvider;
+
+    impl LlmProvider for EchoProvider {
+        fn kind(&self) -> ProviderKind {
+            ProviderKind::OpenAi
+        }
+
+        fn chat<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String, LlmError>> {
+            let answer = request.messages.last().map(|m| m.content.clone()).unwrap_or_default();
+            Box::pin(async move { Ok(answer) })
+        }
+
+        fn chat_stream<'a>(
+            &'a self,
+            request: &'a ChatRequest,
+            on_delta: &'a mut (dyn FnMut(&str) + Send),
+        ) -> BoxFuture<'a, Result<String, LlmError>> {
+            Box::pin(async move {
+                let answer = self.chat(request).await?;
+                let (first, second) = answer.split_at(answer.len() / 2);
+                on_delta(first);
+                on_delta(second);
+                Ok(answer)
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, Value)>>);
+
+    impl RunEvents for Recorder {
+        fn emit(&self, name: &str, payload: Value) {
+            self.0.lock().unwrap().push((name.to_string(), payload));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_run_reports_progress() {
+        let config = ModelConfig::openai("gpt-4o");
+        let recorder = Recorder::default();
+        let input = json!({ "transcript": r#"{"tasks": ["Call Bob", "Ship"]}"# });
+
+        let output = TaskAgent.run(&config, &EchoProvider, input, Some(&recorder)).await.unwrap();
+        assert_eq!(output, json!({ "tasks": ["Call Bob", "Ship"] }));
+
+        let events = recorder.0.into_inner().unwrap();
+        let names: Vec<&str> = events.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names.first(), Some(&"started"));
+        assert_eq!(names.last(), Some(&"complete"));
+        assert_eq!(events.iter().filter(|(name, _)| name == "chunk").count(), 3);
+        let tasks: Vec<&Value> = events.iter().filter(|(name, _)| name == "task").map(|(_, task)| task).collect();
+        assert_eq!(tasks, [&json!("Call Bob"), &json!("Ship")]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_input() {
+        let config = ModelConfig::openai("gpt-4o");
+        let error = TaskAgent.run(&config, &EchoProvider, json!({ "text": "hi" }), None).await.unwrap_err();
+        assert!(error.starts_with("Invalid input for Tasks"), "{}", error);
+    }
+}
@@ -1,10 +1,11 @@
-use futures_util::StreamExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
 
-const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
+use super::agent::{Agent, PromptPart, TranscriptInput};
+use crate::llm::json_stream::CompletedValue;
+use crate::llm::ModelConfig;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BrainDumpResult {
     pub tasks: Vec<Task>,
     pub creative_ideas: Vec<CreativeIdea>,
@@ -12,7 +13,7 @@ pub struct BrainDumpResult {
     pub summary: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Task {
     pub title: String,
     pub description: String,
@@ -20,7 +21,7 @@ pub struct Task {
     pub due_hint: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum EisenhowerQuadrant {
     UrgentImportant,      // Do First
@@ -49,7 +50,7 @@ impl EisenhowerQuadrant {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CreativeIdea {
     pub title: String,
     pub description: String,
@@ -57,33 +58,38 @@ pub struct CreativeIdea {
     pub potential: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Note {
     pub content: String,
     pub tags: Vec<String>,
 }
 
+/// Payload of the `brain-dump-item` event, sent as soon as the model has
+/// finished writing an entry
 #[derive(Debug, Clone, Serialize)]
-pub struct BrainDumpChunk {
-    pub text: String,
-    pub is_complete: bool,
+#[serde(tag = "kind", content = "item", rename_all = "snake_case")]
+pub enum BrainDumpItem {
+    Task(Task),
+    CreativeIdea(CreativeIdea),
+    Note(Note),
+    Summary(String),
 }
 
-#[derive(Debug, Deserialize)]
-struct OpenAiStreamChunk {
-    choices: Vec<OpenAiStreamChoice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAiStreamChoice {
-    delta: OpenAiDelta,
-    #[allow(dead_code)]
-    finish_reason: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAiDelta {
-    content: Option<String>,
+impl BrainDumpItem {
+    fn from_streamed(completed: CompletedValue) -> Option<Self> {
+        let item = if completed.is_element_of("tasks") {
+            Self::Task(serde_json::from_value(completed.value).ok()?)
+        } else if completed.is_element_of("creative_ideas") {
+            Self::CreativeIdea(serde_json::from_value(completed.value).ok()?)
+        } else if completed.is_element_of("notes") {
+            Self::Note(serde_json::from_value(completed.value).ok()?)
+        } else if completed.is_field("summary") {
+            Self::Summary(serde_json::from_value(completed.value).ok()?)
+        } else {
+            return None;
+        };
+        Some(item)
+    }
 }
 
 const SYSTEM_PROMPT: &str = r#"You are a cognitive organization expert specializing in processing unstructured thoughts and voice memos. Your task is to analyze a brain dump transcript and categorize its contents.
@@ -163,159 +169,73 @@ IMPORTANT:
 - Use your judgment to categorize ambiguous items
 - Keep the summary concise but informative"#;
 
-#[tauri::command]
-pub async fn process_brain_dump(
-    app: AppHandle,
-    api_key: String,
-    transcript: String,
-) -> Result<BrainDumpResult, String> {
-    if transcript.trim().is_empty() {
-        return Err("Transcript is empty. Please provide some content.".to_string());
-    }
-
-    let client = reqwest::Client::new();
-
-    let request_body = serde_json::json!({
-        "model": "gpt-4o-mini",
-        "messages": [
-            {
-                "role": "system",
-                "content": SYSTEM_PROMPT
-            },
-            {
-                "role": "user",
-                "content": format!("Process this brain dump and categorize the contents:\n\n{}", transcript)
-            }
-        ],
-        "response_format": { "type": "json_object" },
-        "temperature": 0.3
-    });
-
-    let response = client
-        .post(OPENAI_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
-    }
-
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    let content = response_json["choices"][0]["message"]["content"]
-        .as_str()
-        .ok_or("No content in response")?;
+pub struct BrainDump;
 
-    let result: BrainDumpResult = serde_json::from_str(content)
-        .map_err(|e| format!("Failed to parse brain dump result: {}", e))?;
+impl Agent for BrainDump {
+    type Input = TranscriptInput;
+    type Output = BrainDumpResult;
 
-    let _ = app.emit("brain-dump-processed", &result);
+    fn id(&self) -> &str {
+        "brain-dump"
+    }
 
-    Ok(result)
-}
+    fn name(&self) -> &str {
+        "Brain Dump"
+    }
 
-#[tauri::command]
-pub async fn process_brain_dump_streaming(
-    app: AppHandle,
-    api_key: String,
-    transcript: String,
-) -> Result<(), String> {
-    if transcript.trim().is_empty() {
-        return Err("Transcript is empty. Please provide some content.".to_string());
+    fn description(&self) -> &str {
+        "Sort thoughts into tasks, ideas and notes"
     }
 
-    let _ = app.emit("brain-dump-started", ());
-
-    let client = reqwest::Client::new();
-
-    let request_body = serde_json::json!({
-        "model": "gpt-4o-mini",
-        "messages": [
-            {
-                "role": "system",
-                "content": SYSTEM_PROMPT
-            },
-            {
-                "role": "user",
-                "content": format!("Process this brain dump and categorize the contents:\n\n{}", transcript)
-            }
-        ],
-        "stream": true,
-        "temperature": 0.3
-    });
-
-    let response = client
-        .post(OPENAI_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
+    fn event_prefix(&self) -> &str {
+        "brain-dump"
     }
 
-    let mut stream = response.bytes_stream();
-    let mut full_text = String::new();
-    let mut buffer = String::new();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
-        let chunk_str = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&chunk_str);
-
-        while let Some(event_end) = buffer.find("\n\n") {
-            let event_str = buffer[..event_end].to_string();
-            buffer = buffer[event_end + 2..].to_string();
-
-            for line in event_str.lines() {
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
-
-                    if let Ok(chunk) = serde_json::from_str::<OpenAiStreamChunk>(data) {
-                        if let Some(choice) = chunk.choices.first() {
-                            if let Some(content) = &choice.delta.content {
-                                full_text.push_str(content);
-                                let _ = app.emit(
-                                    "brain-dump-chunk",
-                                    BrainDumpChunk {
-                                        text: content.clone(),
-                                        is_complete: false,
-                                    },
-                                );
-                            }
-                        }
-                    }
-                }
-            }
+    fn prompt(&self, config: &ModelConfig, input: &TranscriptInput) -> Result<Vec<PromptPart>, String> {
+        if input.transcript.trim().is_empty() {
+            return Err("Transcript is empty. Please provide some content.".to_string());
         }
+        Ok(vec![PromptPart::Ask(
+            config
+                .request()
+                .with_system(SYSTEM_PROMPT)
+                .with_user(format!("Process this brain dump and categorize the contents:\n\n{}", input.transcript)),
+        )])
     }
 
-    let _ = app.emit(
-        "brain-dump-chunk",
-        BrainDumpChunk {
-            text: String::new(),
-            is_complete: true,
-        },
-    );
+    fn stream_depth(&self) -> usize {
+        2
+    }
 
-    let result: BrainDumpResult = serde_json::from_str(&full_text)
-        .map_err(|e| format!("Failed to parse brain dump result: {}", e))?;
+    fn streamed_event(&self, value: CompletedValue) -> Option<(&'static str, serde_json::Value)> {
+        let item = BrainDumpItem::from_streamed(value)?;
+        Some(("item", serde_json::to_value(item).ok()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::json_stream::JsonStream;
+
+    #[test]
+    fn test_streamed_items() {
+        let mut stream = JsonStream::new(2);
+        let mut items = Vec::new();
+        for piece in [
+            r#"{"tasks": [{"title": "Book flights", "description": "For the offsite", "#,
+            r#""quadrant": "urgent_important", "due_hint": "by Friday"}], "creative_ideas": [], "#,
+            r#""notes": [{"content": "Ask Sam", "tags": ["team"]}], "summary": "Travel prep"}"#,
+        ] {
+            items.extend(stream.push(piece).into_iter().filter_map(BrainDumpItem::from_streamed));
+        }
 
-    let _ = app.emit("brain-dump-complete", &result);
+        assert_eq!(items.len(), 3);
+        assert!(matches!(&items[0], BrainDumpItem::Task(task) if task.title == "Book flights"));
+        assert!(matches!(&items[1], BrainDumpItem::Note(note) if note.tags == ["team"]));
+        assert!(matches!(&items[2], BrainDumpItem::Summary(summary) if summary == "Travel prep"));
 
-    Ok(())
+        let json = serde_json::to_value(&items[2]).unwrap();
+        assert_eq!(json, serde_json::json!({"kind": "summary", "item": "Travel prep"}));
+    }
 }
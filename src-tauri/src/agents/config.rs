@@ -0,0 +1,309 @@
+//! Per-agent model settings.
+//!
+//! Every agent has defaults, built in or from its custom agent definition;
+//! users can override the provider, model, sampling, timeout and a fallback
+//! provider per agent. Overrides live in `agent-config.json` in the app data
+//! directory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use super::custom;
+use crate::llm::local::LocalLlmConfig;
+use crate::llm::{self, FallbackProvider, LlmProvider, ModelConfig, ProviderKind};
+use crate::platform::secrets::stored_key;
+use crate::storage;
+
+const CONFIG_FILE: &str = "agent-config.json";
+
+/// Agents without a configured timeout give up after this long
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+const MAX_TIMEOUT_SECS: u64 = 600;
+
+/// Provider and model to retry on when the primary is rate limited or down
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FallbackModel {
+    pub provider: ProviderKind,
+    pub model: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AgentConfig {
+    pub provider: ProviderKind,
+    pub model: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub fallback: Option<FallbackModel>,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+impl AgentConfig {
+    fn new(provider: ProviderKind, model: &str, temperature: Option<f32>, max_tokens: Option<u32>) -> Self {
+        Self {
+            provider,
+            model: model.to_string(),
+            temperature,
+            max_tokens,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            fallback: None,
+        }
+    }
+
+    /// Built-in settings for `agent_id`, `None` for unknown agents
+    pub fn defaults(agent_id: &str) -> Option<Self> {
+        let config = match agent_id {
+            "action-items" => Self::new(ProviderKind::OpenAi, "gpt-4o", Some(0.3), None),
+            "brain-dump" => Self::new(ProviderKind::OpenAi, "gpt-4o-mini", Some(0.3), None),
+            "dev-log" => Self::new(ProviderKind::OpenAi, "gpt-4o", Some(0.3), None),
+            "mental-mirror" => Self::new(ProviderKind::OpenAi, "gpt-4o", Some(0.7), None),
+            "music-matcher" => Self::new(ProviderKind::OpenAi, "gpt-4o-mini", None, None),
+            "tone-shifter" => Self::new(ProviderKind::Anthropic, "claude-sonnet-4-20250514", None, Some(4096)),
+            "translator" => Self::new(ProviderKind::OpenAi, "gpt-4o", Some(0.3), None),
+            _ => return None,
+        };
+        Some(config)
+    }
+
+    /// Built-in settings of `agent_id`, or the settings a custom agent's
+    /// definition declares
+    fn defaults_for(app: &AppHandle, agent_id: &str) -> Option<Self> {
+        Self::defaults(agent_id).or_else(|| custom::find(app, agent_id).map(|agent| agent.definition.model))
+    }
+
+    /// The stored settings for `agent_id`, or its defaults
+    pub fn load(app: &AppHandle, agent_id: &str) -> Result<Self, String> {
+        let defaults = Self::defaults_for(app, agent_id).ok_or_else(|| format!("Unknown agent '{}'", agent_id))?;
+        let stored = AgentConfigFile::load(app).agents.remove(agent_id);
+        match stored {
+            Some(config) => match config.validate() {
+                Ok(()) => Ok(config),
+                Err(e) => {
+                    tracing::warn!("Ignoring stored config for agent '{}': {}", agent_id, e);
+                    Ok(defaults)
+                }
+            },
+            None => Ok(defaults),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.trim().is_empty() {
+            return Err("Model must not be empty".to_string());
+        }
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err("Temperature must be between 0 and 2".to_string());
+            }
+        }
+        if self.max_tokens == Some(0) {
+            return Err("Max tokens must be greater than 0".to_string());
+        }
+        if !(1..=MAX_TIMEOUT_SECS).contains(&self.timeout_secs) {
+            return Err(format!("Timeout must be between 1 and {} seconds", MAX_TIMEOUT_SECS));
+        }
+        if let Some(fallback) = &self.fallback {
+            if fallback.model.trim().is_empty() {
+                return Err("Fallback model must not be empty".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn model_config(&self) -> ModelConfig {
+        ModelConfig {
+            provider: self.provider,
+            model: self.model.clone(),
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            timeout_secs: Some(self.timeout_secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct AgentConfigFile {
+    agents: HashMap<String, AgentConfig>,
+}
+
+impl AgentConfigFile {
+    fn load(app: &AppHandle) -> Self {
+        storage::load_json(app, CONFIG_FILE)
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, CONFIG_FILE, self)
+    }
+}
+
+/// API key for `kind`: the caller's key if it belongs to this provider,
+/// otherwise the stored one. Local servers may run without a key.
+fn api_key_for(
+    kind: ProviderKind,
+    caller: Option<(ProviderKind, &str)>,
+    stored: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    match (kind, caller) {
+        (kind, Some((caller_kind, key))) if caller_kind == kind && !key.is_empty() => Some(key.to_string()),
+        (ProviderKind::Local, _) => Some(stored(kind.key_type()).unwrap_or_default()),
+        (kind, _) => stored(kind.key_type()),
+    }
+}
+
+fn client(kind: ProviderKind, api_key: &str, local: &LocalLlmConfig) -> Box<dyn LlmProvider> {
+    match kind {
+        ProviderKind::Local => Box::new(local.provider(api_key)),
+        kind => llm::provider(kind, api_key),
+    }
+}
+
+/// The local server's model when the agent runs on it but still has the
+/// cloud model of its defaults, i.e. only the provider was changed
+fn local_model<'a>(config: &'a AgentConfig, defaults: Option<&AgentConfig>, local: &'a LocalLlmConfig) -> &'a str {
+    match defaults {
+        Some(defaults)
+            if config.provider == ProviderKind::Local
+                && defaults.provider != ProviderKind::Local
+                && config.model == defaults.model =>
+        {
+            &local.model
+        }
+        _ => &config.model,
+    }
+}
+
+/// The model and client `agent_id` runs on. `api_key` is the key the
+/// frontend passed for the agent's default provider and may be empty.
+pub fn resolve(app: &AppHandle, agent_id: &str, api_key: &str) -> Result<(ModelConfig, Box<dyn LlmProvider>), String> {
+    let config = AgentConfig::load(app, agent_id)?;
+    let defaults = AgentConfig::defaults_for(app, agent_id);
+    let caller = defaults.as_ref().map(|defaults| (defaults.provider, api_key));
+    let local = LocalLlmConfig::load(app);
+
+    let key = api_key_for(config.provider, caller, stored_key)
+        .ok_or_else(|| format!("{} API key required for {}", config.provider.label(), agent_id))?;
+    let primary = client(config.provider, &key, &local);
+
+    let provider = match &config.fallback {
+        Some(fallback) => match api_key_for(fallback.provider, caller, stored_key) {
+            Some(fallback_key) => {
+                let secondary = client(fallback.provider, &fallback_key, &local);
+                Box::new(FallbackProvider::new(primary, secondary, &fallback.model)) as Box<dyn LlmProvider>
+            }
+            None => {
+                tracing::warn!(
+                    "No {} API key stored, agent '{}' runs without fallback",
+                    fallback.provider.label(),
+                    agent_id
+                );
+                primary
+            }
+        },
+        None => primary,
+    };
+
+    let model_config = ModelConfig {
+        model: local_model(&config, defaults.as_ref(), &local).to_string(),
+        ..config.model_config()
+    };
+    Ok((model_config, provider))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_agent_config(app: AppHandle, agent_id: String) -> Result<AgentConfig, String> {
+    AgentConfig::load(&app, &agent_id)
+}
+
+#[tauri::command]
+pub async fn set_agent_config(app: AppHandle, agent_id: String, config: AgentConfig) -> Result<(), String> {
+    if AgentConfig::defaults_for(&app, &agent_id).is_none() {
+        return Err(format!("Unknown agent '{}'", agent_id));
+    }
+    config.validate()?;
+
+    let mut file = AgentConfigFile::load(&app);
+    file.agents.insert(agent_id.clone(), config.clone());
+    file.save(&app)?;
+
+    tracing::info!(
+        "Agent '{}' configured: {} ({}), fallback {:?}",
+        agent_id,
+        config.provider.label(),
+        config.model,
+        config.fallback.as_ref().map(|f| &f.model)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let tone = AgentConfig::defaults("tone-shifter").unwrap();
+        assert_eq!(tone.provider, ProviderKind::Anthropic);
+        assert_eq!(tone.max_tokens, Some(4096));
+        assert!(AgentConfig::defaults("brain-dump").unwrap().validate().is_ok());
+        assert!(AgentConfig::defaults("unknown").is_none());
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = AgentConfig::defaults("dev-log").unwrap();
+        assert!(AgentConfig { model: " ".to_string(), ..config.clone() }.validate().is_err());
+        assert!(AgentConfig { temperature: Some(2.5), ..config.clone() }.validate().is_err());
+        assert!(AgentConfig { max_tokens: Some(0), ..config.clone() }.validate().is_err());
+        assert!(AgentConfig { timeout_secs: 0, ..config.clone() }.validate().is_err());
+        assert!(AgentConfig { timeout_secs: 601, ..config.clone() }.validate().is_err());
+        let fallback = Some(FallbackModel {
+            provider: ProviderKind::Anthropic,
+            model: String::new(),
+        });
+        assert!(AgentConfig { fallback, ..config }.validate().is_err());
+    }
+
+    #[test]
+    fn test_deserialize_without_optional_fields() {
+        let config: AgentConfig = serde_json::from_str(
+            r#"{"provider":"local","model":"llama3.2","temperature":null,"max_tokens":null}"#,
+        )
+        .unwrap();
+        assert_eq!(config.timeout_secs, DEFAULT_TIMEOUT_SECS);
+        assert_eq!(config.fallback, None);
+        assert_eq!(config.model_config().timeout_secs, Some(DEFAULT_TIMEOUT_SECS));
+    }
+
+    #[test]
+    fn test_caller_key_only_for_matching_provider() {
+        let stored = |key_type: &str| (key_type == "local_llm").then(|| "local-key".to_string());
+        let caller = Some((ProviderKind::OpenAi, "sk-caller"));
+        assert_eq!(api_key_for(ProviderKind::OpenAi, caller, stored).as_deref(), Some("sk-caller"));
+        assert_eq!(api_key_for(ProviderKind::Local, caller, stored).as_deref(), Some("local-key"));
+        assert_eq!(api_key_for(ProviderKind::Anthropic, caller, stored), None);
+        assert_eq!(api_key_for(ProviderKind::Local, None, |_| None).as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_local_model() {
+        let local = LocalLlmConfig::default();
+        let defaults = AgentConfig::defaults("dev-log").unwrap();
+        let provider_only = AgentConfig { provider: ProviderKind::Local, ..defaults.clone() };
+        assert_eq!(local_model(&provider_only, Some(&defaults), &local), local.model);
+
+        let explicit = AgentConfig { model: "qwen2.5".to_string(), ..provider_only };
+        assert_eq!(local_model(&explicit, Some(&defaults), &local), "qwen2.5");
+        assert_eq!(local_model(&defaults, Some(&defaults), &local), "gpt-4o");
+    }
+}
@@ -0,0 +1,408 @@
+//! User-defined agents built from prompt templates.
+//!
+//! Every `*.toml` or `*.json` file in the `agents` folder of the app data
+//! directory defines one agent: its prompts, how it answers and the model it
+//! runs on. Files are read on every call, so edits take effect without a
+//! restart. Custom agents run through `run_agent` like the built-in ones and
+//! stream their events under their id.
+//!
+//! ```toml
+//! id = "incident-postmortem"
+//! name = "Incident postmortem"
+//! description = "Blameless postmortem from a debrief call"
+//! provider = "openai"
+//! model = "gpt-4o"
+//! temperature = 0.2
+//! system_prompt = "You write blameless incident postmortems. Answer in {{language}}."
+//! user_template = "Debrief from {{date}}:\n\n{{transcript}}"
+//!
+//! [output]
+//! mode = "json"
+//! schema = { type = "object", properties = { summary = { type = "string" } } }
+//! ```
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::agent::{Agent, AgentInfo, AnyAgent, Answer, PromptPart};
+use super::config::AgentConfig;
+use super::registry;
+use crate::llm::{ModelConfig, OutputSchema};
+use crate::storage;
+
+/// Folder in the app data dir holding the definitions
+const AGENTS_DIR: &str = "agents";
+
+/// Variables the prompt templates may use
+const VARIABLES: &[&str] = &["transcript", "date", "language"];
+
+/// `{{language}}` when the caller doesn't pass one
+const DEFAULT_LANGUAGE: &str = "the language of the transcript";
+
+static VARIABLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*(\w+)\s*\}\}").unwrap());
+static AGENT_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").unwrap());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum OutputMode {
+    /// Plain text; the result is a JSON string
+    #[default]
+    Text,
+    /// JSON matching `schema`, which must describe an object
+    Json { schema: Value },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomAgentDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub system_prompt: String,
+    #[serde(default = "default_user_template")]
+    pub user_template: String,
+    #[serde(default)]
+    pub output: OutputMode,
+    /// Provider, model, sampling, timeout and fallback
+    #[serde(flatten)]
+    pub model: AgentConfig,
+}
+
+fn default_user_template() -> String {
+    "{{transcript}}".to_string()
+}
+
+impl CustomAgentDefinition {
+    /// Parse a definition file; the format follows the extension
+    pub fn parse(file_name: &str, contents: &str) -> Result<Self, String> {
+        let definition: Self = match Path::new(file_name).extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(contents).map_err(|e| e.to_string())?,
+            Some("json") => serde_json::from_str(contents).map_err(|e| e.to_string())?,
+            _ => return Err("Agent definitions must be .toml or .json files".to_string()),
+        };
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !AGENT_ID.is_match(&self.id) {
+            return Err(format!(
+                "Agent id '{}' must be lowercase letters, digits and dashes, like 'call-summary'",
+                self.id
+            ));
+        }
+        if registry::is_reserved(&self.id) {
+            return Err(format!("Agent id '{}' is reserved for a built-in agent or app event", self.id));
+        }
+        if self.name.trim().is_empty() {
+            return Err("Name must not be empty".to_string());
+        }
+        if self.system_prompt.trim().is_empty() {
+            return Err("System prompt must not be empty".to_string());
+        }
+
+        let mut used = Vec::new();
+        for template in [&self.system_prompt, &self.user_template] {
+            for captures in VARIABLE.captures_iter(template) {
+                let name = captures[1].to_string();
+                if !VARIABLES.contains(&name.as_str()) {
+                    return Err(format!(
+                        "Unknown template variable '{{{{{}}}}}', expected one of {}",
+                        name,
+                        VARIABLES.join(", ")
+                    ));
+                }
+                used.push(name);
+            }
+        }
+        if !used.iter().any(|name| name == "transcript") {
+            return Err("The prompts must include {{transcript}}".to_string());
+        }
+
+        if let OutputMode::Json { schema } = &self.output {
+            if schema.get("type").and_then(Value::as_str) != Some("object") {
+                return Err("The output schema must describe an object (\"type\": \"object\")".to_string());
+            }
+        }
+
+        self.model.validate()
+    }
+}
+
+/// Replace the `{{variables}}` in `template`
+fn render(template: &str, variables: &[(&str, &str)]) -> String {
+    VARIABLE
+        .replace_all(template, |captures: &regex::Captures| {
+            let name = &captures[1];
+            variables
+                .iter()
+                .find(|(variable, _)| *variable == name)
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct CustomAgentInput {
+    pub transcript: String,
+    /// Fills `{{language}}`; defaults to the transcript's language
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomAgent {
+    pub definition: CustomAgentDefinition,
+}
+
+impl Agent for CustomAgent {
+    type Input = CustomAgentInput;
+    type Output = Value;
+
+    fn id(&self) -> &str {
+        &self.definition.id
+    }
+
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn description(&self) -> &str {
+        &self.definition.description
+    }
+
+    fn event_prefix(&self) -> &str {
+        &self.definition.id
+    }
+
+    fn output_schema(&self) -> Option<OutputSchema> {
+        match &self.definition.output {
+            OutputMode::Text => None,
+            OutputMode::Json { schema } => Some(OutputSchema::new(&self.definition.id, schema.clone())),
+        }
+    }
+
+    fn prompt(&self, config: &ModelConfig, input: &CustomAgentInput) -> Result<Vec<PromptPart>, String> {
+        if input.transcript.trim().is_empty() {
+            return Err("Transcript is empty. Please provide some content.".to_string());
+        }
+
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let variables = [
+            ("transcript", input.transcript.as_str()),
+            ("date", date.as_str()),
+            ("language", input.language.as_deref().unwrap_or(DEFAULT_LANGUAGE)),
+        ];
+        Ok(vec![PromptPart::Ask(
+            config
+                .request()
+                .with_system(render(&self.definition.system_prompt, &variables))
+                .with_user(render(&self.definition.user_template, &variables)),
+        )])
+    }
+
+    fn post_process(&self, _input: CustomAgentInput, answer: Answer<Value>) -> Result<Value, String> {
+        Ok(match answer {
+            Answer::Text(text) => Value::String(text),
+            Answer::Structured(value) => value,
+        })
+    }
+}
+
+/// A definition file that could not be loaded
+#[derive(Debug, Clone, Serialize)]
+pub struct DefinitionError {
+    pub file: String,
+    pub error: String,
+}
+
+/// The valid agents in `dir`, sorted by file name, and the problems with
+/// the others
+fn load_dir(dir: &Path) -> (Vec<CustomAgent>, Vec<DefinitionError>) {
+    let mut agents: Vec<CustomAgent> = Vec::new();
+    let mut errors = Vec::new();
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (agents, errors);
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| matches!(path.extension().and_then(|ext| ext.to_str()), Some("toml" | "json")))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let file = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let definition = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| CustomAgentDefinition::parse(&file, &contents));
+        match definition {
+            Ok(definition) if agents.iter().any(|agent| agent.definition.id == definition.id) => {
+                errors.push(DefinitionError {
+                    file,
+                    error: format!("Another file already defines agent '{}'", definition.id),
+                });
+            }
+            Ok(definition) => agents.push(CustomAgent { definition }),
+            Err(error) => {
+                tracing::warn!("Skipping custom agent {}: {}", file, error);
+                errors.push(DefinitionError { file, error });
+            }
+        }
+    }
+
+    (agents, errors)
+}
+
+fn agents_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = storage::app_data_file(app, AGENTS_DIR)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// All valid custom agents
+pub fn load(app: &AppHandle) -> Vec<CustomAgent> {
+    match agents_dir(app) {
+        Ok(dir) => load_dir(&dir).0,
+        Err(e) => {
+            tracing::warn!("Custom agents unavailable: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn find(app: &AppHandle, agent_id: &str) -> Option<CustomAgent> {
+    load(app).into_iter().find(|agent| agent.definition.id == agent_id)
+}
+
+/// Where the definitions live, the agents loaded from there and the files
+/// that failed to load
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomAgents {
+    pub dir: String,
+    pub agents: Vec<AgentInfo>,
+    pub errors: Vec<DefinitionError>,
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_custom_agents(app: AppHandle) -> Result<CustomAgents, String> {
+    let dir = agents_dir(&app)?;
+    let (agents, errors) = load_dir(&dir);
+    Ok(CustomAgents {
+        dir: dir.display().to_string(),
+        agents: agents.iter().map(AnyAgent::info).collect(),
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::ProviderKind;
+
+    const POSTMORTEM: &str = r#"
+id = "incident-postmortem"
+name = "Incident postmortem"
+provider = "openai"
+model = "gpt-4o"
+temperature = 0.2
+system_prompt = "You write blameless postmortems. Answer in {{ language }}."
+user_template = "Debrief from {{date}}:\n\n{{transcript}}"
+
+[output]
+mode = "json"
+schema = { type = "object", properties = { summary = { type = "string" }, title = { type = "string" } } }
+"#;
+
+    fn definition() -> CustomAgentDefinition {
+        CustomAgentDefinition::parse("postmortem.toml", POSTMORTEM).unwrap()
+    }
+
+    #[test]
+    fn test_parse_toml_and_json() {
+        let definition = definition();
+        assert_eq!(definition.model.provider, ProviderKind::OpenAi);
+        assert_eq!(definition.model.temperature, Some(0.2));
+        assert!(matches!(&definition.output, OutputMode::Json { schema } if schema["properties"]["summary"]["type"] == "string"));
+
+        let json = r#"{"id": "call-summary", "name": "Call summary", "provider": "anthropic",
+            "model": "claude-sonnet-4-20250514", "system_prompt": "Summarize the call."}"#;
+        let definition = CustomAgentDefinition::parse("call.json", json).unwrap();
+        assert_eq!(definition.user_template, "{{transcript}}");
+        assert_eq!(definition.output, OutputMode::Text);
+
+        assert!(CustomAgentDefinition::parse("call.yaml", json).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = definition();
+        let invalid = |change: fn(&mut CustomAgentDefinition)| {
+            let mut definition = valid.clone();
+            change(&mut definition);
+            definition.validate().unwrap_err()
+        };
+
+        assert!(invalid(|d| d.id = "Incident Postmortem".to_string()).contains("lowercase"));
+        assert!(invalid(|d| d.id = "brain-dump".to_string()).contains("built-in"));
+        assert!(invalid(|d| d.id = "translation".to_string()).contains("built-in"));
+        assert!(invalid(|d| d.id = "pipeline".to_string()).contains("reserved"));
+        assert!(invalid(|d| d.user_template = "{{transcrpt}}".to_string()).contains("'{{transcrpt}}'"));
+        assert!(invalid(|d| d.user_template = "Summarize".to_string()).contains("{{transcript}}"));
+        assert!(invalid(|d| d.output = OutputMode::Json { schema: serde_json::json!({"type": "array"}) })
+            .contains("object"));
+        assert!(invalid(|d| d.model.temperature = Some(3.0)).contains("Temperature"));
+    }
+
+    #[test]
+    fn test_prompt_and_schema() {
+        let agent = CustomAgent { definition: definition() };
+        let config = agent.definition.model.model_config();
+        let input = CustomAgentInput {
+            transcript: "The database ran out of disk.".to_string(),
+            language: Some("German".to_string()),
+        };
+
+        let parts = agent.prompt(&config, &input).unwrap();
+        let [PromptPart::Ask(request)] = parts.as_slice() else {
+            panic!("expected a single question");
+        };
+        assert_eq!(request.system.as_deref(), Some("You write blameless postmortems. Answer in German."));
+        let user = &request.messages[0].content;
+        assert!(user.starts_with("Debrief from 20"), "{}", user);
+        assert!(user.ends_with(":\n\nThe database ran out of disk."));
+
+        let schema = agent.output_schema().unwrap();
+        assert_eq!(schema.name, "incident-postmortem");
+        assert_eq!(schema.schema["required"], serde_json::json!(["summary", "title"]));
+        assert_eq!(schema.schema["additionalProperties"], false);
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = std::env::temp_dir().join(format!("custom-agents-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a-postmortem.toml"), POSTMORTEM).unwrap();
+        std::fs::write(dir.join("b-duplicate.toml"), POSTMORTEM).unwrap();
+        std::fs::write(dir.join("c-broken.json"), "{").unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let (agents, errors) = load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(agents.len(), 1);
+        assert_eq!(agents[0].definition.id, "incident-postmortem");
+        let files: Vec<&str> = errors.iter().map(|e| e.file.as_str()).collect();
+        assert_eq!(files, ["b-duplicate.toml", "c-broken.json"]);
+    }
+}
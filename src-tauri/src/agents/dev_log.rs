@@ -1,44 +1,47 @@
-use futures_util::StreamExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
 
-const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
+use super::agent::{Agent, PromptPart, TranscriptInput};
+use crate::llm::json_stream::CompletedValue;
+use crate::llm::ModelConfig;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DevLogResult {
     pub commit_message: String,
     pub ticket: TicketContent,
     pub slack_update: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TicketContent {
     pub title: String,
     pub description: String,
     pub acceptance_criteria: Vec<String>,
 }
 
+/// Payload of the `dev-log-part` event, sent as soon as the model has
+/// finished writing one of the three outputs
 #[derive(Debug, Clone, Serialize)]
-pub struct DevLogChunk {
-    pub text: String,
-    pub is_complete: bool,
+#[serde(tag = "kind", content = "part", rename_all = "snake_case")]
+pub enum DevLogPart {
+    CommitMessage(String),
+    Ticket(TicketContent),
+    SlackUpdate(String),
 }
 
-#[derive(Debug, Deserialize)]
-struct OpenAiStreamChunk {
-    choices: Vec<OpenAiStreamChoice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAiStreamChoice {
-    delta: OpenAiDelta,
-    #[allow(dead_code)]
-    finish_reason: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAiDelta {
-    content: Option<String>,
+impl DevLogPart {
+    fn from_streamed(completed: CompletedValue) -> Option<Self> {
+        let part = if completed.is_field("commit_message") {
+            Self::CommitMessage(serde_json::from_value(completed.value).ok()?)
+        } else if completed.is_field("ticket") {
+            Self::Ticket(serde_json::from_value(completed.value).ok()?)
+        } else if completed.is_field("slack_update") {
+            Self::SlackUpdate(serde_json::from_value(completed.value).ok()?)
+        } else {
+            return None;
+        };
+        Some(part)
+    }
 }
 
 const SYSTEM_PROMPT: &str = r#"You are a technical documentation expert specializing in transforming messy developer thoughts and voice transcripts into clean, professional documentation.
@@ -79,161 +82,46 @@ Return your response in this exact JSON format:
 
 IMPORTANT: Return ONLY valid JSON, no markdown code blocks or explanations."#;
 
-#[tauri::command]
-pub async fn generate_dev_log(
-    app: AppHandle,
-    api_key: String,
-    transcript: String,
-) -> Result<DevLogResult, String> {
-    if transcript.trim().is_empty() {
-        return Err("Transcript is empty. Please provide some content.".to_string());
-    }
-
-    let client = reqwest::Client::new();
-
-    let request_body = serde_json::json!({
-        "model": "gpt-4o",
-        "messages": [
-            {
-                "role": "system",
-                "content": SYSTEM_PROMPT
-            },
-            {
-                "role": "user",
-                "content": format!("Transform this developer transcript into documentation:\n\n{}", transcript)
-            }
-        ],
-        "response_format": { "type": "json_object" },
-        "temperature": 0.3
-    });
-
-    let response = client
-        .post(OPENAI_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
-    }
-
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+pub struct DevLog;
 
-    let content = response_json["choices"][0]["message"]["content"]
-        .as_str()
-        .ok_or("No content in response")?;
+impl Agent for DevLog {
+    type Input = TranscriptInput;
+    type Output = DevLogResult;
 
-    let result: DevLogResult = serde_json::from_str(content)
-        .map_err(|e| format!("Failed to parse dev log result: {}", e))?;
-
-    let _ = app.emit("dev-log-generated", &result);
+    fn id(&self) -> &str {
+        "dev-log"
+    }
 
-    Ok(result)
-}
+    fn name(&self) -> &str {
+        "Dev Log"
+    }
 
-#[tauri::command]
-pub async fn generate_dev_log_streaming(
-    app: AppHandle,
-    api_key: String,
-    transcript: String,
-) -> Result<(), String> {
-    if transcript.trim().is_empty() {
-        return Err("Transcript is empty. Please provide some content.".to_string());
+    fn description(&self) -> &str {
+        "Turn developer notes into a commit message, ticket and Slack update"
     }
 
-    let _ = app.emit("dev-log-started", ());
-
-    let client = reqwest::Client::new();
-
-    let request_body = serde_json::json!({
-        "model": "gpt-4o",
-        "messages": [
-            {
-                "role": "system",
-                "content": SYSTEM_PROMPT
-            },
-            {
-                "role": "user",
-                "content": format!("Transform this developer transcript into documentation:\n\n{}", transcript)
-            }
-        ],
-        "stream": true,
-        "temperature": 0.3
-    });
-
-    let response = client
-        .post(OPENAI_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
+    fn event_prefix(&self) -> &str {
+        "dev-log"
     }
 
-    let mut stream = response.bytes_stream();
-    let mut full_text = String::new();
-    let mut buffer = String::new();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
-        let chunk_str = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&chunk_str);
-
-        // Parse SSE events
-        while let Some(event_end) = buffer.find("\n\n") {
-            let event_str = buffer[..event_end].to_string();
-            buffer = buffer[event_end + 2..].to_string();
-
-            for line in event_str.lines() {
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
-
-                    if let Ok(chunk) = serde_json::from_str::<OpenAiStreamChunk>(data) {
-                        if let Some(choice) = chunk.choices.first() {
-                            if let Some(content) = &choice.delta.content {
-                                full_text.push_str(content);
-                                let _ = app.emit(
-                                    "dev-log-chunk",
-                                    DevLogChunk {
-                                        text: content.clone(),
-                                        is_complete: false,
-                                    },
-                                );
-                            }
-                        }
-                    }
-                }
-            }
+    fn prompt(&self, config: &ModelConfig, input: &TranscriptInput) -> Result<Vec<PromptPart>, String> {
+        if input.transcript.trim().is_empty() {
+            return Err("Transcript is empty. Please provide some content.".to_string());
         }
+        Ok(vec![PromptPart::Ask(
+            config
+                .request()
+                .with_system(SYSTEM_PROMPT)
+                .with_user(format!("Transform this developer transcript into documentation:\n\n{}", input.transcript)),
+        )])
     }
 
-    let _ = app.emit(
-        "dev-log-chunk",
-        DevLogChunk {
-            text: String::new(),
-            is_complete: true,
-        },
-    );
-
-    // Parse the complete JSON response
-    let result: DevLogResult = serde_json::from_str(&full_text)
-        .map_err(|e| format!("Failed to parse dev log result: {}", e))?;
-
-    let _ = app.emit("dev-log-complete", &result);
+    fn stream_depth(&self) -> usize {
+        1
+    }
 
-    Ok(())
+    fn streamed_event(&self, value: CompletedValue) -> Option<(&'static str, serde_json::Value)> {
+        let part = DevLogPart::from_streamed(value)?;
+        Some(("part", serde_json::to_value(part).ok()?))
+    }
 }
@@ -1,10 +1,12 @@
-use futures_util::StreamExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 
-const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
+use super::agent::{Agent, PromptPart, TranscriptInput};
+use crate::llm::json_stream::{CompletedValue, PathSegment};
+use crate::llm::ModelConfig;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MentalMirrorResult {
     pub reflection: String,
     pub mental_checkin: String,
@@ -14,27 +16,12 @@ pub struct MentalMirrorResult {
     pub disclaimer: String,
 }
 
+/// Payload of the `mental-mirror-section` event: one finished field of
+/// [`MentalMirrorResult`], e.g. `reflection`
 #[derive(Debug, Clone, Serialize)]
-pub struct MentalMirrorChunk {
+pub struct MentalMirrorSection {
+    pub section: String,
     pub text: String,
-    pub is_complete: bool,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAiStreamChunk {
-    choices: Vec<OpenAiStreamChoice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAiStreamChoice {
-    delta: OpenAiDelta,
-    #[allow(dead_code)]
-    finish_reason: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAiDelta {
-    content: Option<String>,
 }
 
 const SYSTEM_PROMPT: &str = r#"You are a compassionate therapist and mentor with expertise in emotional processing and self-reflection. Your role is to transform the user's stream-of-consciousness "vent" or daily reflection into a warm, empathetic "Letter to My Future Self."
@@ -74,161 +61,56 @@ Return a JSON object with these exact fields:
 
 IMPORTANT: Return ONLY valid JSON, no markdown code blocks or explanations."#;
 
-#[tauri::command]
-pub async fn generate_mental_mirror(
-    app: AppHandle,
-    api_key: String,
-    transcript: String,
-) -> Result<MentalMirrorResult, String> {
-    if transcript.trim().is_empty() {
-        return Err("Please share your thoughts first. Your reflection space is ready when you are.".to_string());
-    }
+pub struct MentalMirror;
 
-    let client = reqwest::Client::new();
-
-    let request_body = serde_json::json!({
-        "model": "gpt-4o",
-        "messages": [
-            {
-                "role": "system",
-                "content": SYSTEM_PROMPT
-            },
-            {
-                "role": "user",
-                "content": format!("Transform this personal reflection into a Letter to My Future Self:\n\n{}", transcript)
-            }
-        ],
-        "response_format": { "type": "json_object" },
-        "temperature": 0.7
-    });
+impl Agent for MentalMirror {
+    type Input = TranscriptInput;
+    type Output = MentalMirrorResult;
 
-    let response = client
-        .post(OPENAI_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
+    fn id(&self) -> &str {
+        "mental-mirror"
     }
 
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    let content = response_json["choices"][0]["message"]["content"]
-        .as_str()
-        .ok_or("No content in response")?;
-
-    let result: MentalMirrorResult = serde_json::from_str(content)
-        .map_err(|e| format!("Failed to parse mental mirror result: {}", e))?;
-
-    let _ = app.emit("mental-mirror-generated", &result);
-
-    Ok(result)
-}
-
-#[tauri::command]
-pub async fn generate_mental_mirror_streaming(
-    app: AppHandle,
-    api_key: String,
-    transcript: String,
-) -> Result<(), String> {
-    if transcript.trim().is_empty() {
-        return Err("Please share your thoughts first. Your reflection space is ready when you are.".to_string());
+    fn name(&self) -> &str {
+        "Mental Mirror"
     }
 
-    let _ = app.emit("mental-mirror-started", ());
+    fn description(&self) -> &str {
+        "Write a letter to your future self"
+    }
 
-    let client = reqwest::Client::new();
+    fn event_prefix(&self) -> &str {
+        "mental-mirror"
+    }
 
-    let request_body = serde_json::json!({
-        "model": "gpt-4o",
-        "messages": [
-            {
-                "role": "system",
-                "content": SYSTEM_PROMPT
-            },
-            {
-                "role": "user",
-                "content": format!("Transform this personal reflection into a Letter to My Future Self:\n\n{}", transcript)
-            }
-        ],
-        "stream": true,
-        "temperature": 0.7
-    });
+    fn prompt(&self, config: &ModelConfig, input: &TranscriptInput) -> Result<Vec<PromptPart>, String> {
+        if input.transcript.trim().is_empty() {
+            return Err("Please share your thoughts first. Your reflection space is ready when you are.".to_string());
+        }
+        Ok(vec![PromptPart::Ask(
+            config
+                .request()
+                .with_system(SYSTEM_PROMPT)
+                .with_user(format!("Transform this personal reflection into a Letter to My Future Self:\n\n{}", input.transcript)),
+        )])
+    }
 
-    let response = client
-        .post(OPENAI_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
+    fn stream_depth(&self) -> usize {
+        1
     }
 
-    let mut stream = response.bytes_stream();
-    let mut full_text = String::new();
-    let mut buffer = String::new();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
-        let chunk_str = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&chunk_str);
-
-        while let Some(event_end) = buffer.find("\n\n") {
-            let event_str = buffer[..event_end].to_string();
-            buffer = buffer[event_end + 2..].to_string();
-
-            for line in event_str.lines() {
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
-
-                    if let Ok(chunk) = serde_json::from_str::<OpenAiStreamChunk>(data) {
-                        if let Some(choice) = chunk.choices.first() {
-                            if let Some(content) = &choice.delta.content {
-                                full_text.push_str(content);
-                                let _ = app.emit(
-                                    "mental-mirror-chunk",
-                                    MentalMirrorChunk {
-                                        text: content.clone(),
-                                        is_complete: false,
-                                    },
-                                );
-                            }
-                        }
-                    }
-                }
+    fn streamed_event(&self, value: CompletedValue) -> Option<(&'static str, serde_json::Value)> {
+        match (value.path.as_slice(), value.value) {
+            ([PathSegment::Key(section)], serde_json::Value::String(text)) => {
+                let section = MentalMirrorSection {
+                    section: section.clone(),
+                    text,
+                };
+                Some(("section", serde_json::to_value(section).ok()?))
             }
+            _ => None,
         }
     }
-
-    let _ = app.emit(
-        "mental-mirror-chunk",
-        MentalMirrorChunk {
-            text: String::new(),
-            is_complete: true,
-        },
-    );
-
-    let result: MentalMirrorResult = serde_json::from_str(&full_text)
-        .map_err(|e| format!("Failed to parse mental mirror result: {}", e))?;
-
-    let _ = app.emit("mental-mirror-complete", &result);
-
-    Ok(())
 }
 
 /// Schedule a mental mirror letter to be sent via email (mock implementation)
@@ -1,3 +1,9 @@
+pub mod agent;
+pub mod config;
+pub mod custom;
+pub mod pipeline;
+pub mod registry;
+pub mod runs;
 pub mod action_items;
 pub mod tone_shifter;
 pub mod music_matcher;
@@ -1,8 +1,14 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
+use super::agent::{Agent, PromptPart, TranscriptInput};
+use crate::llm::{self, ModelConfig};
+
 // Q-Records API endpoint (placeholder - replace with actual endpoint)
 const QRECORDS_API_URL: &str = "https://api.qrecords.com/v1";
+const QRECORDS_TIMEOUT: Duration = Duration::from_secs(15);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MusicMatchRequest {
@@ -34,7 +40,7 @@ pub struct MusicMatchResult {
     pub analysis: MoodAnalysis,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MoodAnalysis {
     pub detected_mood: String,
     pub energy_level: f32,
@@ -76,7 +82,7 @@ pub async fn match_music(
     api_key: String,
     request: MusicMatchRequest,
 ) -> Result<MusicMatchResult, String> {
-    let client = reqwest::Client::new();
+    let client = llm::http_client();
 
     let mut query_params = vec![("query", request.query.clone())];
 
@@ -98,6 +104,7 @@ pub async fn match_music(
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .query(&query_params)
+        .timeout(QRECORDS_TIMEOUT)
         .send()
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
@@ -156,65 +163,44 @@ pub async fn match_music(
     Ok(result)
 }
 
-#[tauri::command]
-pub async fn analyze_mood_from_transcript(
-    app: AppHandle,
-    openai_key: String,
-    transcript: String,
-) -> Result<MoodAnalysis, String> {
-    let client = reqwest::Client::new();
-
-    let request_body = serde_json::json!({
-        "model": "gpt-4o-mini",
-        "messages": [
-            {
-                "role": "system",
-                "content": r#"Analyze the emotional mood and energy of the given text. Return a JSON object with:
+const MOOD_PROMPT: &str = r#"Analyze the emotional mood and energy of the given text. Return a JSON object with:
 {
   "detected_mood": "primary mood (e.g., happy, sad, energetic, calm, anxious, hopeful)",
   "energy_level": 0.0-1.0 (0 = very low energy, 1 = very high energy),
   "valence": 0.0-1.0 (0 = very negative, 1 = very positive),
   "keywords": ["array", "of", "mood", "keywords"]
 }
-Only return the JSON object, no other text."#
-            },
-            {
-                "role": "user",
-                "content": transcript
-            }
-        ],
-        "response_format": { "type": "json_object" }
-    });
+Only return the JSON object, no other text."#;
 
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", openai_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+/// The mood analysis behind the Music Matcher; the Q-Records search itself
+/// is the separate `match_music` command
+pub struct MoodAnalyzer;
 
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
-    }
+impl Agent for MoodAnalyzer {
+    type Input = TranscriptInput;
+    type Output = MoodAnalysis;
 
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    fn id(&self) -> &str {
+        "music-matcher"
+    }
 
-    let content = response_json["choices"][0]["message"]["content"]
-        .as_str()
-        .ok_or("No content in response")?;
+    fn name(&self) -> &str {
+        "Music Matcher"
+    }
 
-    let analysis: MoodAnalysis = serde_json::from_str(content)
-        .map_err(|e| format!("Failed to parse mood analysis: {}", e))?;
+    fn description(&self) -> &str {
+        "Analyze mood and energy to match music"
+    }
 
-    let _ = app.emit("mood-analyzed", &analysis);
+    fn event_prefix(&self) -> &str {
+        "mood-analysis"
+    }
 
-    Ok(analysis)
+    fn prompt(&self, config: &ModelConfig, input: &TranscriptInput) -> Result<Vec<PromptPart>, String> {
+        Ok(vec![PromptPart::Ask(
+            config.request().with_system(MOOD_PROMPT).with_user(&input.transcript),
+        )])
+    }
 }
 
 fn create_mock_result(query: &str) -> MusicMatchResult {
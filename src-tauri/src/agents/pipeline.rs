@@ -0,0 +1,654 @@
+//! Pipelines: several agents run as a DAG, later steps working on the
+//! results of earlier ones.
+//!
+//! A step's `input` is the JSON input of its agent in which strings may
+//! reference the pipeline input or an earlier step's result:
+//!
+//! ```json
+//! { "transcript": "Tasks from a brain dump:\n{{brain.tasks}}" }
+//! ```
+//!
+//! A string that is just one reference, like `"{{brain.tasks}}"`, becomes the
+//! referenced value itself; references inside longer strings are replaced
+//! with the value's text (JSON for anything but strings). Paths are
+//! dot-separated keys and array indices, starting at `input` or a step id.
+//!
+//! A step runs once the steps it references have succeeded, independent
+//! steps run concurrently. When a step fails, the steps depending on it are
+//! skipped and the rest still run. Progress is reported as
+//! `pipeline-step-started`, `pipeline-step-finished` and `pipeline-complete`.
+
+use futures_util::future::BoxFuture;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use tauri::AppHandle;
+
+use super::agent::RunEvents;
+use super::registry::{self, AppEvents};
+use super::{config, runs};
+use crate::storage;
+
+const PIPELINES_FILE: &str = "pipelines.json";
+
+/// Prefix of the pipeline events
+pub(crate) const EVENT_PREFIX: &str = "pipeline";
+
+/// Root of references to the pipeline input
+const INPUT: &str = "input";
+
+static REFERENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*([\w-]+(?:\.[\w-]+)*)\s*\}\}").unwrap());
+static WHOLE_REFERENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\{\{\s*([\w-]+(?:\.[\w-]+)*)\s*\}\}$").unwrap());
+static ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").unwrap());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineStep {
+    /// Unique within the pipeline; later steps reference the result by it
+    pub id: String,
+    /// Id of the agent to run
+    pub agent: String,
+    /// The agent's input, with references resolved before it runs
+    pub input: Value,
+}
+
+impl PipelineStep {
+    /// Ids of the steps this step references, in order of appearance
+    pub fn dependencies(&self) -> Vec<String> {
+        let mut roots = Vec::new();
+        collect_roots(&self.input, &mut roots);
+        let mut seen = HashSet::new();
+        roots.retain(|root| root != INPUT && seen.insert(root.clone()));
+        roots
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<PipelineStep>,
+}
+
+impl PipelineDefinition {
+    /// Check ids, references and that the steps form a DAG. Whether the
+    /// agents exist is checked against the registry when saving and running.
+    pub fn validate(&self) -> Result<(), String> {
+        if !ID.is_match(&self.id) {
+            return Err(format!(
+                "Pipeline id '{}' must be lowercase letters, digits and dashes, like 'brain-dump-follow-up'",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err("Name must not be empty".to_string());
+        }
+        if self.steps.is_empty() {
+            return Err("A pipeline needs at least one step".to_string());
+        }
+
+        let mut ids = HashSet::new();
+        for step in &self.steps {
+            if !ID.is_match(&step.id) || step.id == INPUT {
+                return Err(format!(
+                    "Step id '{}' must be lowercase letters, digits and dashes, and not '{}'",
+                    step.id, INPUT
+                ));
+            }
+            if !ids.insert(step.id.as_str()) {
+                return Err(format!("Step id '{}' is used twice", step.id));
+            }
+        }
+        for step in &self.steps {
+            if let Some(unknown) = step.dependencies().iter().find(|dependency| !ids.contains(dependency.as_str())) {
+                return Err(format!("Step '{}' references unknown step '{}'", step.id, unknown));
+            }
+        }
+
+        // Kahn's algorithm: whatever never becomes ready is on a cycle
+        let mut done = HashSet::new();
+        while done.len() < self.steps.len() {
+            let ready: Vec<&str> = self
+                .steps
+                .iter()
+                .filter(|step| !done.contains(step.id.as_str()))
+                .filter(|step| step.dependencies().iter().all(|dependency| done.contains(dependency.as_str())))
+                .map(|step| step.id.as_str())
+                .collect();
+            if ready.is_empty() {
+                let stuck: Vec<&str> = self
+                    .steps
+                    .iter()
+                    .map(|step| step.id.as_str())
+                    .filter(|id| !done.contains(id))
+                    .collect();
+                return Err(format!("Steps {} depend on each other in a cycle", stuck.join(", ")));
+            }
+            done.extend(ready);
+        }
+        Ok(())
+    }
+
+    /// Brain dump, then action items for its tasks and a reflection on its
+    /// ideas — the first pipeline of new installs
+    fn brain_dump_follow_up() -> Self {
+        Self {
+            id: "brain-dump-follow-up".to_string(),
+            name: "Brain dump follow-up".to_string(),
+            description: "Sort a brain dump, then plan its tasks and reflect on its ideas".to_string(),
+            steps: vec![
+                PipelineStep {
+                    id: "brain".to_string(),
+                    agent: "brain-dump".to_string(),
+                    input: json!({ "transcript": "{{input.transcript}}" }),
+                },
+                PipelineStep {
+                    id: "actions".to_string(),
+                    agent: "action-items".to_string(),
+                    input: json!({ "transcript": "Tasks from a brain dump:\n{{brain.tasks}}" }),
+                },
+                PipelineStep {
+                    id: "reflection".to_string(),
+                    agent: "mental-mirror".to_string(),
+                    input: json!({
+                        "transcript": "{{input.transcript}}\n\nIdeas that came up:\n{{brain.creative_ideas}}"
+                    }),
+                },
+            ],
+        }
+    }
+}
+
+/// Push the root of every reference in `value`'s strings to `roots`
+fn collect_roots(value: &Value, roots: &mut Vec<String>) {
+    match value {
+        Value::String(text) => {
+            for captures in REFERENCE.captures_iter(text) {
+                let root = captures[1].split('.').next().unwrap_or_default();
+                roots.push(root.to_string());
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_roots(item, roots)),
+        Value::Object(fields) => fields.values().for_each(|field| collect_roots(field, roots)),
+        _ => {}
+    }
+}
+
+/// The value at `path` in `values` (the pipeline input and step results)
+fn lookup<'a>(path: &str, values: &'a HashMap<String, Value>) -> Result<&'a Value, String> {
+    let mut segments = path.split('.');
+    let root = segments.next().unwrap_or_default();
+    let mut value = values.get(root).ok_or_else(|| format!("No result for '{}'", root))?;
+    for segment in segments {
+        let next = match value {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|index| items.get(index)),
+            Value::Object(fields) => fields.get(segment),
+            _ => None,
+        };
+        value = next.ok_or_else(|| format!("Reference '{{{{{}}}}}' not found", path))?;
+    }
+    Ok(value)
+}
+
+/// `template` with its references replaced
+fn resolve(template: &Value, values: &HashMap<String, Value>) -> Result<Value, String> {
+    match template {
+        Value::String(text) => {
+            if let Some(captures) = WHOLE_REFERENCE.captures(text) {
+                return lookup(&captures[1], values).cloned();
+            }
+            let mut error = None;
+            let resolved = REFERENCE.replace_all(text, |captures: &regex::Captures| {
+                match lookup(&captures[1], values) {
+                    Ok(Value::String(value)) => value.clone(),
+                    Ok(value) => serde_json::to_string_pretty(value).unwrap_or_default(),
+                    Err(e) => {
+                        error.get_or_insert(e);
+                        String::new()
+                    }
+                }
+            });
+            match error {
+                Some(error) => Err(error),
+                None => Ok(Value::String(resolved.into_owned())),
+            }
+        }
+        Value::Array(items) => items.iter().map(|item| resolve(item, values)).collect(),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, field)| Ok((key.clone(), resolve(field, values)?)))
+            .collect::<Result<serde_json::Map<_, _>, String>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum StepOutcome {
+    Succeeded { output: Value },
+    Failed { error: String },
+    /// Not run because a step it depends on didn't succeed
+    Skipped { reason: String },
+}
+
+/// Payload of the `pipeline-step-finished` events
+#[derive(Debug, Clone, Serialize)]
+pub struct StepReport {
+    pub pipeline_id: String,
+    pub step: String,
+    pub agent: String,
+    #[serde(flatten)]
+    pub outcome: StepOutcome,
+}
+
+/// Payload of the `pipeline-step-started` events
+#[derive(Debug, Clone, Serialize)]
+pub struct StepStarted {
+    pub pipeline_id: String,
+    pub step: String,
+    pub agent: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStatus {
+    Succeeded,
+    /// Some steps failed or were skipped
+    PartiallyFailed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineResult {
+    pub pipeline_id: String,
+    pub status: PipelineStatus,
+    /// In definition order
+    pub steps: Vec<StepReport>,
+}
+
+/// Runs one step's agent
+pub trait StepRunner: Send + Sync {
+    fn run<'a>(&'a self, agent_id: &'a str, input: Value) -> BoxFuture<'a, Result<Value, String>>;
+}
+
+/// Runs the registered agents with their configured models and stored keys
+struct RegistryRunner<'a> {
+    app: &'a AppHandle,
+}
+
+impl StepRunner for RegistryRunner<'_> {
+    fn run<'a>(&'a self, agent_id: &'a str, input: Value) -> BoxFuture<'a, Result<Value, String>> {
+        Box::pin(async move {
+            let agent = registry::find(self.app, agent_id).ok_or_else(|| format!("Unknown agent '{}'", agent_id))?;
+            let (config, provider) = config::resolve(self.app, agent.id(), "")?;
+            agent.run(&config, provider.as_ref(), input, None).await
+        })
+    }
+}
+
+/// Run the steps of a validated `definition` on `input`
+pub async fn execute(
+    definition: &PipelineDefinition,
+    input: Value,
+    runner: &dyn StepRunner,
+    events: &dyn RunEvents,
+) -> PipelineResult {
+    let mut values = HashMap::from([(INPUT.to_string(), input)]);
+    let mut outcomes: HashMap<String, StepOutcome> = HashMap::new();
+
+    let report = |step: &PipelineStep, outcome: &StepOutcome| StepReport {
+        pipeline_id: definition.id.clone(),
+        step: step.id.clone(),
+        agent: step.agent.clone(),
+        outcome: outcome.clone(),
+    };
+    let emit_finished = |step: &PipelineStep, outcome: &StepOutcome| {
+        events.emit("step-finished", serde_json::to_value(report(step, outcome)).unwrap_or(Value::Null));
+    };
+
+    // Steps that were started or skipped
+    let mut launched: HashSet<&str> = HashSet::new();
+    let mut running = FuturesUnordered::new();
+
+    loop {
+        // Skipping a step can make its dependents ready, so look again until
+        // nothing new can start
+        loop {
+            let ready: Vec<&PipelineStep> = definition
+                .steps
+                .iter()
+                .filter(|step| !launched.contains(step.id.as_str()))
+                .filter(|step| step.dependencies().iter().all(|dependency| outcomes.contains_key(dependency)))
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+
+            for step in ready {
+                launched.insert(&step.id);
+                let failed = step
+                    .dependencies()
+                    .into_iter()
+                    .find(|dependency| !matches!(outcomes[dependency], StepOutcome::Succeeded { .. }));
+                if let Some(dependency) = failed {
+                    let outcome = StepOutcome::Skipped {
+                        reason: format!("Step '{}' did not succeed", dependency),
+                    };
+                    emit_finished(step, &outcome);
+                    outcomes.insert(step.id.clone(), outcome);
+                    continue;
+                }
+
+                let started = StepStarted {
+                    pipeline_id: definition.id.clone(),
+                    step: step.id.clone(),
+                    agent: step.agent.clone(),
+                };
+                events.emit("step-started", serde_json::to_value(started).unwrap_or(Value::Null));
+                let input = resolve(&step.input, &values);
+                running.push(async move {
+                    let result = match input {
+                        Ok(input) => runner.run(&step.agent, input).await,
+                        Err(error) => Err(error),
+                    };
+                    (step, result)
+                });
+            }
+        }
+
+        // Whichever step finishes first may unblock its dependents
+        let Some((step, result)) = running.next().await else {
+            break;
+        };
+        let outcome = match result {
+            Ok(output) => {
+                values.insert(step.id.clone(), output.clone());
+                StepOutcome::Succeeded { output }
+            }
+            Err(error) => {
+                tracing::warn!("Pipeline '{}' step '{}' failed: {}", definition.id, step.id, error);
+                StepOutcome::Failed { error }
+            }
+        };
+        emit_finished(step, &outcome);
+        outcomes.insert(step.id.clone(), outcome);
+    }
+
+    let steps: Vec<StepReport> = definition
+        .steps
+        .iter()
+        .filter_map(|step| outcomes.get(&step.id).map(|outcome| report(step, outcome)))
+        .collect();
+    let succeeded = steps
+        .iter()
+        .filter(|report| matches!(report.outcome, StepOutcome::Succeeded { .. }))
+        .count();
+    let status = match succeeded {
+        n if n == definition.steps.len() => PipelineStatus::Succeeded,
+        0 => PipelineStatus::Failed,
+        _ => PipelineStatus::PartiallyFailed,
+    };
+
+    let result = PipelineResult {
+        pipeline_id: definition.id.clone(),
+        status,
+        steps,
+    };
+    events.emit("complete", serde_json::to_value(&result).unwrap_or(Value::Null));
+    result
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct PipelineFile {
+    pipelines: Vec<PipelineDefinition>,
+}
+
+impl Default for PipelineFile {
+    fn default() -> Self {
+        Self {
+            pipelines: vec![PipelineDefinition::brain_dump_follow_up()],
+        }
+    }
+}
+
+impl PipelineFile {
+    fn load(app: &AppHandle) -> Self {
+        storage::load_json(app, PIPELINES_FILE)
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, PIPELINES_FILE, self)
+    }
+}
+
+fn check_agents(app: &AppHandle, definition: &PipelineDefinition) -> Result<(), String> {
+    match definition.steps.iter().find(|step| registry::find(app, &step.agent).is_none()) {
+        Some(step) => Err(format!("Step '{}' uses unknown agent '{}'", step.id, step.agent)),
+        None => Ok(()),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_pipelines(app: AppHandle) -> Result<Vec<PipelineDefinition>, String> {
+    Ok(PipelineFile::load(&app).pipelines)
+}
+
+/// Add `pipeline`, or replace the saved one with its id
+#[tauri::command]
+pub async fn save_pipeline(app: AppHandle, pipeline: PipelineDefinition) -> Result<(), String> {
+    pipeline.validate()?;
+    check_agents(&app, &pipeline)?;
+
+    let mut file = PipelineFile::load(&app);
+    match file.pipelines.iter_mut().find(|saved| saved.id == pipeline.id) {
+        Some(saved) => *saved = pipeline,
+        None => file.pipelines.push(pipeline),
+    }
+    file.save(&app)
+}
+
+/// Returns false if there was no such pipeline
+#[tauri::command]
+pub async fn delete_pipeline(app: AppHandle, pipeline_id: String) -> Result<bool, String> {
+    let mut file = PipelineFile::load(&app);
+    let count = file.pipelines.len();
+    file.pipelines.retain(|pipeline| pipeline.id != pipeline_id);
+    if file.pipelines.len() == count {
+        return Ok(false);
+    }
+    file.save(&app)?;
+    Ok(true)
+}
+
+/// Run saved pipeline `pipeline_id` on `input` (usually `{ "transcript" }`).
+/// Failed steps don't fail the command; they are in the result. `None` if
+/// the run was cancelled with `cancel_agent_run`.
+#[tauri::command]
+pub async fn run_pipeline(
+    app: AppHandle,
+    pipeline_id: String,
+    input: Value,
+    run_id: Option<String>,
+) -> Result<Option<PipelineResult>, String> {
+    let definition = PipelineFile::load(&app)
+        .pipelines
+        .into_iter()
+        .find(|pipeline| pipeline.id == pipeline_id)
+        .ok_or_else(|| format!("Unknown pipeline '{}'", pipeline_id))?;
+    definition.validate()?;
+    check_agents(&app, &definition)?;
+
+    let runner = RegistryRunner { app: &app };
+    let events = AppEvents::new(&app, EVENT_PREFIX);
+    runs::cancellable(&app, run_id, EVENT_PREFIX, async {
+        Ok(execute(&definition, input, &runner, &events).await)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn step(id: &str, agent: &str, input: Value) -> PipelineStep {
+        PipelineStep {
+            id: id.to_string(),
+            agent: agent.to_string(),
+            input,
+        }
+    }
+
+    fn pipeline(steps: Vec<PipelineStep>) -> PipelineDefinition {
+        PipelineDefinition {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: String::new(),
+            steps,
+        }
+    }
+
+    /// `split` splits its text into words, `count` counts its items, `wait`
+    /// splits its text after `ms` milliseconds and `fail` always fails
+    struct FakeRunner;
+
+    impl StepRunner for FakeRunner {
+        fn run<'a>(&'a self, agent_id: &'a str, input: Value) -> BoxFuture<'a, Result<Value, String>> {
+            Box::pin(async move {
+                match agent_id {
+                    "split" => {
+                        let text = input["text"].as_str().ok_or("text must be a string")?;
+                        Ok(json!({ "words": text.split_whitespace().collect::<Vec<_>>() }))
+                    }
+                    "count" => Ok(json!(input["items"].as_array().ok_or("items must be an array")?.len())),
+                    "wait" => {
+                        let ms = input["ms"].as_u64().unwrap_or_default();
+                        tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+                        let text = input["text"].as_str().ok_or("text must be a string")?;
+                        Ok(json!({ "words": text.split_whitespace().collect::<Vec<_>>() }))
+                    }
+                    _ => Err("Model unavailable".to_string()),
+                }
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl RunEvents for Recorder {
+        fn emit(&self, name: &str, payload: Value) {
+            let step = payload["step"].as_str().unwrap_or_default();
+            self.0.lock().unwrap().push(format!("{} {}", name, step).trim_end().to_string());
+        }
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let values = HashMap::from([
+            ("input".to_string(), json!({ "transcript": "hello" })),
+            ("brain".to_string(), json!({ "tasks": [{ "title": "Call Bob" }] })),
+        ]);
+
+        let template = json!({
+            "items": "{{ brain.tasks }}",
+            "first": "{{brain.tasks.0.title}}",
+            "text": "{{input.transcript}}: {{brain.tasks}}",
+            "fixed": 3
+        });
+        let resolved = resolve(&template, &values).unwrap();
+        assert_eq!(resolved["items"], json!([{ "title": "Call Bob" }]));
+        assert_eq!(resolved["first"], "Call Bob");
+        assert!(resolved["text"].as_str().unwrap().starts_with("hello: [\n"));
+        assert_eq!(resolved["fixed"], 3);
+
+        let error = resolve(&json!("{{brain.tasks.3}}"), &values).unwrap_err();
+        assert!(error.contains("brain.tasks.3"), "{}", error);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(PipelineDefinition::brain_dump_follow_up().validate().is_ok());
+        assert_eq!(
+            PipelineDefinition::brain_dump_follow_up().steps[2].dependencies(),
+            ["brain"]
+        );
+
+        let duplicate = pipeline(vec![step("a", "split", json!({})), step("a", "count", json!({}))]);
+        assert!(duplicate.validate().unwrap_err().contains("used twice"));
+
+        let unknown = pipeline(vec![step("a", "count", json!({ "items": "{{b.words}}" }))]);
+        assert!(unknown.validate().unwrap_err().contains("unknown step 'b'"));
+
+        let cycle = pipeline(vec![
+            step("a", "count", json!({ "items": "{{b.words}}" })),
+            step("b", "split", json!({ "text": "{{a}}" })),
+            step("c", "split", json!({ "text": "{{input.text}}" })),
+        ]);
+        let error = cycle.validate().unwrap_err();
+        assert!(error.contains("a, b") && error.contains("cycle"), "{}", error);
+
+        assert!(pipeline(vec![step("input", "split", json!({}))]).validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_skips_dependents_only() {
+        let definition = pipeline(vec![
+            step("words", "split", json!({ "text": "{{input.text}}" })),
+            step("broken", "fail", json!({ "text": "{{input.text}}" })),
+            step("count", "count", json!({ "items": "{{words.words}}" })),
+            step("after-broken", "count", json!({ "items": "{{broken.words}}" })),
+        ]);
+        definition.validate().unwrap();
+        let recorder = Recorder::default();
+
+        let result = execute(&definition, json!({ "text": "one two three" }), &FakeRunner, &recorder).await;
+
+        assert_eq!(result.status, PipelineStatus::PartiallyFailed);
+        let outcome = |id: &str| &result.steps.iter().find(|report| report.step == id).unwrap().outcome;
+        assert_eq!(outcome("count"), &StepOutcome::Succeeded { output: json!(3) });
+        assert_eq!(
+            outcome("broken"),
+            &StepOutcome::Failed {
+                error: "Model unavailable".to_string()
+            }
+        );
+        assert!(matches!(outcome("after-broken"), StepOutcome::Skipped { reason } if reason.contains("'broken'")));
+
+        let events = recorder.0.into_inner().unwrap();
+        assert_eq!(events.first().map(String::as_str), Some("step-started words"));
+        assert_eq!(events.last().map(String::as_str), Some("complete"));
+        assert!(!events.contains(&"step-started after-broken".to_string()));
+        assert!(events.contains(&"step-finished after-broken".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_dependents_start_without_waiting_for_slow_steps() {
+        let definition = pipeline(vec![
+            step("slow", "wait", json!({ "text": "{{input.text}}", "ms": 300 })),
+            step("fast", "wait", json!({ "text": "{{input.text}}", "ms": 10 })),
+            step("count", "count", json!({ "items": "{{fast.words}}" })),
+            step("after-slow", "count", json!({ "items": "{{slow.words}}" })),
+        ]);
+        definition.validate().unwrap();
+        let recorder = Recorder::default();
+
+        let result = execute(&definition, json!({ "text": "one two" }), &FakeRunner, &recorder).await;
+
+        assert_eq!(result.status, PipelineStatus::Succeeded);
+        let events = recorder.0.into_inner().unwrap();
+        let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert!(position("step-finished count") < position("step-finished slow"), "{:?}", events);
+        assert!(position("step-started after-slow") > position("step-finished slow"), "{:?}", events);
+    }
+}
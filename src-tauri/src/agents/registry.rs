@@ -0,0 +1,158 @@
+//! All agents, and the generic commands that list and run them.
+//!
+//! The frontend calls `run_agent` with an agent id and JSON input instead of
+//! one command per agent, so adding an agent only means adding it here.
+//! Custom agents (see `custom`) are looked up after the built-in ones.
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+use super::agent::{AgentInfo, AnyAgent, RunEvents};
+use super::{
+    action_items, brain_dump, config, custom, dev_log, mental_mirror, music_matcher, pipeline, runs, tone_shifter,
+    translator,
+};
+
+static BUILTIN: Lazy<Vec<Arc<dyn AnyAgent>>> = Lazy::new(|| {
+    vec![
+        Arc::new(action_items::ActionItems),
+        Arc::new(tone_shifter::ToneShifter),
+        Arc::new(music_matcher::MoodAnalyzer),
+        Arc::new(translator::Translator),
+        Arc::new(dev_log::DevLog),
+        Arc::new(brain_dump::BrainDump),
+        Arc::new(mental_mirror::MentalMirror),
+    ]
+});
+
+/// Prefixes of app events outside the agents, e.g. `pipeline-started` or
+/// `audio-chunk`
+const EVENT_PREFIXES: &[&str] = &[
+    pipeline::EVENT_PREFIX,
+    "audio",
+    "deepgram",
+    "music",
+    "recording",
+    "silence",
+    "sync",
+    "transcript",
+    "transcription",
+    "tts",
+    "usage",
+    "vad",
+    "whisper",
+];
+
+/// The id or event prefix of a built-in agent, or a prefix other app events
+/// use, so custom agents can't shadow it or its events
+pub fn is_reserved(name: &str) -> bool {
+    EVENT_PREFIXES.contains(&name) || BUILTIN.iter().any(|agent| agent.id() == name || agent.event_prefix() == name)
+}
+
+/// Built-in agents, then the valid custom ones
+pub fn all(app: &AppHandle) -> Vec<Arc<dyn AnyAgent>> {
+    let custom = custom::load(app).into_iter().map(|agent| Arc::new(agent) as Arc<dyn AnyAgent>);
+    BUILTIN.iter().cloned().chain(custom).collect()
+}
+
+pub fn find(app: &AppHandle, agent_id: &str) -> Option<Arc<dyn AnyAgent>> {
+    match BUILTIN.iter().find(|agent| agent.id() == agent_id) {
+        Some(agent) => Some(agent.clone()),
+        None => custom::find(app, agent_id).map(|agent| Arc::new(agent) as Arc<dyn AnyAgent>),
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RunOptions {
+    /// Key for the agent's provider if the frontend has one; otherwise the
+    /// stored key is used
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Stream the answer and report progress as events
+    #[serde(default)]
+    pub stream: bool,
+    /// Makes the run cancellable with `cancel_agent_run`
+    #[serde(default)]
+    pub run_id: Option<String>,
+}
+
+/// Emits a run's events as `{prefix}-{name}` app events
+pub(crate) struct AppEvents<'a> {
+    app: &'a AppHandle,
+    prefix: &'a str,
+}
+
+impl<'a> AppEvents<'a> {
+    pub(crate) fn new(app: &'a AppHandle, prefix: &'a str) -> Self {
+        Self { app, prefix }
+    }
+}
+
+impl RunEvents for AppEvents<'_> {
+    fn emit(&self, name: &str, payload: Value) {
+        let _ = self.app.emit(&format!("{}-{}", self.prefix, name), payload);
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Every agent `run_agent` can run
+#[tauri::command]
+pub async fn list_agents(app: AppHandle) -> Result<Vec<AgentInfo>, String> {
+    Ok(all(&app).iter().map(|agent| agent.info()).collect())
+}
+
+/// Run agent `agent_id` on `input`, returning its result; `None` if the
+/// run was cancelled
+#[tauri::command]
+pub async fn run_agent(
+    app: AppHandle,
+    agent_id: String,
+    input: Value,
+    options: Option<RunOptions>,
+) -> Result<Option<Value>, String> {
+    let agent = find(&app, &agent_id).ok_or_else(|| format!("Unknown agent '{}'", agent_id))?;
+    let options = options.unwrap_or_default();
+
+    let (config, provider) = config::resolve(&app, agent.id(), options.api_key.as_deref().unwrap_or_default())?;
+    let events = AppEvents::new(&app, agent.event_prefix());
+    let events = options.stream.then_some(&events as &dyn RunEvents);
+
+    runs::cancellable(
+        &app,
+        options.run_id,
+        agent.event_prefix(),
+        agent.run(&config, provider.as_ref(), input, events),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_builtin_agents() {
+        let agents: Vec<AgentInfo> = BUILTIN.iter().map(|agent| agent.info()).collect();
+        let ids: HashSet<&str> = agents.iter().map(|agent| agent.id.as_str()).collect();
+        assert_eq!(ids.len(), agents.len());
+
+        for agent in &agents {
+            assert!(config::AgentConfig::defaults(&agent.id).is_some(), "no defaults for {}", agent.id);
+            assert_eq!(agent.input_schema["type"], "object", "{}", agent.id);
+        }
+        let info = |id: &str| agents.iter().find(|agent| agent.id == id).unwrap();
+        assert!(info("brain-dump").output_schema.is_some());
+        assert!(info("tone-shifter").output_schema.is_none());
+
+        assert!(is_reserved("translator") && is_reserved("translation"));
+        assert!(is_reserved("pipeline") && is_reserved("audio"));
+        assert!(!is_reserved("call-summary"));
+    }
+}
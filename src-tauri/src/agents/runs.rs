@@ -0,0 +1,113 @@
+//! In-flight agent runs, so the frontend can cancel them.
+//!
+//! The frontend passes a `run_id` when starting an agent run and can
+//! later call `cancel_agent_run` with it. Cancelling drops the agent's
+//! future, which closes its HTTP stream, and emits `<event prefix>-cancelled`.
+
+use futures_util::future::{AbortHandle, AbortRegistration, Abortable};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex as StdMutex;
+use tauri::{AppHandle, Emitter};
+
+static RUNS: Lazy<StdMutex<HashMap<String, AbortHandle>>> = Lazy::new(|| StdMutex::new(HashMap::new()));
+
+/// Payload of the `*-cancelled` events
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentRunCancelled {
+    pub run_id: String,
+}
+
+/// Removes the run from the registry however the run ends
+struct Registration {
+    run_id: String,
+}
+
+impl Registration {
+    fn new(run_id: &str) -> Result<(Self, AbortRegistration), String> {
+        let (handle, registration) = AbortHandle::new_pair();
+        let mut runs = RUNS.lock().map_err(|e| e.to_string())?;
+        if runs.contains_key(run_id) {
+            return Err(format!("Agent run '{}' is already in progress", run_id));
+        }
+        runs.insert(run_id.to_string(), handle);
+        Ok((Self { run_id: run_id.to_string() }, registration))
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Ok(mut runs) = RUNS.lock() {
+            runs.remove(&self.run_id);
+        }
+    }
+}
+
+/// Run `task` as cancellable run `run_id`; without an id it just runs.
+/// A cancelled run ends with `Ok(None)` after emitting
+/// `{event_prefix}-cancelled`.
+pub async fn cancellable<F, T>(
+    app: &AppHandle,
+    run_id: Option<String>,
+    event_prefix: &str,
+    task: F,
+) -> Result<Option<T>, String>
+where
+    F: Future<Output = Result<T, String>>,
+{
+    let Some(run_id) = run_id else {
+        return task.await.map(Some);
+    };
+
+    let (_registration, abort_registration) = Registration::new(&run_id)?;
+    match Abortable::new(task, abort_registration).await {
+        Ok(result) => result.map(Some),
+        Err(_) => {
+            tracing::info!("Agent run '{}' cancelled", run_id);
+            let _ = app.emit(&format!("{}-cancelled", event_prefix), AgentRunCancelled { run_id });
+            Ok(None)
+        }
+    }
+}
+
+/// Abort run `run_id`; false if no such run is in progress
+fn cancel(run_id: &str) -> Result<bool, String> {
+    let runs = RUNS.lock().map_err(|e| e.to_string())?;
+    match runs.get(run_id) {
+        Some(handle) => {
+            handle.abort();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Cancel a streaming agent run; returns false if it already finished
+#[tauri::command]
+pub async fn cancel_agent_run(run_id: String) -> Result<bool, String> {
+    cancel(&run_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_aborts_and_unregisters() {
+        let (registration, abort_registration) = Registration::new("run-1").unwrap();
+        assert!(Registration::new("run-1").is_err());
+
+        let task = Abortable::new(std::future::pending::<()>(), abort_registration);
+        assert!(cancel("run-1").unwrap());
+        assert!(task.await.is_err());
+
+        drop(registration);
+        assert!(!cancel("run-1").unwrap());
+    }
+}
@@ -1,8 +1,8 @@
-use futures_util::StreamExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
 
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+use super::agent::{Agent, Answer, PromptPart};
+use crate::llm::{ModelConfig, OutputSchema};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToneShiftRequest {
@@ -11,7 +11,7 @@ pub struct ToneShiftRequest {
     pub context: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ToneType {
     Professional,
@@ -39,7 +39,7 @@ impl ToneType {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ToneShiftResult {
     pub original: String,
     pub shifted: String,
@@ -47,24 +47,6 @@ pub struct ToneShiftResult {
     pub suggestions: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct ToneShiftChunk {
-    pub text: String,
-    pub is_complete: bool,
-}
-
-#[derive(Debug, Deserialize)]
-struct AnthropicStreamEvent {
-    #[serde(rename = "type")]
-    event_type: String,
-    delta: Option<AnthropicDelta>,
-}
-
-#[derive(Debug, Deserialize)]
-struct AnthropicDelta {
-    text: Option<String>,
-}
-
 fn build_system_prompt(tone: &ToneType, intensity: u8, length_adjustment: i8) -> String {
     let intensity_desc = match intensity {
         1..=3 => "subtle",
@@ -118,182 +100,85 @@ Respond with ONLY the rewritten text, no explanations or preamble."#,
     )
 }
 
-#[tauri::command]
-pub async fn shift_tone(
-    app: AppHandle,
-    api_key: String,
-    text: String,
-    target_tone: ToneType,
-    intensity: Option<u8>,
-    length_adjustment: Option<i8>,
-) -> Result<ToneShiftResult, String> {
-    let intensity = intensity.unwrap_or(5).clamp(1, 10);
-    let length_adjustment = length_adjustment.unwrap_or(0).clamp(-50, 100);
-    let client = reqwest::Client::new();
-
-    let length_instruction = if length_adjustment != 0 {
-        format!(" Adjust length by {}%.", if length_adjustment > 0 { format!("+{}", length_adjustment) } else { length_adjustment.to_string() })
-    } else {
-        String::new()
-    };
-
-    let request_body = serde_json::json!({
-        "model": "claude-sonnet-4-20250514",
-        "max_tokens": 4096,
-        "system": build_system_prompt(&target_tone, intensity, length_adjustment),
-        "messages": [
-            {
-                "role": "user",
-                "content": format!("Rewrite this text in a {} tone (intensity {}/10).{}\n\n{}", target_tone.description(), intensity, length_instruction, text)
-            }
-        ]
-    });
-
-    let response = client
-        .post(ANTHROPIC_API_URL)
-        .header("x-api-key", &api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToneShiftInput {
+    pub text: String,
+    pub target_tone: ToneType,
+    /// 1-10, default 5
+    pub intensity: Option<u8>,
+    /// Percent, -50 to +100, default 0
+    pub length_adjustment: Option<i8>,
+}
 
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("Anthropic API error: {}", error_text));
+impl ToneShiftInput {
+    fn intensity(&self) -> u8 {
+        self.intensity.unwrap_or(5).clamp(1, 10)
     }
 
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    let shifted_text = response_json["content"][0]["text"]
-        .as_str()
-        .unwrap_or("")
-        .to_string();
-
-    let result = ToneShiftResult {
-        original: text,
-        shifted: shifted_text,
-        tone: target_tone,
-        suggestions: vec![],
-    };
-
-    let _ = app.emit("tone-shifted", &result);
-
-    Ok(result)
+    fn length_adjustment(&self) -> i8 {
+        self.length_adjustment.unwrap_or(0).clamp(-50, 100)
+    }
 }
 
-#[tauri::command]
-pub async fn shift_tone_streaming(
-    app: AppHandle,
-    api_key: String,
-    text: String,
-    target_tone: ToneType,
-    intensity: Option<u8>,
-    length_adjustment: Option<i8>,
-) -> Result<(), String> {
-    let intensity = intensity.unwrap_or(5).clamp(1, 10);
-    let length_adjustment = length_adjustment.unwrap_or(0).clamp(-50, 100);
-    let client = reqwest::Client::new();
-
-    let length_instruction = if length_adjustment != 0 {
-        format!(" Adjust length by {}%.", if length_adjustment > 0 { format!("+{}", length_adjustment) } else { length_adjustment.to_string() })
-    } else {
-        String::new()
-    };
-
-    let request_body = serde_json::json!({
-        "model": "claude-sonnet-4-20250514",
-        "max_tokens": 4096,
-        "stream": true,
-        "system": build_system_prompt(&target_tone, intensity, length_adjustment),
-        "messages": [
-            {
-                "role": "user",
-                "content": format!("Rewrite this text in a {} tone (intensity {}/10).{}\n\n{}", target_tone.description(), intensity, length_instruction, text)
-            }
-        ]
-    });
+pub struct ToneShifter;
 
-    let response = client
-        .post(ANTHROPIC_API_URL)
-        .header("x-api-key", &api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+impl Agent for ToneShifter {
+    type Input = ToneShiftInput;
+    type Output = ToneShiftResult;
 
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("Anthropic API error: {}", error_text));
+    fn id(&self) -> &str {
+        "tone-shifter"
     }
 
-    let _ = app.emit("tone-shift-started", ());
-
-    let mut stream = response.bytes_stream();
-    let mut full_text = String::new();
-    let mut buffer = String::new();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
-        let chunk_str = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&chunk_str);
-
-        // Parse SSE events
-        while let Some(event_end) = buffer.find("\n\n") {
-            let event_str = buffer[..event_end].to_string();
-            buffer = buffer[event_end + 2..].to_string();
-
-            for line in event_str.lines() {
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
+    fn name(&self) -> &str {
+        "Tone Shifter"
+    }
 
-                    if let Ok(event) = serde_json::from_str::<AnthropicStreamEvent>(data) {
-                        if event.event_type == "content_block_delta" {
-                            if let Some(delta) = event.delta {
-                                if let Some(text) = delta.text {
-                                    full_text.push_str(&text);
-                                    let _ = app.emit(
-                                        "tone-shift-chunk",
-                                        ToneShiftChunk {
-                                            text: text.clone(),
-                                            is_complete: false,
-                                        },
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    fn description(&self) -> &str {
+        "Rewrite text in a different tone"
     }
 
-    let _ = app.emit(
-        "tone-shift-chunk",
-        ToneShiftChunk {
-            text: String::new(),
-            is_complete: true,
-        },
-    );
+    fn event_prefix(&self) -> &str {
+        "tone-shift"
+    }
 
-    let result = ToneShiftResult {
-        original: text,
-        shifted: full_text,
-        tone: target_tone,
-        suggestions: vec![],
-    };
+    fn output_schema(&self) -> Option<OutputSchema> {
+        None
+    }
 
-    let _ = app.emit("tone-shift-complete", &result);
+    fn prompt(&self, config: &ModelConfig, input: &ToneShiftInput) -> Result<Vec<PromptPart>, String> {
+        let (intensity, length_adjustment) = (input.intensity(), input.length_adjustment());
+        let length_instruction = if length_adjustment != 0 {
+            format!(" Adjust length by {}%.", if length_adjustment > 0 { format!("+{}", length_adjustment) } else { length_adjustment.to_string() })
+        } else {
+            String::new()
+        };
+
+        Ok(vec![PromptPart::Ask(
+            config
+                .request()
+                .with_system(build_system_prompt(&input.target_tone, intensity, length_adjustment))
+                .with_user(format!(
+                    "Rewrite this text in a {} tone (intensity {}/10).{}\n\n{}",
+                    input.target_tone.description(),
+                    intensity,
+                    length_instruction,
+                    input.text
+                )),
+        )])
+    }
 
-    Ok(())
+    fn post_process(&self, input: ToneShiftInput, answer: Answer<ToneShiftResult>) -> Result<ToneShiftResult, String> {
+        let Answer::Text(shifted) = answer else {
+            return Err("Tone Shifter answers in plain text".to_string());
+        };
+        Ok(ToneShiftResult {
+            original: input.text,
+            shifted,
+            tone: input.target_tone,
+            suggestions: vec![],
+        })
+    }
 }
 
 #[tauri::command]
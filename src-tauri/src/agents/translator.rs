@@ -1,11 +1,11 @@
-use futures_util::StreamExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
 
-const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
+use super::agent::{Agent, Answer, PromptPart};
+use crate::llm::{self, ModelConfig, OutputSchema};
 
 /// Supported languages for translation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum Language {
     #[serde(rename = "en")]
     English,
@@ -73,34 +73,16 @@ impl Language {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TranslationResult {
     pub original: String,
     pub translated: String,
     pub source_language: String,
     pub target_language: String,
     pub detected_language: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize)]
-pub struct TranslationChunk {
-    pub text: String,
-    pub is_complete: bool,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAIStreamChunk {
-    choices: Vec<OpenAIStreamChoice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAIStreamChoice {
-    delta: OpenAIDelta,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAIDelta {
-    content: Option<String>,
+    /// Segments passed through because they were already in the target language
+    #[serde(default)]
+    pub skipped_segments: usize,
 }
 
 fn build_system_prompt(source_lang: &Language, target_lang: &Language) -> String {
@@ -128,179 +110,149 @@ Guidelines:
     )
 }
 
-/// Non-streaming translation
-#[tauri::command]
-pub async fn translate_text(
-    app: AppHandle,
-    api_key: String,
-    text: String,
-    source_language: Language,
-    target_language: Language,
-) -> Result<TranslationResult, String> {
-    let client = reqwest::Client::new();
-
-    let request_body = serde_json::json!({
-        "model": "gpt-4o",
-        "messages": [
-            {
-                "role": "system",
-                "content": build_system_prompt(&source_language, &target_language)
-            },
-            {
-                "role": "user",
-                "content": text
+/// A transcript segment with the language it was spoken in
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct SourceSegment {
+    pub text: String,
+    /// ISO 639-1 code; `None` if the recognizer didn't report one
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// A run of consecutive segments that is either translated or kept as-is
+#[derive(Debug, PartialEq)]
+enum Passage {
+    /// Already in the target language
+    Keep(String),
+    Translate(String),
+}
+
+/// Group segments into passages, so code-switched transcripts only send the
+/// parts that aren't in the target language yet
+fn plan_passages(segments: &[SourceSegment], target_language: &Language) -> Vec<Passage> {
+    let mut passages: Vec<Passage> = Vec::new();
+
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let keep = segment.language.as_deref() == Some(target_language.code());
+
+        match passages.last_mut() {
+            Some(Passage::Keep(run)) if keep => {
+                run.push(' ');
+                run.push_str(text);
+            }
+            Some(Passage::Translate(run)) if !keep => {
+                run.push(' ');
+                run.push_str(text);
             }
-        ],
-        "temperature": 0.3
-    });
-
-    let response = client
-        .post(OPENAI_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
+            _ if keep => passages.push(Passage::Keep(text.to_string())),
+            _ => passages.push(Passage::Translate(text.to_string())),
+        }
     }
 
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    let translated_text = response_json["choices"][0]["message"]["content"]
-        .as_str()
-        .unwrap_or("")
-        .to_string();
-
-    let result = TranslationResult {
-        original: text,
-        translated: translated_text,
-        source_language: source_language.code().to_string(),
-        target_language: target_language.code().to_string(),
-        detected_language: if matches!(source_language, Language::Auto) {
-            Some("auto-detected".to_string())
-        } else {
-            None
-        },
-    };
+    passages
+}
 
-    let _ = app.emit("translation-complete", &result);
+/// The whole text as one passage, unless per-segment languages are known
+fn passages_for(
+    text: &str,
+    segments: Option<&[SourceSegment]>,
+    target_language: &Language,
+) -> Vec<Passage> {
+    match segments {
+        Some(segments) if !segments.is_empty() => plan_passages(segments, target_language),
+        _ => vec![Passage::Translate(text.to_string())],
+    }
+}
 
-    Ok(result)
+fn skipped_segments(segments: Option<&[SourceSegment]>, target_language: &Language) -> usize {
+    segments
+        .unwrap_or_default()
+        .iter()
+        .filter(|s| s.language.as_deref() == Some(target_language.code()))
+        .count()
 }
 
-/// Streaming translation with real-time chunks
-#[tauri::command]
-pub async fn translate_text_streaming(
-    app: AppHandle,
-    api_key: String,
-    text: String,
-    source_language: Language,
-    target_language: Language,
-) -> Result<(), String> {
-    let client = reqwest::Client::new();
-
-    let request_body = serde_json::json!({
-        "model": "gpt-4o",
-        "messages": [
-            {
-                "role": "system",
-                "content": build_system_prompt(&source_language, &target_language)
-            },
-            {
-                "role": "user",
-                "content": text
-            }
-        ],
-        "temperature": 0.3,
-        "stream": true
-    });
-
-    let response = client
-        .post(OPENAI_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
+fn translation_request(config: &ModelConfig, text: &str, source_language: &Language, target_language: &Language) -> llm::ChatRequest {
+    config
+        .request()
+        .with_system(build_system_prompt(source_language, target_language))
+        .with_user(text)
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct TranslationInput {
+    pub text: String,
+    pub source_language: Language,
+    pub target_language: Language,
+    /// With per-segment languages, parts already spoken in the target
+    /// language are kept verbatim and only the rest is translated
+    #[serde(default)]
+    pub segments: Option<Vec<SourceSegment>>,
+}
+
+pub struct Translator;
+
+impl Agent for Translator {
+    type Input = TranslationInput;
+    type Output = TranslationResult;
+
+    fn id(&self) -> &str {
+        "translator"
     }
 
-    let _ = app.emit("translation-started", ());
-
-    let mut stream = response.bytes_stream();
-    let mut full_text = String::new();
-    let mut buffer = String::new();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
-        let chunk_str = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&chunk_str);
-
-        // Parse SSE events (data: {...}\n\n format)
-        while let Some(event_end) = buffer.find("\n\n") {
-            let event_str = buffer[..event_end].to_string();
-            buffer = buffer[event_end + 2..].to_string();
-
-            for line in event_str.lines() {
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
-
-                    if let Ok(chunk_data) = serde_json::from_str::<OpenAIStreamChunk>(data) {
-                        if let Some(choice) = chunk_data.choices.first() {
-                            if let Some(content) = &choice.delta.content {
-                                full_text.push_str(content);
-                                let _ = app.emit(
-                                    "translation-chunk",
-                                    TranslationChunk {
-                                        text: content.clone(),
-                                        is_complete: false,
-                                    },
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    fn name(&self) -> &str {
+        "Translator"
     }
 
-    // Emit completion marker
-    let _ = app.emit(
-        "translation-chunk",
-        TranslationChunk {
-            text: String::new(),
-            is_complete: true,
-        },
-    );
-
-    let result = TranslationResult {
-        original: text,
-        translated: full_text,
-        source_language: source_language.code().to_string(),
-        target_language: target_language.code().to_string(),
-        detected_language: if matches!(source_language, Language::Auto) {
-            Some("auto-detected".to_string())
-        } else {
-            None
-        },
-    };
+    fn description(&self) -> &str {
+        "Translate to another language"
+    }
+
+    fn event_prefix(&self) -> &str {
+        "translation"
+    }
 
-    let _ = app.emit("translation-complete", &result);
+    fn output_schema(&self) -> Option<OutputSchema> {
+        None
+    }
+
+    fn prompt(&self, config: &ModelConfig, input: &TranslationInput) -> Result<Vec<PromptPart>, String> {
+        let parts = passages_for(&input.text, input.segments.as_deref(), &input.target_language)
+            .into_iter()
+            .map(|passage| match passage {
+                Passage::Keep(kept) => PromptPart::Verbatim(kept),
+                Passage::Translate(original) => PromptPart::Ask(translation_request(
+                    config,
+                    &original,
+                    &input.source_language,
+                    &input.target_language,
+                )),
+            })
+            .collect();
+        Ok(parts)
+    }
 
-    Ok(())
+    fn post_process(&self, input: TranslationInput, answer: Answer<TranslationResult>) -> Result<TranslationResult, String> {
+        let Answer::Text(translated) = answer else {
+            return Err("Translator answers in plain text".to_string());
+        };
+        Ok(TranslationResult {
+            translated,
+            source_language: input.source_language.code().to_string(),
+            target_language: input.target_language.code().to_string(),
+            detected_language: if matches!(input.source_language, Language::Auto) {
+                Some("auto-detected".to_string())
+            } else {
+                None
+            },
+            skipped_segments: skipped_segments(input.segments.as_deref(), &input.target_language),
+            original: input.text,
+        })
+    }
 }
 
 /// Get list of available languages for translation
@@ -322,3 +274,53 @@ pub fn get_available_languages() -> Vec<serde_json::Value> {
         serde_json::json!({ "code": "ar", "name": "Arabic", "isSource": true }),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, language: Option<&str>) -> SourceSegment {
+        SourceSegment {
+            text: text.to_string(),
+            language: language.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_segments_in_target_language_are_kept() {
+        let segments = [
+            segment("Guten Morgen zusammen.", Some("de")),
+            segment("Let's look at the release.", Some("en")),
+            segment("The build is green.", Some("en")),
+            segment("Super, danke.", Some("de")),
+            segment("Okay.", None),
+        ];
+        assert_eq!(
+            plan_passages(&segments, &Language::English),
+            vec![
+                Passage::Translate("Guten Morgen zusammen.".to_string()),
+                Passage::Keep("Let's look at the release. The build is green.".to_string()),
+                Passage::Translate("Super, danke. Okay.".to_string()),
+            ]
+        );
+        assert_eq!(skipped_segments(Some(&segments), &Language::English), 2);
+    }
+
+    #[test]
+    fn test_without_segments_everything_is_translated() {
+        assert_eq!(
+            passages_for("Hallo", None, &Language::English),
+            vec![Passage::Translate("Hallo".to_string())]
+        );
+        assert_eq!(
+            passages_for("Hallo", Some(&[]), &Language::English),
+            vec![Passage::Translate("Hallo".to_string())]
+        );
+
+        let all_english = [segment("Hello.", Some("en"))];
+        assert_eq!(
+            passages_for("Hello.", Some(&all_english), &Language::English),
+            vec![Passage::Keep("Hello.".to_string())]
+        );
+    }
+}
@@ -4,7 +4,7 @@ use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager};
 use once_cell::sync::Lazy;
 
-use crate::transcription::TranscriptionManager;
+use crate::transcription::session::AudioIngress;
 
 const TARGET_SAMPLE_RATE: u32 = 16000;
 const VAD_THRESHOLD: f32 = 0.02;
@@ -116,8 +116,8 @@ fn run_audio_capture(app: AppHandle) -> Result<(), String> {
     let buffer_clone = buffer.clone();
     let channels = config.channels as usize;
 
-    // Get transcription state for direct audio forwarding
-    let transcription_state: TranscriptionManager = app.state::<TranscriptionManager>().inner().clone();
+    // Lock-free route to the live transcription session
+    let ingress: AudioIngress = app.state::<AudioIngress>().inner().clone();
 
     // Calculate chunk size based on actual sample rate (~100ms of audio)
     let chunk_size = (actual_sample_rate as usize) / 10; // 100ms worth of samples
@@ -170,12 +170,8 @@ fn run_audio_capture(app: AppHandle) -> Result<(), String> {
                             rec_buffer.extend(resampled.iter());
                         }
 
-                        // Send directly to Deepgram (bypassing frontend JSON serialization)
-                        if let Ok(state) = transcription_state.try_lock() {
-                            if state.is_streaming {
-                                let _ = state.send_audio_direct(resampled.clone());
-                            }
-                        }
+                        // Send directly to the live session (bypassing frontend JSON serialization)
+                        ingress.push(resampled.clone());
 
                         // Also emit for frontend visualization (but not for transcription)
                         let _ = app_clone.emit(
@@ -288,6 +284,14 @@ pub fn save_recording(app: AppHandle, filepath: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Copy of the audio recorded so far (16kHz mono)
+pub fn recorded_samples() -> Result<Vec<i16>, String> {
+    let buffer = RECORDING_BUFFER
+        .lock()
+        .map_err(|_| "Failed to lock recording buffer")?;
+    Ok(buffer.clone())
+}
+
 /// Check if there's recorded audio available to save
 #[tauri::command]
 pub fn has_recording() -> Result<bool, String> {
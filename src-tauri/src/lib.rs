@@ -1,6 +1,8 @@
 pub mod agents;
+pub mod llm;
 pub mod platform;
 pub mod secrets;
+pub mod storage;
 pub mod sync;
 pub mod transcription;
 
@@ -10,6 +12,9 @@ pub mod audio;
 #[cfg(not(any(target_os = "ios", target_os = "android")))]
 pub mod tts;
 
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub mod whisper;
+
 use std::sync::Arc;
 use tauri::Manager;
 use sync::SyncManager;
@@ -32,8 +37,17 @@ pub fn run() {
             // Initialize transcription state
             let transcription_state: TranscriptionManager =
                 Arc::new(tokio::sync::Mutex::new(transcription::TranscriptionState::default()));
+            app.manage(transcription::session::AudioIngress::start(transcription_state.clone()));
             app.manage(transcription_state);
 
+            // Local Whisper model is loaded lazily and kept for the app session
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            {
+                let whisper_state: whisper::WhisperManager =
+                    Arc::new(tokio::sync::Mutex::new(whisper::WhisperEngine::default()));
+                app.manage(whisper_state);
+            }
+
             // Initialize sync state (ephemeral — wiped on drop)
             let sync_state: SyncManager =
                 Arc::new(tokio::sync::Mutex::new(sync::SyncState::default()));
@@ -108,37 +122,89 @@ pub fn run() {
             transcription::stop_deepgram_stream,
             transcription::send_audio_to_deepgram,
             transcription::is_deepgram_streaming,
-            transcription::transcribe_with_assemblyai,
+            transcription::session::get_transcription_stats,
+            transcription::assemblyai::transcribe_with_assemblyai,
+            transcription::assemblyai::cancel_transcription_job,
+            transcription::openai_compatible::get_openai_stt_config,
+            transcription::openai_compatible::set_openai_stt_config,
+            transcription::openai_compatible::transcribe_with_openai_compatible,
+            transcription::vocabulary::get_vocabulary,
+            transcription::vocabulary::set_vocabulary,
+            transcription::language::get_language_config,
+            transcription::language::set_language_config,
+            transcription::postprocess::get_postprocess_config,
+            transcription::postprocess::set_postprocess_config,
+            transcription::postprocess::process_transcript,
+            transcription::postprocess::rehydrate_text,
+            transcription::usage::get_usage_config,
+            transcription::usage::set_usage_config,
+            transcription::usage::get_usage_report,
+            transcription::export::export_transcript,
             #[cfg(not(any(target_os = "ios", target_os = "android")))]
             transcription::transcribe_local_whisper,
-            // Action Items agent
-            agents::action_items::extract_action_items,
-            agents::action_items::extract_action_items_streaming,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::streaming::start_local_whisper_stream,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::streaming::stop_local_whisper_stream,
+            // Hybrid cloud/local transcription policy
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            transcription::hybrid::get_hybrid_config,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            transcription::hybrid::set_hybrid_config,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            transcription::hybrid::start_hybrid_transcription,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            transcription::hybrid::stop_hybrid_transcription,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            transcription::hybrid::retranscribe_recording,
+            // Whisper model manager
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::models::list_whisper_models,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::models::download_whisper_model,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::models::set_active_whisper_model,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::models::verify_whisper_model,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::models::import_whisper_model,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::models::delete_whisper_model,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::models::get_whisper_models_disk_usage,
+            // Whisper tuning and performance
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::settings::get_whisper_settings,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::settings::set_whisper_settings,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            whisper::get_whisper_metrics,
+            // Agents
+            agents::registry::list_agents,
+            agents::registry::run_agent,
+            agents::custom::get_custom_agents,
+            agents::runs::cancel_agent_run,
+            agents::pipeline::list_pipelines,
+            agents::pipeline::save_pipeline,
+            agents::pipeline::delete_pipeline,
+            agents::pipeline::run_pipeline,
             // Tone Shifter agent
-            agents::tone_shifter::shift_tone,
-            agents::tone_shifter::shift_tone_streaming,
             agents::tone_shifter::get_available_tones,
             agents::tone_shifter::get_tone_presets,
             // Music Matcher agent
             agents::music_matcher::match_music,
-            agents::music_matcher::analyze_mood_from_transcript,
             agents::music_matcher::get_available_moods,
             agents::music_matcher::get_available_genres,
             // Translator agent
-            agents::translator::translate_text,
-            agents::translator::translate_text_streaming,
             agents::translator::get_available_languages,
-            // Dev-Log agent
-            agents::dev_log::generate_dev_log,
-            agents::dev_log::generate_dev_log_streaming,
-            // Brain Dump agent
-            agents::brain_dump::process_brain_dump,
-            agents::brain_dump::process_brain_dump_streaming,
             // Mental Mirror agent (Letter to Myself)
-            agents::mental_mirror::generate_mental_mirror,
-            agents::mental_mirror::generate_mental_mirror_streaming,
             agents::mental_mirror::schedule_mental_mirror_email,
             agents::mental_mirror::export_letter_to_file,
+            // LLM providers
+            agents::config::get_agent_config,
+            agents::config::set_agent_config,
+            llm::local::get_local_llm_config,
+            llm::local::set_local_llm_config,
             // Text-to-Speech (desktop only)
             #[cfg(not(any(target_os = "ios", target_os = "android")))]
             tts::speak_text,
@@ -0,0 +1,221 @@
+//! Anthropic Messages provider.
+
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+
+use super::{http_client, read_sse, send_checked, ChatRequest, LlmError, LlmProvider, ProviderKind, Role};
+
+const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// The Messages API requires a limit
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+/// Structured answers are requested as a call to this tool
+const ANSWER_TOOL: &str = "answer";
+/// There is no JSON mode, so ask for it in the system prompt
+const JSON_INSTRUCTION: &str =
+    "Respond with a single valid JSON object only, without Markdown code fences or explanations.";
+
+pub struct AnthropicProvider {
+    api_key: String,
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    text: Option<String>,
+    /// Arguments of a tool call, i.e. the structured answer
+    input: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    ContentBlockDelta { delta: Delta },
+    Error { error: ErrorBody },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    text: Option<String>,
+    /// Streamed tool call arguments
+    partial_json: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            http: http_client(),
+        }
+    }
+
+    async fn send(&self, request: &ChatRequest, stream: bool) -> Result<reqwest::Response, LlmError> {
+        let builder = self
+            .http
+            .post(ANTHROPIC_API_URL)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("Content-Type", "application/json")
+            .json(&request_body(request, stream));
+        send_checked(ProviderKind::Anthropic, builder, request).await
+    }
+}
+
+fn request_body(request: &ChatRequest, stream: bool) -> serde_json::Value {
+    let messages: Vec<serde_json::Value> = request
+        .messages
+        .iter()
+        .map(|message| {
+            let role = match message.role {
+                Role::User => "user",
+                Role::Assistant => "assistant",
+            };
+            serde_json::json!({ "role": role, "content": message.content })
+        })
+        .collect();
+
+    let mut body = serde_json::json!({
+        "model": request.model,
+        "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+        "messages": messages,
+    });
+
+    // With a schema the answer is forced through a tool whose input is the
+    // schema, which Anthropic enforces
+    if let Some(schema) = &request.schema {
+        body["tools"] = serde_json::json!([{
+            "name": ANSWER_TOOL,
+            "description": format!("Return the {}", schema.name),
+            "input_schema": schema.schema,
+        }]);
+        body["tool_choice"] = serde_json::json!({ "type": "tool", "name": ANSWER_TOOL });
+    }
+
+    let system = match (&request.system, request.json && request.schema.is_none()) {
+        (Some(system), true) => Some(format!("{}\n\n{}", system, JSON_INSTRUCTION)),
+        (Some(system), false) => Some(system.clone()),
+        (None, true) => Some(JSON_INSTRUCTION.to_string()),
+        (None, false) => None,
+    };
+    if let Some(system) = system {
+        body["system"] = system.into();
+    }
+    if let Some(temperature) = request.temperature {
+        body["temperature"] = temperature.into();
+    }
+    if stream {
+        body["stream"] = true.into();
+    }
+    body
+}
+
+/// Text carried by one streamed event; error events end the stream
+fn parse_event(data: &str) -> Result<Option<String>, LlmError> {
+    match serde_json::from_str::<StreamEvent>(data) {
+        Ok(StreamEvent::ContentBlockDelta { delta }) => Ok(delta.text.or(delta.partial_json)),
+        Ok(StreamEvent::Error { error }) => Err(LlmError::new(format!("Anthropic API error: {}", error.message))),
+        Ok(StreamEvent::Other) | Err(_) => Ok(None),
+    }
+}
+
+impl LlmProvider for AnthropicProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Anthropic
+    }
+
+    fn chat<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String, LlmError>> {
+        Box::pin(async move {
+            let response: MessagesResponse = self
+                .send(request, false)
+                .await?
+                .json()
+                .await
+                .map_err(|e| LlmError::new(format!("Failed to parse response: {}", e)))?;
+
+            let text: String = response
+                .content
+                .into_iter()
+                .filter_map(|block| block.text.or_else(|| block.input.map(|input| input.to_string())))
+                .collect();
+            if text.is_empty() {
+                return Err(LlmError::new("No content in response"));
+            }
+            Ok(text)
+        })
+    }
+
+    fn chat_stream<'a>(
+        &'a self,
+        request: &'a ChatRequest,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+    ) -> BoxFuture<'a, Result<String, LlmError>> {
+        Box::pin(async move {
+            let response = self.send(request, true).await?;
+            let mut full_text = String::new();
+            read_sse(response, |event| {
+                if let Some(text) = parse_event(&event.data)? {
+                    full_text.push_str(&text);
+                    on_delta(&text);
+                }
+                Ok(())
+            })
+            .await?;
+            Ok(full_text)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::OutputSchema;
+
+    #[test]
+    fn test_request_body() {
+        let request = ChatRequest::new("claude-sonnet-4-20250514")
+            .with_system("system prompt")
+            .with_user("hello");
+        let body = request_body(&request, true);
+        assert_eq!(body["system"], "system prompt");
+        assert_eq!(body["max_tokens"], DEFAULT_MAX_TOKENS);
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["stream"], true);
+
+        let body = request_body(&request.clone().with_json(), false);
+        assert!(body["system"].as_str().unwrap().ends_with(JSON_INSTRUCTION));
+        assert!(body.get("stream").is_none());
+
+        let schema = OutputSchema {
+            name: "Mood".to_string(),
+            schema: serde_json::json!({ "type": "object" }),
+        };
+        let body = request_body(&request.with_schema(schema), false);
+        assert_eq!(body["system"], "system prompt");
+        assert_eq!(body["tools"][0]["input_schema"]["type"], "object");
+        assert_eq!(body["tool_choice"]["name"], ANSWER_TOOL);
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let delta = r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi"}}"#;
+        assert_eq!(parse_event(delta).unwrap().as_deref(), Some("Hi"));
+        assert_eq!(parse_event(r#"{"type":"message_stop"}"#).unwrap(), None);
+        let tool_delta = r#"{"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":"{\"mood"}}"#;
+        assert_eq!(parse_event(tool_delta).unwrap().as_deref(), Some("{\"mood"));
+
+        let error = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        assert!(parse_event(error).unwrap_err().message.contains("Overloaded"));
+    }
+}
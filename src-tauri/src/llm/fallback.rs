@@ -0,0 +1,193 @@
+//! Secondary provider for when the primary is rate limited or down.
+
+use futures_util::future::BoxFuture;
+
+use super::{ChatRequest, LlmError, LlmProvider, ProviderKind};
+
+/// Sends every request to `primary` and retries it once on `fallback` (with
+/// the fallback's model) when the primary answers 429 or 5xx
+pub struct FallbackProvider {
+    primary: Box<dyn LlmProvider>,
+    fallback: Box<dyn LlmProvider>,
+    fallback_model: String,
+}
+
+impl FallbackProvider {
+    pub fn new(primary: Box<dyn LlmProvider>, fallback: Box<dyn LlmProvider>, fallback_model: &str) -> Self {
+        Self {
+            primary,
+            fallback,
+            fallback_model: fallback_model.to_string(),
+        }
+    }
+
+    fn fallback_request(&self, request: &ChatRequest, error: &LlmError) -> ChatRequest {
+        tracing::warn!(
+            "{} failed ({}), retrying on {} ({})",
+            self.primary.kind().label(),
+            error,
+            self.fallback.kind().label(),
+            self.fallback_model
+        );
+        ChatRequest {
+            model: self.fallback_model.clone(),
+            ..request.clone()
+        }
+    }
+}
+
+impl LlmProvider for FallbackProvider {
+    fn kind(&self) -> ProviderKind {
+        self.primary.kind()
+    }
+
+    fn chat<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String, LlmError>> {
+        Box::pin(async move {
+            match self.primary.chat(request).await {
+                Err(error) if error.is_retryable() => {
+                    let request = self.fallback_request(request, &error);
+                    self.fallback.chat(&request).await
+                }
+                result => result,
+            }
+        })
+    }
+
+    fn chat_stream<'a>(
+        &'a self,
+        request: &'a ChatRequest,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+    ) -> BoxFuture<'a, Result<String, LlmError>> {
+        Box::pin(async move {
+            // Once text has reached the caller, switching providers would
+            // splice two different answers together
+            let mut emitted = false;
+            let result = {
+                let mut tracked = |text: &str| {
+                    emitted = true;
+                    on_delta(text);
+                };
+                self.primary.chat_stream(request, &mut tracked).await
+            };
+            match result {
+                Err(error) if error.is_retryable() && !emitted => {
+                    let request = self.fallback_request(request, &error);
+                    self.fallback.chat_stream(&request, on_delta).await
+                }
+                result => result,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Streams `deltas`, then fails with `status` if set
+    struct MockProvider {
+        kind: ProviderKind,
+        deltas: Vec<&'static str>,
+        status: Option<u16>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl MockProvider {
+        fn new(kind: ProviderKind, deltas: Vec<&'static str>, status: Option<u16>) -> (Self, Arc<AtomicUsize>) {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let provider = Self {
+                kind,
+                deltas,
+                status,
+                calls: calls.clone(),
+            };
+            (provider, calls)
+        }
+
+        fn result(&self, request: &ChatRequest) -> Result<String, LlmError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.status {
+                Some(status) => Err(LlmError {
+                    status: Some(status),
+                    retry_after: None,
+                    message: format!("{} failed", request.model),
+                }),
+                None => Ok(format!("{}:{}", request.model, self.deltas.concat())),
+            }
+        }
+    }
+
+    impl LlmProvider for MockProvider {
+        fn kind(&self) -> ProviderKind {
+            self.kind
+        }
+
+        fn chat<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String, LlmError>> {
+            Box::pin(async move { self.result(request) })
+        }
+
+        fn chat_stream<'a>(
+            &'a self,
+            request: &'a ChatRequest,
+            on_delta: &'a mut (dyn FnMut(&str) + Send),
+        ) -> BoxFuture<'a, Result<String, LlmError>> {
+            Box::pin(async move {
+                for delta in &self.deltas {
+                    on_delta(delta);
+                }
+                self.result(request)
+            })
+        }
+    }
+
+    fn fallback_provider(primary_status: Option<u16>, primary_deltas: Vec<&'static str>) -> (FallbackProvider, Arc<AtomicUsize>) {
+        let (primary, _) = MockProvider::new(ProviderKind::OpenAi, primary_deltas, primary_status);
+        let (fallback, fallback_calls) = MockProvider::new(ProviderKind::Anthropic, vec!["ok"], None);
+        let provider = FallbackProvider::new(Box::new(primary), Box::new(fallback), "claude-haiku");
+        (provider, fallback_calls)
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_on_retryable_errors() {
+        let request = ChatRequest::new("gpt-4o");
+        for status in [429, 500, 503] {
+            let (provider, fallback_calls) = fallback_provider(Some(status), vec![]);
+            assert_eq!(provider.chat(&request).await.unwrap(), "claude-haiku:ok");
+            assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+        }
+
+        let (provider, fallback_calls) = fallback_provider(Some(401), vec![]);
+        assert_eq!(provider.chat(&request).await.unwrap_err().status, Some(401));
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 0);
+
+        let (provider, fallback_calls) = fallback_provider(None, vec!["hi"]);
+        assert_eq!(provider.chat(&request).await.unwrap(), "gpt-4o:hi");
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_stream_falls_back_only_before_output() {
+        let request = ChatRequest::new("gpt-4o");
+
+        let (provider, _) = fallback_provider(Some(429), vec![]);
+        let mut received = String::new();
+        let text = provider
+            .chat_stream(&request, &mut |delta: &str| received.push_str(delta))
+            .await
+            .unwrap();
+        assert_eq!(text, "claude-haiku:ok");
+        assert_eq!(received, "ok");
+
+        let (provider, fallback_calls) = fallback_provider(Some(500), vec!["partial"]);
+        let mut received = String::new();
+        let error = provider
+            .chat_stream(&request, &mut |delta: &str| received.push_str(delta))
+            .await
+            .unwrap_err();
+        assert_eq!(error.status, Some(500));
+        assert_eq!(received, "partial");
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 0);
+    }
+}
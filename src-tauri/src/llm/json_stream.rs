@@ -0,0 +1,301 @@
+//! Incremental parser for JSON answers that arrive in pieces.
+//!
+//! The scanner tracks strings and nesting as text streams in and reports
+//! every value the moment its closing quote, bracket or delimiter arrives,
+//! together with its path in the document. Agents use it to emit typed
+//! items (a task, a ticket) long before the whole answer is complete.
+//!
+//! Text before the first `{` or `[` (such as a Markdown code fence) and
+//! after the document ends is ignored.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// A value that has been fully received
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletedValue {
+    pub path: Vec<PathSegment>,
+    pub value: Value,
+}
+
+impl CompletedValue {
+    /// The value is member `key` of the root object
+    pub fn is_field(&self, key: &str) -> bool {
+        matches!(self.path.as_slice(), [PathSegment::Key(k)] if k == key)
+    }
+
+    /// The value is an element of the array in member `key` of the root
+    pub fn is_element_of(&self, key: &str) -> bool {
+        matches!(self.path.as_slice(), [PathSegment::Key(k), PathSegment::Index(_)] if k == key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Container {
+    Object,
+    Array,
+}
+
+#[derive(Debug)]
+struct Frame {
+    container: Container,
+    /// Where this container sits in its parent; `None` for the root
+    segment: Option<PathSegment>,
+    start: usize,
+    /// Objects: the next string is a member name
+    expecting_key: bool,
+    /// Objects: name of the member whose value comes next
+    key: Option<String>,
+    /// Arrays: index of the current element
+    index: usize,
+}
+
+impl Frame {
+    /// Segment of the value currently being read in this container
+    fn current_segment(&self) -> Option<PathSegment> {
+        match self.container {
+            Container::Object => self.key.clone().map(PathSegment::Key),
+            Container::Array => Some(PathSegment::Index(self.index)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonStream {
+    buffer: String,
+    /// Bytes of `buffer` already scanned
+    scanned: usize,
+    stack: Vec<Frame>,
+    /// Values nested deeper than this are not reported on their own
+    max_depth: usize,
+    string_start: Option<usize>,
+    escaped: bool,
+    scalar_start: Option<usize>,
+    done: bool,
+}
+
+impl JsonStream {
+    /// Report values whose path has at most `max_depth` segments
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            buffer: String::new(),
+            scanned: 0,
+            stack: Vec::new(),
+            max_depth,
+            string_start: None,
+            escaped: false,
+            scalar_start: None,
+            done: false,
+        }
+    }
+
+    /// Feed the next piece of text, returning the values it completed
+    pub fn push(&mut self, text: &str) -> Vec<CompletedValue> {
+        self.buffer.push_str(text);
+        let mut completed = Vec::new();
+
+        while self.scanned < self.buffer.len() && !self.done {
+            let i = self.scanned;
+            let byte = self.buffer.as_bytes()[i];
+            self.scanned += 1;
+
+            if let Some(start) = self.string_start {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.string_start = None;
+                    self.end_string(start, i + 1, &mut completed);
+                }
+                continue;
+            }
+
+            if self.stack.is_empty() {
+                if matches!(byte, b'{' | b'[') {
+                    self.open(byte, i, None);
+                }
+                continue;
+            }
+
+            if let Some(start) = self.scalar_start {
+                if matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace() {
+                    self.scalar_start = None;
+                    self.complete(start, i, &mut completed);
+                } else {
+                    continue;
+                }
+            }
+
+            match byte {
+                b'"' => self.string_start = Some(i),
+                b'{' | b'[' => {
+                    let segment = self.stack.last().and_then(Frame::current_segment);
+                    self.open(byte, i, segment);
+                }
+                b'}' | b']' => self.close(i, &mut completed),
+                b':' => {
+                    if let Some(frame) = self.stack.last_mut() {
+                        frame.expecting_key = false;
+                    }
+                }
+                b',' => {
+                    if let Some(frame) = self.stack.last_mut() {
+                        match frame.container {
+                            Container::Object => {
+                                frame.expecting_key = true;
+                                frame.key = None;
+                            }
+                            Container::Array => frame.index += 1,
+                        }
+                    }
+                }
+                byte if byte.is_ascii_whitespace() => {}
+                _ => self.scalar_start = Some(i),
+            }
+        }
+
+        completed
+    }
+
+    fn open(&mut self, byte: u8, start: usize, segment: Option<PathSegment>) {
+        let container = if byte == b'{' { Container::Object } else { Container::Array };
+        self.stack.push(Frame {
+            container,
+            segment,
+            start,
+            expecting_key: container == Container::Object,
+            key: None,
+            index: 0,
+        });
+    }
+
+    fn close(&mut self, end: usize, completed: &mut Vec<CompletedValue>) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        if self.stack.is_empty() {
+            self.done = true;
+            return;
+        }
+        if let Some(segment) = frame.segment {
+            self.report(frame.start, end + 1, segment, completed);
+        }
+    }
+
+    fn end_string(&mut self, start: usize, end: usize, completed: &mut Vec<CompletedValue>) {
+        let is_key = self
+            .stack
+            .last()
+            .is_some_and(|frame| frame.container == Container::Object && frame.expecting_key);
+        if is_key {
+            let key = serde_json::from_str::<String>(&self.buffer[start..end]).ok();
+            if let Some(frame) = self.stack.last_mut() {
+                frame.key = key;
+            }
+        } else {
+            self.complete(start, end, completed);
+        }
+    }
+
+    /// A string or scalar in the innermost container ended
+    fn complete(&self, start: usize, end: usize, completed: &mut Vec<CompletedValue>) {
+        if let Some(segment) = self.stack.last().and_then(Frame::current_segment) {
+            self.report(start, end, segment, completed);
+        }
+    }
+
+    fn report(&self, start: usize, end: usize, segment: PathSegment, completed: &mut Vec<CompletedValue>) {
+        let mut path: Vec<PathSegment> = self.stack.iter().filter_map(|frame| frame.segment.clone()).collect();
+        path.push(segment);
+        if path.len() > self.max_depth {
+            return;
+        }
+        match serde_json::from_str(&self.buffer[start..end]) {
+            Ok(value) => completed.push(CompletedValue { path, value }),
+            Err(e) => tracing::debug!("Skipping malformed streamed value at {:?}: {}", path, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DOCUMENT: &str = r#"```json
+{
+  "tasks": [
+    {"title": "Call \"Bob\"", "tags": ["a", "b"]},
+    {"title": "Grüße {senden}", "tags": []}
+  ],
+  "count": 2,
+  "done": false,
+  "summary": "All good"
+}
+```"#;
+
+    fn stream_in_pieces(text: &str, piece: usize) -> Vec<CompletedValue> {
+        let mut stream = JsonStream::new(2);
+        let chars: Vec<char> = text.chars().collect();
+        chars
+            .chunks(piece)
+            .flat_map(|chunk| stream.push(&chunk.iter().collect::<String>()))
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_values_as_they_complete() {
+        for piece in [1, 3, 7, DOCUMENT.len()] {
+            let values = stream_in_pieces(DOCUMENT, piece);
+            let tasks: Vec<&Value> = values.iter().filter(|v| v.is_element_of("tasks")).map(|v| &v.value).collect();
+            assert_eq!(
+                tasks,
+                [
+                    &json!({"title": "Call \"Bob\"", "tags": ["a", "b"]}),
+                    &json!({"title": "Grüße {senden}", "tags": []})
+                ]
+            );
+
+            let field = |key| values.iter().find(|v| v.is_field(key)).map(|v| v.value.clone());
+            assert_eq!(field("count"), Some(json!(2)));
+            assert_eq!(field("done"), Some(json!(false)));
+            assert_eq!(field("summary"), Some(json!("All good")));
+            assert_eq!(field("tasks").unwrap().as_array().unwrap().len(), 2);
+
+            // Nested deeper than max_depth
+            assert!(values.iter().all(|v| v.path.len() <= 2));
+        }
+    }
+
+    #[test]
+    fn test_items_arrive_before_the_document_ends() {
+        let mut stream = JsonStream::new(2);
+        assert!(stream.push(r#"{"tasks": [{"title": "One"}"#).iter().any(|v| v.is_element_of("tasks")));
+        let values = stream.push(r#", {"title": "Tw"#);
+        assert!(values.is_empty());
+        let values = stream.push(r#"o"}], "summary": "cut"#);
+        assert_eq!(
+            values[0],
+            CompletedValue {
+                path: vec![PathSegment::Key("tasks".to_string()), PathSegment::Index(1)],
+                value: json!({"title": "Two"}),
+            }
+        );
+        assert!(values.iter().all(|v| !v.is_field("summary")));
+    }
+
+    #[test]
+    fn test_ignores_text_after_document() {
+        let mut stream = JsonStream::new(1);
+        let values = stream.push(r#"{"a": 1} {"b": 2}"#);
+        assert_eq!(values.len(), 1);
+        assert!(values[0].is_field("a"));
+        assert!(stream.push(r#"{"c": 3}"#).is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+//! Local LLM server speaking the OpenAI Chat Completions API (Ollama,
+//! llama.cpp server, vLLM, LM Studio, ...).
+//!
+//! Agents whose provider is `local` run against this server instead of a
+//! cloud API, so transcripts never leave the machine. An API key, if the
+//! server needs one, is kept in secure storage as `local_llm`.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::OpenAiProvider;
+use crate::storage;
+
+const CONFIG_FILE: &str = "local-llm.json";
+
+/// Ollama's OpenAI-compatible endpoint
+const DEFAULT_BASE_URL: &str = "http://localhost:11434/v1";
+const DEFAULT_MODEL: &str = "llama3.2";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LocalLlmConfig {
+    /// API root including the version, e.g. `http://localhost:8080/v1`
+    pub base_url: String,
+    /// Model agents use on this server unless they name one themselves
+    pub model: String,
+}
+
+impl Default for LocalLlmConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+        }
+    }
+}
+
+impl LocalLlmConfig {
+    pub fn load(app: &AppHandle) -> Self {
+        storage::load_json(app, CONFIG_FILE)
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, CONFIG_FILE, self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let url = reqwest::Url::parse(&self.base_url)
+            .map_err(|e| format!("Invalid base URL '{}': {}", self.base_url, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("Base URL must start with http:// or https://".to_string());
+        }
+        if self.model.trim().is_empty() {
+            return Err("Model must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// `api_key` may be empty for servers started without one
+    pub fn provider(&self, api_key: &str) -> OpenAiProvider {
+        OpenAiProvider::compatible(&self.base_url, api_key)
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_local_llm_config(app: AppHandle) -> Result<LocalLlmConfig, String> {
+    Ok(LocalLlmConfig::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_local_llm_config(app: AppHandle, config: LocalLlmConfig) -> Result<(), String> {
+    config.validate()?;
+    config.save(&app)?;
+    tracing::info!("Local LLM configured: {} ({})", config.base_url, config.model);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(LocalLlmConfig::default().validate().is_ok());
+        assert!(LocalLlmConfig { base_url: "ftp://x".to_string(), ..Default::default() }.validate().is_err());
+        assert!(LocalLlmConfig { base_url: "localhost".to_string(), ..Default::default() }.validate().is_err());
+        assert!(LocalLlmConfig { model: " ".to_string(), ..Default::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,437 @@
+//! Shared chat-completion client for the agents.
+//!
+//! Every agent talks to its model through an [`LlmProvider`], so the
+//! provider and model are configuration instead of hard-coded URLs. All
+//! providers share one pooled HTTP client.
+//!
+//! Implementations:
+//! - OpenAI Chat Completions (`openai`)
+//! - Anthropic Messages (`anthropic`)
+//! - Local OpenAI-compatible servers such as Ollama (`local`)
+//!
+//! Rate-limited or failing requests are retried with backoff (`retry`);
+//! a [`FallbackProvider`] then moves them to a second provider.
+//!
+//! Agents that expect JSON use [`chat_structured`], which sends the result
+//! type's schema and repairs answers that don't match it.
+
+pub mod anthropic;
+pub mod fallback;
+pub mod json_stream;
+pub mod local;
+pub mod openai;
+mod retry;
+pub mod sse;
+pub mod structured;
+
+use futures_util::future::BoxFuture;
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+pub use anthropic::AnthropicProvider;
+pub use fallback::FallbackProvider;
+pub use openai::OpenAiProvider;
+pub use structured::{chat_structured, finish_structured, OutputSchema};
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .pool_idle_timeout(Duration::from_secs(90))
+        .build()
+        .unwrap_or_default()
+});
+
+/// The app-wide HTTP client; clones share its connection pool
+pub fn http_client() -> reqwest::Client {
+    HTTP_CLIENT.clone()
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    #[serde(rename = "openai")]
+    OpenAi,
+    Anthropic,
+    /// OpenAI-compatible server on this machine or network
+    Local,
+}
+
+impl ProviderKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "OpenAI",
+            ProviderKind::Anthropic => "Anthropic",
+            ProviderKind::Local => "Local LLM",
+        }
+    }
+
+    /// Secure storage entry holding this provider's API key
+    pub fn key_type(self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "openai",
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::Local => "local_llm",
+        }
+    }
+}
+
+/// A failed chat request
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmError {
+    /// HTTP status, if the provider answered at all
+    pub status: Option<u16>,
+    /// How long the provider asked us to wait before retrying
+    pub retry_after: Option<Duration>,
+    pub message: String,
+}
+
+impl LlmError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            status: None,
+            retry_after: None,
+            message: message.into(),
+        }
+    }
+
+    /// Rate limits and server errors are worth trying elsewhere
+    pub fn is_retryable(&self) -> bool {
+        self.status.is_some_and(|status| status == 429 || status >= 500)
+    }
+}
+
+impl fmt::Display for LlmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+impl From<LlmError> for String {
+    fn from(err: LlmError) -> Self {
+        err.message
+    }
+}
+
+impl From<String> for LlmError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+/// A provider-neutral chat request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatRequest {
+    pub model: String,
+    pub system: Option<String>,
+    pub messages: Vec<ChatMessage>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Ask for a single JSON object as the answer
+    pub json: bool,
+    /// Schema the JSON answer must match
+    pub schema: Option<OutputSchema>,
+    /// Limit for each attempt, including a streamed answer
+    pub timeout: Option<Duration>,
+}
+
+impl ChatRequest {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            system: None,
+            messages: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            json: false,
+            schema: None,
+            timeout: None,
+        }
+    }
+
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    pub fn with_user(mut self, content: impl Into<String>) -> Self {
+        self.messages.push(ChatMessage {
+            role: Role::User,
+            content: content.into(),
+        });
+        self
+    }
+
+    pub fn with_assistant(mut self, content: impl Into<String>) -> Self {
+        self.messages.push(ChatMessage {
+            role: Role::Assistant,
+            content: content.into(),
+        });
+        self
+    }
+
+    pub fn with_json(mut self) -> Self {
+        self.json = true;
+        self
+    }
+
+    /// Ask for a JSON answer matching `schema`
+    pub fn with_schema(mut self, schema: OutputSchema) -> Self {
+        self.json = true;
+        self.schema = Some(schema);
+        self
+    }
+
+    /// System prompt extended with the schema, for providers that cannot
+    /// enforce it themselves
+    fn system_with_schema(&self) -> Option<String> {
+        let Some(schema) = &self.schema else {
+            return self.system.clone();
+        };
+        let instruction = format!("Your answer must be JSON matching this schema:\n{}", schema.to_prompt());
+        Some(match &self.system {
+            Some(system) => format!("{}\n\n{}", system, instruction),
+            None => instruction,
+        })
+    }
+}
+
+/// Which provider and model an agent uses, and how it samples
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelConfig {
+    pub provider: ProviderKind,
+    pub model: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub timeout_secs: Option<u64>,
+}
+
+impl ModelConfig {
+    pub fn new(provider: ProviderKind, model: &str) -> Self {
+        Self {
+            provider,
+            model: model.to_string(),
+            temperature: None,
+            max_tokens: None,
+            timeout_secs: None,
+        }
+    }
+
+    pub fn openai(model: &str) -> Self {
+        Self::new(ProviderKind::OpenAi, model)
+    }
+
+    pub fn anthropic(model: &str) -> Self {
+        Self::new(ProviderKind::Anthropic, model)
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// An empty request for this model
+    pub fn request(&self) -> ChatRequest {
+        ChatRequest {
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            timeout: self.timeout_secs.map(Duration::from_secs),
+            ..ChatRequest::new(&self.model)
+        }
+    }
+}
+
+/// A chat-completion backend
+pub trait LlmProvider: Send + Sync {
+    fn kind(&self) -> ProviderKind;
+
+    /// The complete answer
+    fn chat<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String, LlmError>>;
+
+    /// Stream the answer, calling `on_delta` for every piece of text as it
+    /// arrives; resolves to the complete answer
+    fn chat_stream<'a>(
+        &'a self,
+        request: &'a ChatRequest,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+    ) -> BoxFuture<'a, Result<String, LlmError>>;
+}
+
+/// Client for `kind`; `Local` targets the default server address, use
+/// [`local::LocalLlmConfig::provider`] for the configured one
+pub fn provider(kind: ProviderKind, api_key: &str) -> Box<dyn LlmProvider> {
+    match kind {
+        ProviderKind::OpenAi => Box::new(OpenAiProvider::new(api_key)),
+        ProviderKind::Anthropic => Box::new(AnthropicProvider::new(api_key)),
+        ProviderKind::Local => Box::new(local::LocalLlmConfig::default().provider(api_key)),
+    }
+}
+
+/// Parse a model's JSON answer, tolerating a surrounding Markdown code fence
+pub fn parse_json<T: DeserializeOwned>(content: &str) -> Result<T, serde_json::Error> {
+    let trimmed = content.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed);
+    serde_json::from_str(unfenced.trim())
+}
+
+/// Send `builder`, retrying rate limits and server errors, and fail with
+/// the provider's error body unless the request eventually succeeded
+async fn send_checked(
+    kind: ProviderKind,
+    builder: reqwest::RequestBuilder,
+    request: &ChatRequest,
+) -> Result<reqwest::Response, LlmError> {
+    let mut builder = match request.timeout {
+        Some(timeout) => builder.timeout(timeout),
+        None => builder,
+    };
+    let mut attempt = 0;
+    loop {
+        let next = builder.try_clone();
+        let error = match send_once(kind, builder).await {
+            Ok(response) => return Ok(response),
+            Err(error) => error,
+        };
+        match (next, retry::delay(attempt, &error)) {
+            (Some(next), Some(delay)) => {
+                tracing::warn!("{} (attempt {}), retrying in {:?}", error, attempt + 1, delay);
+                tokio::time::sleep(delay).await;
+                builder = next;
+                attempt += 1;
+            }
+            _ => return Err(error),
+        }
+    }
+}
+
+async fn send_once(kind: ProviderKind, builder: reqwest::RequestBuilder) -> Result<reqwest::Response, LlmError> {
+    let response = builder.send().await.map_err(|e| {
+        if e.is_timeout() {
+            LlmError::new(format!("{} did not answer in time", kind.label()))
+        } else {
+            LlmError::new(format!("Request to {} failed: {}", kind.label(), e))
+        }
+    })?;
+
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(retry::parse_retry_after);
+    let error_text = response.text().await.unwrap_or_default();
+    Err(LlmError {
+        status: Some(status.as_u16()),
+        retry_after,
+        message: format!("{} API error ({}): {}", kind.label(), status.as_u16(), error_text),
+    })
+}
+
+/// Call `on_event` with every event of a server-sent event stream
+async fn read_sse(
+    response: reqwest::Response,
+    mut on_event: impl FnMut(&sse::SseEvent) -> Result<(), LlmError>,
+) -> Result<(), LlmError> {
+    let mut stream = response.bytes_stream();
+    let mut decoder = sse::SseDecoder::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| {
+            if e.is_timeout() {
+                LlmError::new("The answer did not finish in time")
+            } else {
+                LlmError::new(format!("Stream error: {}", e))
+            }
+        })?;
+        for event in decoder.push(&chunk) {
+            on_event(&event)?;
+        }
+    }
+    decoder.finish();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_strips_code_fence() {
+        let value: serde_json::Value = parse_json("```json\n{\"a\": 1}\n```").unwrap();
+        assert_eq!(value["a"], 1);
+        let value: serde_json::Value = parse_json(" {\"a\": 2} ").unwrap();
+        assert_eq!(value["a"], 2);
+        assert!(parse_json::<serde_json::Value>("not json").is_err());
+    }
+
+    #[test]
+    fn test_model_config_request() {
+        let config = ModelConfig::anthropic("claude-sonnet-4-20250514")
+            .with_temperature(0.3)
+            .with_max_tokens(1024);
+        let request = config.request().with_system("be brief").with_user("hi");
+        assert_eq!(request.model, "claude-sonnet-4-20250514");
+        assert_eq!(request.temperature, Some(0.3));
+        assert_eq!(request.max_tokens, Some(1024));
+        assert_eq!(request.system.as_deref(), Some("be brief"));
+        assert_eq!(request.messages.len(), 1);
+        assert!(!request.json);
+        assert_eq!(request.timeout, None);
+        assert_eq!(
+            ModelConfig { timeout_secs: Some(30), ..config }.request().timeout,
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        let error = |status| LlmError {
+            status: Some(status),
+            retry_after: None,
+            message: String::new(),
+        };
+        assert!(error(429).is_retryable());
+        assert!(error(500).is_retryable());
+        assert!(error(529).is_retryable());
+        assert!(!error(400).is_retryable());
+        assert!(!error(401).is_retryable());
+        assert!(!LlmError::new("connection refused").is_retryable());
+    }
+
+    #[test]
+    fn test_provider_kind_serialization() {
+        assert_eq!(serde_json::to_string(&ProviderKind::OpenAi).unwrap(), "\"openai\"");
+        assert_eq!(serde_json::to_string(&ProviderKind::Anthropic).unwrap(), "\"anthropic\"");
+        assert_eq!(serde_json::to_string(&ProviderKind::Local).unwrap(), "\"local\"");
+    }
+}
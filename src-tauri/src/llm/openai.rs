@@ -0,0 +1,240 @@
+//! OpenAI Chat Completions provider, also used for local servers that
+//! implement the same API.
+
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+
+use super::{http_client, read_sse, send_checked, ChatRequest, LlmError, LlmProvider, ProviderKind, Role};
+
+const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
+
+pub struct OpenAiProvider {
+    kind: ProviderKind,
+    url: String,
+    api_key: String,
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    delta: Delta,
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    content: Option<String>,
+}
+
+impl OpenAiProvider {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            kind: ProviderKind::OpenAi,
+            url: OPENAI_API_URL.to_string(),
+            api_key: api_key.to_string(),
+            http: http_client(),
+        }
+    }
+
+    /// A local server at `base_url` (API root including the version, e.g.
+    /// `http://localhost:11434/v1`); `api_key` may be empty
+    pub fn compatible(base_url: &str, api_key: &str) -> Self {
+        Self {
+            kind: ProviderKind::Local,
+            url: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            api_key: api_key.to_string(),
+            http: http_client(),
+        }
+    }
+
+    async fn send(&self, request: &ChatRequest, stream: bool) -> Result<reqwest::Response, LlmError> {
+        let mut builder = self
+            .http
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .json(&request_body(request, stream, self.kind == ProviderKind::OpenAi));
+        if !self.api_key.is_empty() {
+            builder = builder.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+        send_checked(self.kind, builder, request).await
+    }
+}
+
+/// `native_schema`: the server enforces `json_schema` response formats;
+/// otherwise the schema goes into the system prompt
+fn request_body(request: &ChatRequest, stream: bool, native_schema: bool) -> serde_json::Value {
+    let system = if native_schema {
+        request.system.clone()
+    } else {
+        request.system_with_schema()
+    };
+
+    let mut messages = Vec::new();
+    if let Some(system) = &system {
+        messages.push(serde_json::json!({ "role": "system", "content": system }));
+    }
+    for message in &request.messages {
+        let role = match message.role {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        };
+        messages.push(serde_json::json!({ "role": role, "content": message.content }));
+    }
+
+    let mut body = serde_json::json!({
+        "model": request.model,
+        "messages": messages,
+    });
+    if let Some(temperature) = request.temperature {
+        body["temperature"] = temperature.into();
+    }
+    if let Some(max_tokens) = request.max_tokens {
+        body["max_tokens"] = max_tokens.into();
+    }
+    match &request.schema {
+        Some(schema) if native_schema => {
+            body["response_format"] = serde_json::json!({
+                "type": "json_schema",
+                "json_schema": { "name": schema.name, "schema": schema.schema, "strict": true },
+            });
+        }
+        _ if request.json => body["response_format"] = serde_json::json!({ "type": "json_object" }),
+        _ => {}
+    }
+    if stream {
+        body["stream"] = true.into();
+    }
+    body
+}
+
+/// Text carried by one streamed chunk
+fn parse_delta(data: &str) -> Option<String> {
+    if data == "[DONE]" {
+        return None;
+    }
+    let chunk: StreamChunk = serde_json::from_str(data).ok()?;
+    chunk.choices.into_iter().next()?.delta.content
+}
+
+impl LlmProvider for OpenAiProvider {
+    fn kind(&self) -> ProviderKind {
+        self.kind
+    }
+
+    fn chat<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String, LlmError>> {
+        Box::pin(async move {
+            let response: ChatResponse = self
+                .send(request, false)
+                .await?
+                .json()
+                .await
+                .map_err(|e| LlmError::new(format!("Failed to parse response: {}", e)))?;
+
+            response
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|c| c.message.content)
+                .ok_or_else(|| LlmError::new("No content in response"))
+        })
+    }
+
+    fn chat_stream<'a>(
+        &'a self,
+        request: &'a ChatRequest,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+    ) -> BoxFuture<'a, Result<String, LlmError>> {
+        Box::pin(async move {
+            let response = self.send(request, true).await?;
+            let mut full_text = String::new();
+            read_sse(response, |event| {
+                if let Some(content) = parse_delta(&event.data) {
+                    full_text.push_str(&content);
+                    on_delta(&content);
+                }
+                Ok(())
+            })
+            .await?;
+            Ok(full_text)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::OutputSchema;
+
+    #[test]
+    fn test_request_body() {
+        let request = ChatRequest::new("gpt-4o")
+            .with_system("system prompt")
+            .with_user("hello")
+            .with_json();
+        let body = request_body(&request, false, true);
+        assert_eq!(body["model"], "gpt-4o");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], "hello");
+        assert_eq!(body["response_format"]["type"], "json_object");
+        assert!(body.get("stream").is_none());
+        assert!(body.get("temperature").is_none());
+
+        let body = request_body(&ChatRequest::new("gpt-4o").with_user("hi"), true, true);
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_schema_request_body() {
+        let schema = OutputSchema {
+            name: "Mood".to_string(),
+            schema: serde_json::json!({ "type": "object" }),
+        };
+        let request = ChatRequest::new("gpt-4o").with_system("system prompt").with_schema(schema);
+
+        let body = request_body(&request, false, true);
+        assert_eq!(body["response_format"]["type"], "json_schema");
+        assert_eq!(body["response_format"]["json_schema"]["name"], "Mood");
+        assert_eq!(body["response_format"]["json_schema"]["strict"], true);
+        assert_eq!(body["messages"][0]["content"], "system prompt");
+
+        let body = request_body(&request, false, false);
+        assert_eq!(body["response_format"]["type"], "json_object");
+        assert!(body["messages"][0]["content"].as_str().unwrap().contains("\"type\": \"object\""));
+    }
+
+    #[test]
+    fn test_compatible_endpoint() {
+        let provider = OpenAiProvider::compatible("http://localhost:11434/v1/", "");
+        assert_eq!(provider.url, "http://localhost:11434/v1/chat/completions");
+        assert_eq!(provider.kind(), ProviderKind::Local);
+        assert_eq!(OpenAiProvider::new("sk-test").url, OPENAI_API_URL);
+    }
+
+    #[test]
+    fn test_parse_delta() {
+        let data = r#"{"choices":[{"delta":{"content":"Hel"},"finish_reason":null}]}"#;
+        assert_eq!(parse_delta(data).as_deref(), Some("Hel"));
+        assert_eq!(parse_delta(r#"{"choices":[{"delta":{}}]}"#), None);
+        assert_eq!(parse_delta("[DONE]"), None);
+    }
+}
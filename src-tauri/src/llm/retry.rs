@@ -0,0 +1,86 @@
+//! Retrying rate-limited and failed requests with jittered exponential
+//! backoff.
+//!
+//! Only the initial request is retried, never a stream that already
+//! delivered text.
+
+use rand::Rng;
+use std::time::Duration;
+
+use super::LlmError;
+
+/// Retries after the first attempt
+const MAX_RETRIES: u32 = 2;
+const BASE_DELAY: Duration = Duration::from_millis(500);
+/// A server asking us to wait longer than this is better served by the
+/// fallback provider
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// How long to wait before retry number `attempt + 1`, or `None` to give up
+pub(crate) fn delay(attempt: u32, error: &LlmError) -> Option<Duration> {
+    let jitter = rand::thread_rng().gen_range(0.5..=1.0);
+    delay_with_jitter(attempt, error, jitter)
+}
+
+fn delay_with_jitter(attempt: u32, error: &LlmError, jitter: f64) -> Option<Duration> {
+    if !error.is_retryable() || attempt >= MAX_RETRIES {
+        return None;
+    }
+    match error.retry_after {
+        Some(retry_after) if retry_after > MAX_DELAY => None,
+        Some(retry_after) => Some(retry_after),
+        None => {
+            let backoff = BASE_DELAY.saturating_mul(1 << attempt).min(MAX_DELAY);
+            Some(backoff.mul_f64(jitter))
+        }
+    }
+}
+
+/// The `Retry-After` header: either seconds or an HTTP date
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(status: u16, retry_after: Option<Duration>) -> LlmError {
+        LlmError {
+            status: Some(status),
+            retry_after,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_with_jitter() {
+        let overloaded = error(529, None);
+        assert_eq!(delay_with_jitter(0, &overloaded, 1.0), Some(BASE_DELAY));
+        assert_eq!(delay_with_jitter(1, &overloaded, 1.0), Some(BASE_DELAY * 2));
+        assert_eq!(delay_with_jitter(1, &overloaded, 0.5), Some(BASE_DELAY));
+        assert_eq!(delay_with_jitter(MAX_RETRIES, &overloaded, 1.0), None);
+        assert_eq!(delay_with_jitter(0, &error(400, None), 1.0), None);
+    }
+
+    #[test]
+    fn test_honors_retry_after() {
+        let limited = error(429, Some(Duration::from_secs(3)));
+        assert_eq!(delay_with_jitter(0, &limited, 0.5), Some(Duration::from_secs(3)));
+        let long_wait = error(429, Some(Duration::from_secs(60)));
+        assert_eq!(delay_with_jitter(0, &long_wait, 1.0), None);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("7"), Some(Duration::from_secs(7)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
@@ -0,0 +1,204 @@
+//! Incremental decoder for server-sent event streams.
+//!
+//! Follows the WHATWG event stream format: lines end in CRLF, LF or CR,
+//! `data:` lines of one event are joined with newlines and a blank line
+//! dispatches the event. Bytes are buffered until a line is complete, so
+//! UTF-8 sequences split across network chunks decode correctly.
+
+/// One dispatched event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The `event:` field, `None` for the default `message` type
+    pub event: Option<String>,
+    pub data: String,
+    /// The last `id:` seen on the stream so far
+    pub id: Option<String>,
+    /// Reconnection delay requested with `retry:`, in milliseconds
+    pub retry: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    /// Bytes of the current, incomplete line
+    line: Vec<u8>,
+    /// The previous chunk ended in CR, so a leading LF belongs to it
+    after_cr: bool,
+    /// Still at the start of the stream, where a BOM is skipped
+    at_start: bool,
+    event: Option<String>,
+    data: String,
+    has_data: bool,
+    last_id: Option<String>,
+    retry: Option<u64>,
+}
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self {
+            at_start: true,
+            ..Default::default()
+        }
+    }
+
+    /// Feed the next chunk of the stream, returning the events it completed
+    pub fn push(&mut self, mut chunk: &[u8]) -> Vec<SseEvent> {
+        let mut events = Vec::new();
+
+        if self.after_cr && !chunk.is_empty() {
+            self.after_cr = false;
+            if chunk[0] == b'\n' {
+                chunk = &chunk[1..];
+            }
+        }
+
+        while let Some(end) = chunk.iter().position(|&b| b == b'\n' || b == b'\r') {
+            self.line.extend_from_slice(&chunk[..end]);
+            let terminator_len = match chunk[end] {
+                b'\r' if end + 1 == chunk.len() => {
+                    self.after_cr = true;
+                    1
+                }
+                b'\r' if chunk[end + 1] == b'\n' => 2,
+                _ => 1,
+            };
+            chunk = &chunk[end + terminator_len..];
+
+            let line = std::mem::take(&mut self.line);
+            if let Some(event) = self.process_line(&line) {
+                events.push(event);
+            }
+        }
+        self.line.extend_from_slice(chunk);
+
+        events
+    }
+
+    /// End of stream. Per the spec an event without its closing blank line
+    /// is discarded.
+    pub fn finish(&mut self) {
+        self.line.clear();
+        self.reset_event();
+    }
+
+    fn process_line(&mut self, mut line: &[u8]) -> Option<SseEvent> {
+        if self.at_start {
+            self.at_start = false;
+            line = line.strip_prefix(BOM).unwrap_or(line);
+        }
+
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line[0] == b':' {
+            return None;
+        }
+
+        let line = String::from_utf8_lossy(line);
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line.as_ref(), ""),
+        };
+
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => {
+                if self.has_data {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value);
+                self.has_data = true;
+            }
+            "id" if !value.contains('\0') => self.last_id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(retry) = value.parse() {
+                    self.retry = Some(retry);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        if !self.has_data {
+            self.reset_event();
+            return None;
+        }
+        let event = SseEvent {
+            event: self.event.take().filter(|event| !event.is_empty()),
+            data: std::mem::take(&mut self.data),
+            id: self.last_id.clone(),
+            retry: self.retry,
+        };
+        self.reset_event();
+        Some(event)
+    }
+
+    fn reset_event(&mut self) {
+        self.event = None;
+        self.data.clear();
+        self.has_data = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_in_pieces(stream: &[u8], piece: usize) -> Vec<SseEvent> {
+        let mut decoder = SseDecoder::new();
+        let events = stream.chunks(piece).flat_map(|chunk| decoder.push(chunk)).collect();
+        decoder.finish();
+        events
+    }
+
+    fn data(events: &[SseEvent]) -> Vec<&str> {
+        events.iter().map(|event| event.data.as_str()).collect()
+    }
+
+    #[test]
+    fn test_split_code_points() {
+        let stream = "data: Grüße aus Köln 東京\n\ndata: ✓\n\n".as_bytes();
+        for piece in 1..stream.len() {
+            assert_eq!(data(&decode_in_pieces(stream, piece)), ["Grüße aus Köln 東京", "✓"], "piece size {}", piece);
+        }
+    }
+
+    #[test]
+    fn test_line_endings() {
+        for stream in ["data: a\r\n\r\ndata: b\r\n\r\n", "data: a\r\rdata: b\r\r", "data: a\n\ndata: b\n\n"] {
+            for piece in 1..=stream.len() {
+                assert_eq!(data(&decode_in_pieces(stream.as_bytes(), piece)), ["a", "b"], "{:?}", stream);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_line_data_and_fields() {
+        let stream = b"\xEF\xBB\xBF: keep-alive\nid: 7\nevent: content_block_delta\ndata: first\ndata:second\ndata\n\n";
+        let events = decode_in_pieces(stream, 4);
+        assert_eq!(
+            events,
+            [SseEvent {
+                event: Some("content_block_delta".to_string()),
+                data: "first\nsecond\n".to_string(),
+                id: Some("7".to_string()),
+                retry: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_events_without_data_or_terminator() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.push(b"event: ping\n\nretry: 3000\n\n").is_empty());
+        let events = decoder.push(b"data: [DONE]\n\ndata: cut off");
+        assert_eq!(data(&events), ["[DONE]"]);
+        assert_eq!(events[0].event, None);
+        assert_eq!(events[0].retry, Some(3000));
+        decoder.finish();
+        assert!(decoder.push(b"\n").is_empty());
+    }
+}
@@ -0,0 +1,468 @@
+//! Structured output: JSON schemas generated from the result types, sent to
+//! providers that can enforce them, and validated on return.
+//!
+//! An answer that does not match is sent back to the model with the
+//! validation errors, at most [`MAX_REPAIRS`] times, before giving up.
+
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use super::{parse_json, ChatRequest, LlmProvider};
+
+/// Extra rounds a model gets to fix an answer that fails validation
+pub const MAX_REPAIRS: usize = 2;
+
+/// Keywords that carry no constraint the providers' schema dialects accept
+const UNSUPPORTED_KEYWORDS: &[&str] = &["$schema", "title", "format", "default", "definitions"];
+
+/// Schema the answer to a request must match
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputSchema {
+    /// Name of the Rust type, e.g. `BrainDumpResult`
+    pub name: String,
+    pub schema: Value,
+}
+
+impl OutputSchema {
+    /// Schema for `T`, in the strict form OpenAI's structured outputs accept:
+    /// everything inlined, every object closed and every property required
+    /// (optional ones are nullable instead)
+    pub fn of<T: JsonSchema>() -> Self {
+        let root = SchemaSettings::draft07()
+            .with(|settings| {
+                settings.inline_subschemas = true;
+                settings.option_add_null_type = true;
+                settings.option_nullable = false;
+            })
+            .into_generator()
+            .into_root_schema_for::<T>();
+        // Options are nullable already; fields with serde defaults stay
+        // non-null so the answer still deserializes
+        let mut schema = serde_json::to_value(root).unwrap_or(Value::Null);
+        make_strict(&mut schema, false);
+        Self {
+            name: T::schema_name(),
+            schema,
+        }
+    }
+
+    /// A hand-written schema, brought into the same strict form; properties
+    /// missing from its `required` list become nullable
+    pub fn new(name: impl Into<String>, mut schema: Value) -> Self {
+        make_strict(&mut schema, true);
+        Self {
+            name: name.into(),
+            schema,
+        }
+    }
+
+    /// The schema as pretty JSON, for prompts
+    pub fn to_prompt(&self) -> String {
+        serde_json::to_string_pretty(&self.schema).unwrap_or_default()
+    }
+
+    /// Everything in `value` that violates the schema
+    pub fn validate(&self, value: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        check(&self.schema, value, "$", &mut errors);
+        errors
+    }
+
+    /// Parse and validate an answer
+    pub fn parse<T: DeserializeOwned>(&self, answer: &str) -> Result<T, String> {
+        let value: Value = parse_json(answer).map_err(|e| format!("The answer is not valid JSON: {}", e))?;
+        let errors = self.validate(&value);
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+}
+
+fn make_strict(schema: &mut Value, nullable_optional: bool) {
+    let Value::Object(map) = schema else {
+        return;
+    };
+    for keyword in UNSUPPORTED_KEYWORDS {
+        map.remove(*keyword);
+    }
+    for (keyword, value) in map.iter_mut() {
+        match (keyword.as_str(), value) {
+            // Maps of field names to schemas; the names are not keywords
+            ("properties", Value::Object(properties)) => {
+                properties.values_mut().for_each(|value| make_strict(value, nullable_optional))
+            }
+            ("enum" | "const" | "required", _) => {}
+            (_, Value::Array(subschemas)) => subschemas.iter_mut().for_each(|value| make_strict(value, nullable_optional)),
+            (_, value @ Value::Object(_)) => make_strict(value, nullable_optional),
+            _ => {}
+        }
+    }
+    if nullable_optional {
+        let required: Vec<String> = match map.get("required") {
+            Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).map(String::from).collect(),
+            _ => Vec::new(),
+        };
+        if let Some(Value::Object(properties)) = map.get_mut("properties") {
+            for (name, property) in properties.iter_mut() {
+                if !required.contains(name) {
+                    make_nullable(property);
+                }
+            }
+        }
+    }
+    if let Some(Value::Object(properties)) = map.get("properties") {
+        let required = properties.keys().cloned().map(Value::String).collect();
+        map.insert("required".to_string(), Value::Array(required));
+        map.insert("additionalProperties".to_string(), Value::Bool(false));
+    }
+}
+
+/// Let `schema` also accept null, so an optional property can be required
+fn make_nullable(schema: &mut Value) {
+    let Value::Object(map) = schema else {
+        return;
+    };
+    let null = Value::String("null".to_string());
+    match map.get_mut("type") {
+        Some(Value::String(t)) if t != "null" => {
+            let t = Value::String(std::mem::take(t));
+            map.insert("type".to_string(), Value::Array(vec![t, null]));
+        }
+        Some(Value::Array(types)) if !types.contains(&null) => types.push(null),
+        Some(_) => {}
+        None => {
+            if let Some(Value::Array(subschemas)) = map.get_mut("anyOf") {
+                let null_schema = serde_json::json!({ "type": "null" });
+                if !subschemas.contains(&null_schema) {
+                    subschemas.push(null_schema);
+                }
+            }
+        }
+    }
+    if let Some(Value::Array(values)) = map.get_mut("enum") {
+        if !values.contains(&Value::Null) {
+            values.push(Value::Null);
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    let actual = type_name(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+/// Check the keywords schemars generates: type, enum, properties, required,
+/// additionalProperties, items, anyOf/oneOf and numeric bounds
+fn check(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+        errors.push(format!("{}: expected {}, got {}", path, types.join(" or "), type_name(value)));
+        return;
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            errors.push(format!("{}: {} is not one of {}", path, value, allowed.join(", ")));
+        }
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(Value::Array(options)) = schema.get(keyword) {
+            let matches = options.iter().any(|option| {
+                let mut option_errors = Vec::new();
+                check(option, value, path, &mut option_errors);
+                option_errors.is_empty()
+            });
+            if !matches {
+                errors.push(format!("{}: does not match any allowed shape", path));
+            }
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                errors.push(format!("{}: {} is less than {}", path, number, minimum));
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                errors.push(format!("{}: {} is greater than {}", path, number, maximum));
+            }
+        }
+    }
+
+    if let Value::Object(object) = value {
+        check_object(schema, object, path, errors);
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{}[{}]", path, i), errors);
+        }
+    }
+}
+
+fn check_object(schema: &Map<String, Value>, object: &Map<String, Value>, path: &str, errors: &mut Vec<String>) {
+    let empty = Map::new();
+    let properties = schema.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                errors.push(format!("{}: missing field \"{}\"", path, name));
+            }
+        }
+    }
+
+    for (name, field) in object {
+        match properties.get(name) {
+            Some(field_schema) => check(field_schema, field, &format!("{}.{}", path, name), errors),
+            None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                errors.push(format!("{}: unexpected field \"{}\"", path, name));
+            }
+            None => {}
+        }
+    }
+}
+
+fn repair_prompt(errors: &str) -> String {
+    format!(
+        "Your answer does not match the required JSON schema:\n{}\n\nReply with the corrected JSON object only.",
+        errors
+    )
+}
+
+/// Ask for an answer matching `T`'s schema, repairing invalid answers
+pub async fn chat_structured<T: DeserializeOwned + JsonSchema>(
+    provider: &dyn LlmProvider,
+    request: ChatRequest,
+) -> Result<T, String> {
+    let request = request.with_schema(OutputSchema::of::<T>());
+    let answer = provider.chat(&request).await?;
+    finish_structured(provider, request, answer).await
+}
+
+/// Validate `answer` to `request` (which must carry a schema, see
+/// [`ChatRequest::with_schema`]), asking the model to repair it if needed.
+/// Streaming agents call this with the streamed text.
+pub async fn finish_structured<T: DeserializeOwned>(
+    provider: &dyn LlmProvider,
+    mut request: ChatRequest,
+    mut answer: String,
+) -> Result<T, String> {
+    let Some(schema) = request.schema.clone() else {
+        return parse_json(&answer).map_err(|e| e.to_string());
+    };
+
+    let mut repairs = 0;
+    loop {
+        let error = match schema.parse(&answer) {
+            Ok(result) => return Ok(result),
+            Err(error) => error,
+        };
+        if repairs == MAX_REPAIRS {
+            return Err(format!(
+                "{} returned an invalid {} after {} attempts: {}",
+                provider.kind().label(),
+                schema.name,
+                MAX_REPAIRS + 1,
+                error
+            ));
+        }
+        repairs += 1;
+        tracing::warn!("Invalid {} (repair {}/{}): {}", schema.name, repairs, MAX_REPAIRS, error);
+
+        request = request.with_assistant(answer).with_user(repair_prompt(&error));
+        answer = provider.chat(&request).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LlmError, ProviderKind};
+    use futures_util::future::BoxFuture;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    enum Priority {
+        High,
+        Low,
+    }
+
+    #[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+    struct Item {
+        task: String,
+        assignee: Option<String>,
+        priority: Priority,
+        score: f32,
+    }
+
+    #[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+    struct Items {
+        items: Vec<Item>,
+    }
+
+    #[test]
+    fn test_strict_schema() {
+        let schema = OutputSchema::of::<Items>();
+        assert_eq!(schema.name, "Items");
+        assert_eq!(schema.schema["additionalProperties"], false);
+        assert!(schema.schema.get("$schema").is_none());
+
+        let item = &schema.schema["properties"]["items"]["items"];
+        assert_eq!(item["required"], json!(["assignee", "priority", "score", "task"]));
+        assert_eq!(item["properties"]["assignee"]["type"], json!(["string", "null"]));
+        assert_eq!(item["properties"]["priority"]["enum"], json!(["high", "low"]));
+        assert!(item["properties"]["score"].get("format").is_none());
+    }
+
+    #[test]
+    fn test_strict_schema_keeps_fields_named_like_keywords() {
+        #[derive(Deserialize, JsonSchema)]
+        #[allow(dead_code)]
+        struct Link {
+            title: String,
+            format: Option<String>,
+        }
+
+        let schema = OutputSchema::of::<Link>();
+        assert_eq!(schema.schema["required"], json!(["format", "title"]));
+        assert_eq!(schema.schema["properties"]["title"]["type"], "string");
+    }
+
+    #[test]
+    fn test_strict_hand_written_schema_keeps_optional_fields_optional() {
+        let schema = OutputSchema::new(
+            "Summary",
+            json!({
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "owner": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "status": { "enum": ["open", "done"] }
+                },
+                "required": ["title"]
+            }),
+        );
+        assert_eq!(schema.schema["required"], json!(["owner", "status", "tags", "title"]));
+        assert_eq!(schema.schema["properties"]["title"]["type"], "string");
+        assert_eq!(schema.schema["properties"]["owner"]["type"], json!(["string", "null"]));
+        assert_eq!(schema.schema["properties"]["tags"]["type"], json!(["array", "null"]));
+        assert_eq!(schema.schema["properties"]["status"]["enum"], json!(["open", "done", null]));
+
+        let answer = json!({ "title": "Q3", "owner": null, "tags": null, "status": null });
+        assert!(schema.validate(&answer).is_empty());
+        let answer = json!({ "title": null, "owner": "Ana", "tags": ["x"], "status": "open" });
+        assert_eq!(schema.validate(&answer).len(), 1);
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = OutputSchema::of::<Items>();
+        let valid = json!({"items": [{"task": "Ship", "assignee": null, "priority": "high", "score": 1}]});
+        assert!(schema.validate(&valid).is_empty());
+
+        let invalid = json!({"items": [{"task": 3, "priority": "urgent", "score": 0.5, "extra": true}]});
+        let errors = schema.validate(&invalid);
+        assert!(errors.contains(&"$.items[0]: missing field \"assignee\"".to_string()), "{:?}", errors);
+        assert!(errors.contains(&"$.items[0].task: expected string, got integer".to_string()));
+        assert!(errors.contains(&"$.items[0].priority: \"urgent\" is not one of \"high\", \"low\"".to_string()));
+        assert!(errors.contains(&"$.items[0]: unexpected field \"extra\"".to_string()));
+    }
+
+    /// Answers with `answers` in order and records the requests
+    struct ScriptedProvider {
+        answers: Mutex<Vec<&'static str>>,
+        requests: Mutex<Vec<ChatRequest>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(mut answers: Vec<&'static str>) -> Self {
+            answers.reverse();
+            Self {
+                answers: Mutex::new(answers),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl LlmProvider for ScriptedProvider {
+        fn kind(&self) -> ProviderKind {
+            ProviderKind::OpenAi
+        }
+
+        fn chat<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String, LlmError>> {
+            Box::pin(async move {
+                self.requests.lock().unwrap().push(request.clone());
+                Ok(self.answers.lock().unwrap().pop().unwrap_or_default().to_string())
+            })
+        }
+
+        fn chat_stream<'a>(
+            &'a self,
+            request: &'a ChatRequest,
+            _on_delta: &'a mut (dyn FnMut(&str) + Send),
+        ) -> BoxFuture<'a, Result<String, LlmError>> {
+            self.chat(request)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repairs_invalid_answers() {
+        let provider = ScriptedProvider::new(vec![
+            r#"{"items": [{"task": "Ship", "priority": "urgent", "score": 1}]}"#,
+            r#"{"items": [{"task": "Ship", "assignee": null, "priority": "high", "score": 1}]}"#,
+        ]);
+        let items: Items = chat_structured(&provider, ChatRequest::new("gpt-4o").with_user("extract"))
+            .await
+            .unwrap();
+        assert_eq!(items.items[0].priority, Priority::High);
+
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].schema.is_some());
+        let repair = &requests[1].messages;
+        assert_eq!(repair.len(), 3);
+        assert!(repair[2].content.contains("\"urgent\" is not one of"));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_repairs() {
+        let provider = ScriptedProvider::new(vec!["not json"; MAX_REPAIRS + 1]);
+        let error = chat_structured::<Items>(&provider, ChatRequest::new("gpt-4o"))
+            .await
+            .unwrap_err();
+        assert!(error.contains("invalid Items after 3 attempts"), "{}", error);
+        assert_eq!(provider.requests.lock().unwrap().len(), MAX_REPAIRS + 1);
+    }
+}
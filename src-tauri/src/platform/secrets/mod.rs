@@ -102,7 +102,15 @@ compile_error!("Unsupported platform for secure storage");
 pub const SERVICE_NAME: &str = "com.aurusvoiceintelligence";
 
 /// Valid API key types
-pub const VALID_KEY_TYPES: &[&str] = &["deepgram", "assembly_ai", "openai", "anthropic", "qrecords"];
+pub const VALID_KEY_TYPES: &[&str] = &[
+    "deepgram",
+    "assembly_ai",
+    "openai_compatible",
+    "openai",
+    "anthropic",
+    "local_llm",
+    "qrecords",
+];
 
 /// Check if a key type is valid
 pub fn is_valid_key_type(key_type: &str) -> bool {
@@ -114,6 +122,17 @@ pub fn get_storage() -> PlatformStorage {
     PlatformStorage::new()
 }
 
+/// The stored key of `key_type`; `None` if it's missing, empty or can't be read
+pub fn stored_key(key_type: &str) -> Option<String> {
+    match get_storage().get(key_type) {
+        Ok(key) => key.filter(|k| !k.is_empty()),
+        Err(e) => {
+            tracing::warn!("Failed to read '{}' key: {}", key_type, e);
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,6 +141,7 @@ mod tests {
     fn test_valid_key_types() {
         assert!(is_valid_key_type("deepgram"));
         assert!(is_valid_key_type("openai"));
+        assert!(is_valid_key_type("openai_compatible"));
         assert!(!is_valid_key_type("invalid"));
     }
 }
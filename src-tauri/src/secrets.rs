@@ -83,8 +83,9 @@ pub async fn has_api_keys() -> Result<bool, String> {
 
     let has_deepgram = storage.get("deepgram")?.is_some();
     let has_assemblyai = storage.get("assembly_ai")?.is_some();
+    let has_openai_compatible = storage.get("openai_compatible")?.is_some();
 
-    Ok(has_deepgram || has_assemblyai)
+    Ok(has_deepgram || has_assemblyai || has_openai_compatible)
 }
 
 /// Get a list of all configured API key types
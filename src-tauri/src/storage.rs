@@ -0,0 +1,40 @@
+//! Small JSON documents (settings, vocabularies, ...) persisted in the app data dir.
+//!
+//! Missing or unreadable files load as `T::default()`, so a corrupt settings
+//! file never prevents the app from starting.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// Path of a file in the app data dir (the directory is created if needed)
+pub fn app_data_file(app: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data dir: {}", e))?;
+    Ok(dir.join(file_name))
+}
+
+/// Load a JSON document, falling back to the default if absent or invalid
+pub fn load_json<T: DeserializeOwned + Default>(app: &AppHandle, file_name: &str) -> T {
+    let Ok(path) = app_data_file(app, file_name) else {
+        return T::default();
+    };
+    let Ok(json) = std::fs::read_to_string(&path) else {
+        return T::default();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring invalid {}: {}", path.display(), e);
+        T::default()
+    })
+}
+
+pub fn save_json<T: Serialize>(app: &AppHandle, file_name: &str, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", file_name, e))?;
+    std::fs::write(app_data_file(app, file_name)?, json)
+        .map_err(|e| format!("Failed to save {}: {}", file_name, e))
+}
@@ -0,0 +1,629 @@
+//! AssemblyAI batch transcription as a cancellable job.
+//!
+//! A job uploads the audio, creates a transcript and polls it until it
+//! completes, fails, times out or is cancelled. Every status change is
+//! reported through `transcription-job-progress`, so the UI doesn't need a
+//! webhook to show queued/processing state.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::watch;
+
+use super::language::{normalize_language, LanguageConfig};
+use super::usage::{record_usage, UsageProvider};
+use super::export::TimedSegment;
+use super::vocabulary::{Replacer, Vocabulary};
+use super::{
+    encode_wav, low_confidence_spans, samples_to_ms, segments_from_words, ScoredWord, TranscriptEvent,
+    TranscriptionManager,
+};
+
+const ASSEMBLYAI_URL: &str = "https://api.assemblyai.com/v2";
+
+/// Give up on a job after this long unless the caller asks otherwise
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Deserialize)]
+struct AssemblyAiUploadResponse {
+    upload_url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AssemblyAiTranscriptRequest<'a> {
+    audio_url: &'a str,
+    speech_model: &'a str,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    word_boost: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language_code: Option<&'a str>,
+    language_detection: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssemblyAiTranscript {
+    pub id: Option<String>,
+    pub status: Option<String>,
+    pub text: Option<String>,
+    pub error: Option<String>,
+    /// Overall transcript confidence (0.0–1.0), present once completed
+    pub confidence: Option<f32>,
+    pub words: Option<Vec<AssemblyAiWord>>,
+    /// Spoken language, given or detected
+    #[serde(default)]
+    pub language_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssemblyAiWord {
+    pub text: String,
+    /// Milliseconds from the start of the audio
+    pub start: i64,
+    pub end: i64,
+    pub confidence: f32,
+}
+
+impl AssemblyAiTranscript {
+    /// Language of the whole recording as an ISO 639-1 code
+    pub fn language(&self) -> Option<String> {
+        self.language_code.as_deref().and_then(normalize_language)
+    }
+
+    /// Timed segments built from the word timings
+    pub fn segments(&self, replacer: &Replacer) -> Vec<TimedSegment> {
+        segments_from_words(&self.scored_words(), self.language().as_deref(), replacer)
+    }
+
+    pub fn scored_words(&self) -> Vec<ScoredWord> {
+        self.words
+            .iter()
+            .flatten()
+            .map(|w| ScoredWord {
+                text: w.text.clone(),
+                start_ms: w.start,
+                end_ms: w.end,
+                confidence: w.confidence,
+            })
+            .collect()
+    }
+
+    /// Returned confidence, or the mean word confidence if only words are present
+    pub fn overall_confidence(&self) -> f32 {
+        if let Some(confidence) = self.confidence {
+            return confidence;
+        }
+        let words = self.words.as_deref().unwrap_or_default();
+        if words.is_empty() {
+            return 0.0;
+        }
+        words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32
+    }
+}
+
+// ============================================================================
+// Job
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Uploading,
+    Queued,
+    Processing,
+    Completed,
+    Failed,
+    Cancelled,
+    TimedOut,
+}
+
+/// Payload of the `transcription-job-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct JobProgress {
+    pub job_id: String,
+    pub provider: String,
+    pub status: JobStatus,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+    /// Set by providers that upload long recordings in several parts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_chunks: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_chunks: Option<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum JobError {
+    Cancelled,
+    TimedOut(Duration),
+    Failed(String),
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "Transcription job was cancelled"),
+            Self::TimedOut(after) => {
+                write!(f, "Transcription job timed out after {}s", after.as_secs())
+            }
+            Self::Failed(msg) => write!(f, "Transcription job failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for JobError {}
+
+impl From<JobError> for String {
+    fn from(err: JobError) -> Self {
+        err.to_string()
+    }
+}
+
+impl JobError {
+    pub(super) fn status(&self) -> JobStatus {
+        match self {
+            Self::Cancelled => JobStatus::Cancelled,
+            Self::TimedOut(_) => JobStatus::TimedOut,
+            Self::Failed(_) => JobStatus::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobOptions {
+    /// Upper bound for upload + processing
+    pub timeout: Duration,
+    pub poll_interval: Duration,
+    /// Custom vocabulary to favour during recognition
+    pub word_boost: Vec<String>,
+    /// Spoken language (ISO 639-1); `None` has AssemblyAI detect it
+    pub language: Option<String>,
+}
+
+impl Default for JobOptions {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            poll_interval: POLL_INTERVAL,
+            word_boost: Vec::new(),
+            language: None,
+        }
+    }
+}
+
+/// Thin typed client for the AssemblyAI v2 REST API
+pub struct AssemblyAiClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl AssemblyAiClient {
+    pub fn new(api_key: String) -> Self {
+        Self::with_base_url(api_key, ASSEMBLYAI_URL)
+    }
+
+    /// Point the client at another host (e.g. a local stand-in in tests)
+    pub fn with_base_url(api_key: String, base_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+
+    async fn upload(&self, audio: Vec<u8>) -> Result<String, String> {
+        let response = self
+            .http
+            .post(format!("{}/upload", self.base_url))
+            .header("Authorization", &self.api_key)
+            .header("Content-Type", "application/octet-stream")
+            .body(audio)
+            .send()
+            .await
+            .map_err(|e| format!("Upload failed: {}", e))?;
+
+        let upload: AssemblyAiUploadResponse = error_for_status(response, "Upload")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse upload response: {}", e))?;
+
+        Ok(upload.upload_url)
+    }
+
+    async fn create_transcript(
+        &self,
+        audio_url: &str,
+        options: &JobOptions,
+    ) -> Result<AssemblyAiTranscript, String> {
+        let response = self
+            .http
+            .post(format!("{}/transcript", self.base_url))
+            .header("Authorization", &self.api_key)
+            .json(&AssemblyAiTranscriptRequest {
+                audio_url,
+                speech_model: "nano",
+                word_boost: &options.word_boost,
+                language_code: options.language.as_deref(),
+                language_detection: options.language.is_none(),
+            })
+            .send()
+            .await
+            .map_err(|e| format!("Create transcript failed: {}", e))?;
+
+        error_for_status(response, "Create transcript")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse create response: {}", e))
+    }
+
+    async fn get_transcript(&self, transcript_id: &str) -> Result<AssemblyAiTranscript, String> {
+        let response = self
+            .http
+            .get(format!("{}/transcript/{}", self.base_url, transcript_id))
+            .header("Authorization", &self.api_key)
+            .send()
+            .await
+            .map_err(|e| format!("Poll failed: {}", e))?;
+
+        error_for_status(response, "Poll")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse poll response: {}", e))
+    }
+
+    /// Upload audio and wait for the finished transcript.
+    ///
+    /// `on_status` is called once per status change. Setting `cancel` to true
+    /// stops the job; the transcript is then abandoned on AssemblyAI's side.
+    pub async fn run_job(
+        &self,
+        audio: Vec<u8>,
+        options: &JobOptions,
+        cancel: watch::Receiver<bool>,
+        mut on_status: impl FnMut(JobStatus),
+    ) -> Result<AssemblyAiTranscript, JobError> {
+        let work = self.upload_and_poll(audio, options, &mut on_status);
+
+        tokio::select! {
+            result = tokio::time::timeout(options.timeout, work) => match result {
+                Ok(result) => result,
+                Err(_) => Err(JobError::TimedOut(options.timeout)),
+            },
+            _ = cancelled(cancel) => Err(JobError::Cancelled),
+        }
+    }
+
+    async fn upload_and_poll(
+        &self,
+        audio: Vec<u8>,
+        options: &JobOptions,
+        on_status: &mut impl FnMut(JobStatus),
+    ) -> Result<AssemblyAiTranscript, JobError> {
+        on_status(JobStatus::Uploading);
+        let upload_url = self.upload(audio).await.map_err(JobError::Failed)?;
+
+        let created = self
+            .create_transcript(&upload_url, options)
+            .await
+            .map_err(JobError::Failed)?;
+        let transcript_id = created
+            .id
+            .ok_or_else(|| JobError::Failed("No transcript ID returned".to_string()))?;
+
+        let mut last_status = None;
+        loop {
+            let transcript = self
+                .get_transcript(&transcript_id)
+                .await
+                .map_err(JobError::Failed)?;
+
+            let status = match transcript.status.as_deref() {
+                Some("queued") => JobStatus::Queued,
+                Some("processing") => JobStatus::Processing,
+                Some("completed") => return Ok(transcript),
+                Some("error") => {
+                    return Err(JobError::Failed(
+                        transcript.error.unwrap_or("Unknown error".to_string()),
+                    ))
+                }
+                other => {
+                    tracing::warn!("Unexpected AssemblyAI status: {:?}", other);
+                    JobStatus::Processing
+                }
+            };
+
+            if last_status != Some(status) {
+                last_status = Some(status);
+                on_status(status);
+            }
+
+            tokio::time::sleep(options.poll_interval).await;
+        }
+    }
+}
+
+/// Turn a non-2xx response into an error carrying AssemblyAI's message
+async fn error_for_status(
+    response: reqwest::Response,
+    action: &str,
+) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v["error"].as_str().map(String::from))
+        .unwrap_or(body);
+    Err(format!("{} failed: HTTP {}: {}", action, status, message))
+}
+
+/// Resolves once the job is cancelled; never resolves if the sender goes away
+pub(super) async fn cancelled(mut cancel: watch::Receiver<bool>) {
+    while !*cancel.borrow_and_update() {
+        if cancel.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn transcribe_with_assemblyai(
+    app: AppHandle,
+    api_key: String,
+    audio_data: Vec<i16>,
+    job_id: Option<String>,
+    timeout_secs: Option<u64>,
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<String, String> {
+    let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let vocabulary = Vocabulary::load(&app);
+    let options = JobOptions {
+        timeout: timeout_secs.map(Duration::from_secs).unwrap_or(DEFAULT_TIMEOUT),
+        word_boost: vocabulary.assemblyai_word_boost(),
+        language: LanguageConfig::load(&app).fixed_language().map(String::from),
+        ..Default::default()
+    };
+
+    let cancel_rx = state.lock().await.start_job(&job_id)?;
+
+    let started = Instant::now();
+    let emit_status = |status: JobStatus, error: Option<String>| {
+        let _ = app.emit(
+            "transcription-job-progress",
+            JobProgress {
+                job_id: job_id.clone(),
+                provider: "assemblyai".to_string(),
+                status,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                error,
+                completed_chunks: None,
+                total_chunks: None,
+            },
+        );
+    };
+
+    let result = match encode_wav(&audio_data) {
+        Ok(wav) => {
+            AssemblyAiClient::new(api_key)
+                .run_job(wav, &options, cancel_rx, |status| emit_status(status, None))
+                .await
+        }
+        Err(e) => Err(JobError::Failed(e)),
+    };
+
+    state.lock().await.transcription_jobs.remove(&job_id);
+
+    let transcript = match result {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            tracing::warn!("AssemblyAI job {} ended: {}", job_id, e);
+            emit_status(e.status(), Some(e.to_string()));
+            return Err(e.into());
+        }
+    };
+    emit_status(JobStatus::Completed, None);
+    let audio_secs = samples_to_ms(audio_data.len()) as f64 / 1000.0;
+    record_usage(&app, UsageProvider::AssemblyAi, &job_id, audio_secs);
+
+    let replacer = vocabulary.replacer();
+    let text = replacer.apply(transcript.text.as_deref().unwrap_or_default());
+    let _ = app.emit(
+        "transcript",
+        TranscriptEvent {
+            text: text.clone(),
+            is_final: true,
+            confidence: transcript.overall_confidence(),
+            source: "assemblyai".to_string(),
+            low_confidence_spans: low_confidence_spans(&transcript.scored_words()),
+            start_ms: Some(0),
+            end_ms: Some(samples_to_ms(audio_data.len())),
+            language: transcript.language(),
+            segments: transcript.segments(&replacer),
+        },
+    );
+    Ok(text)
+}
+
+/// Cancel a running batch transcription job
+#[tauri::command]
+pub async fn cancel_transcription_job(
+    job_id: String,
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<(), String> {
+    let cancel = state
+        .lock()
+        .await
+        .transcription_jobs
+        .remove(&job_id)
+        .ok_or_else(|| format!("No running transcription job {}", job_id))?;
+
+    let _ = cancel.send(true);
+    tracing::info!("Cancelled transcription job {}", job_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::test_support::stand_in;
+
+    const UPLOAD: (u16, &str) = (200, r#"{"upload_url": "https://cdn.example/audio"}"#);
+    const CREATED: (u16, &str) = (200, r#"{"id": "t1", "status": "queued"}"#);
+    const PROCESSING: (u16, &str) = (200, r#"{"id": "t1", "status": "processing"}"#);
+
+    fn fast_options(timeout_ms: u64) -> JobOptions {
+        JobOptions {
+            timeout: Duration::from_millis(timeout_ms),
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_job_reports_status_changes_until_completed() {
+        let server = stand_in(&[
+            ("POST /upload", &[UPLOAD]),
+            ("POST /transcript", &[CREATED]),
+            (
+                "GET /transcript/t1",
+                &[
+                    (200, r#"{"id": "t1", "status": "queued"}"#),
+                    PROCESSING,
+                    PROCESSING,
+                    (
+                        200,
+                        r#"{"id": "t1", "status": "completed", "text": "Hello", "confidence": 0.93, "language_code": "en_us"}"#,
+                    ),
+                ],
+            ),
+        ])
+        .await;
+
+        let client = AssemblyAiClient::with_base_url("key".to_string(), server.url.clone());
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let mut statuses = Vec::new();
+        let options = JobOptions {
+            word_boost: vec!["Aurus".to_string()],
+            ..fast_options(5000)
+        };
+
+        let transcript = client
+            .run_job(vec![0; 16], &options, cancel_rx, |s| statuses.push(s))
+            .await
+            .unwrap();
+
+        assert_eq!(transcript.text.as_deref(), Some("Hello"));
+        assert!((transcript.overall_confidence() - 0.93).abs() < f32::EPSILON);
+        assert_eq!(transcript.language().as_deref(), Some("en"));
+        assert_eq!(
+            statuses,
+            vec![JobStatus::Uploading, JobStatus::Queued, JobStatus::Processing]
+        );
+
+        let create = server.requests("POST /transcript");
+        assert_eq!(create.len(), 1);
+        assert!(create[0].body_text().contains(r#""audio_url":"https://cdn.example/audio""#));
+        assert!(create[0].body_text().contains(r#""word_boost":["Aurus"]"#));
+        assert!(create[0].body_text().contains(r#""language_detection":true"#));
+    }
+
+    #[tokio::test]
+    async fn test_job_surfaces_service_errors() {
+        let server = stand_in(&[
+            ("POST /upload", &[UPLOAD]),
+            ("POST /transcript", &[CREATED]),
+            ("GET /transcript/t1", &[(200, r#"{"id": "t1", "status": "error", "error": "Audio too short"}"#)]),
+        ])
+        .await;
+
+        let client = AssemblyAiClient::with_base_url("key".to_string(), server.url);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let result = client.run_job(vec![0; 16], &fast_options(5000), cancel_rx, |_| {}).await;
+
+        assert_eq!(result.unwrap_err(), JobError::Failed("Audio too short".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejection_is_reported() {
+        let server = stand_in(&[("POST /upload", &[(401, r#"{"error": "Invalid API key"}"#)])]).await;
+
+        let client = AssemblyAiClient::with_base_url("bad".to_string(), server.url);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let result = client.run_job(vec![0; 16], &fast_options(5000), cancel_rx, |_| {}).await;
+
+        match result {
+            Err(JobError::Failed(msg)) => assert!(msg.contains("Invalid API key"), "{}", msg),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_job_times_out() {
+        let server = stand_in(&[
+            ("POST /upload", &[UPLOAD]),
+            ("POST /transcript", &[CREATED]),
+            ("GET /transcript/t1", &[PROCESSING]),
+        ])
+        .await;
+
+        let client = AssemblyAiClient::with_base_url("key".to_string(), server.url);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let result = client.run_job(vec![0; 16], &fast_options(100), cancel_rx, |_| {}).await;
+
+        assert_eq!(result.unwrap_err(), JobError::TimedOut(Duration::from_millis(100)));
+    }
+
+    #[tokio::test]
+    async fn test_job_can_be_cancelled() {
+        let server = stand_in(&[
+            ("POST /upload", &[UPLOAD]),
+            ("POST /transcript", &[CREATED]),
+            ("GET /transcript/t1", &[PROCESSING]),
+        ])
+        .await;
+
+        let client = AssemblyAiClient::with_base_url("key".to_string(), server.url);
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+
+        let result = client
+            .run_job(vec![0; 16], &fast_options(5000), cancel_rx, |status| {
+                if status == JobStatus::Processing {
+                    let _ = cancel_tx.send(true);
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap_err(), JobError::Cancelled);
+    }
+
+    #[test]
+    fn test_assemblyai_confidence() {
+        let completed: AssemblyAiTranscript = serde_json::from_str(
+            r#"{"id": "t1", "status": "completed", "text": "Hi there", "confidence": 0.82,
+                "words": [{"text": "Hi", "start": 0, "end": 200, "confidence": 0.99},
+                          {"text": "there", "start": 200, "end": 500, "confidence": 0.31}]}"#,
+        )
+        .unwrap();
+        assert!((completed.overall_confidence() - 0.82).abs() < f32::EPSILON);
+        let spans = low_confidence_spans(&completed.scored_words());
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].text, "there");
+
+        let queued: AssemblyAiTranscript =
+            serde_json::from_str(r#"{"id": "t1", "status": "queued"}"#).unwrap();
+        assert_eq!(queued.overall_confidence(), 0.0);
+        assert!(queued.scored_words().is_empty());
+    }
+}
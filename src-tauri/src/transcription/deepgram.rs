@@ -0,0 +1,381 @@
+//! Messages of the Deepgram live API.
+//!
+//! Every text frame carries a `type` tag, so frames deserialize into
+//! [`DeepgramMessage`] instead of being probed for a `channel` field.
+//! Failures (rejected handshakes, error frames, close codes) are turned into
+//! messages that tell the user what to do about them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::language::normalize_language;
+use super::{dominant_language, ScoredWord};
+
+/// A text frame from the live API
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum DeepgramMessage {
+    Results(DeepgramResults),
+    Metadata(DeepgramMetadata),
+    SpeechStarted(SpeechStarted),
+    UtteranceEnd(UtteranceEnd),
+    Error(DeepgramErrorFrame),
+    /// Message types this client doesn't know yet
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeepgramResults {
+    pub channel: DeepgramChannel,
+    #[serde(default)]
+    pub is_final: bool,
+    /// Seconds from stream start
+    pub start: Option<f64>,
+    pub duration: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeepgramChannel {
+    pub alternatives: Vec<DeepgramAlternative>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeepgramAlternative {
+    pub transcript: String,
+    pub confidence: f32,
+    #[serde(default)]
+    pub words: Vec<DeepgramWord>,
+    /// Languages heard, most prominent first (multilingual mode only)
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeepgramWord {
+    pub word: String,
+    /// Seconds from stream start
+    pub start: f64,
+    pub end: f64,
+    pub confidence: f32,
+    /// Detected language of this word (multilingual mode only)
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl DeepgramAlternative {
+    pub fn scored_words(&self) -> Vec<ScoredWord> {
+        self.words
+            .iter()
+            .map(|w| ScoredWord {
+                text: w.word.clone(),
+                start_ms: (w.start * 1000.0) as i64,
+                end_ms: (w.end * 1000.0) as i64,
+                confidence: w.confidence,
+            })
+            .collect()
+    }
+
+    /// Language most of the words are in, else the most prominent one reported
+    pub fn language(&self) -> Option<String> {
+        dominant_language(self.words.iter().filter_map(|w| w.language.as_deref()))
+            .or_else(|| self.languages.first().and_then(|l| normalize_language(l)))
+    }
+}
+
+/// Control message sent to the live API
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub(crate) enum DeepgramControl {
+    /// Flush remaining results and metadata, then close the connection
+    CloseStream,
+}
+
+impl DeepgramControl {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Summary sent once the stream is finished
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeepgramMetadata {
+    pub request_id: String,
+    #[serde(default)]
+    pub created: Option<String>,
+    /// Seconds of audio processed
+    #[serde(default)]
+    pub duration: Option<f64>,
+    /// Keyed by model UUID
+    #[serde(default)]
+    pub model_info: HashMap<String, ModelInfo>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ModelInfo {
+    pub name: String,
+    pub version: String,
+    pub arch: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SpeechStarted {
+    /// Seconds from stream start
+    pub timestamp: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UtteranceEnd {
+    /// End of the last word before the pause, seconds from stream start
+    pub last_word_end: f64,
+}
+
+/// Error frame; Deepgram has used both field sets
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeepgramErrorFrame {
+    #[serde(default)]
+    pub err_code: Option<String>,
+    #[serde(default)]
+    pub err_msg: Option<String>,
+    #[serde(default)]
+    pub variant: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl DeepgramErrorFrame {
+    pub fn code(&self) -> Option<&str> {
+        self.err_code.as_deref().or(self.variant.as_deref())
+    }
+
+    pub fn detail(&self) -> &str {
+        self.err_msg
+            .as_deref()
+            .or(self.description.as_deref())
+            .or(self.message.as_deref())
+            .unwrap_or("unknown error")
+    }
+}
+
+/// Payload of the `deepgram-metadata` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DeepgramMetadataEvent {
+    pub request_id: String,
+    pub models: Vec<ModelInfo>,
+    pub duration_secs: Option<f64>,
+    pub created: Option<String>,
+}
+
+impl From<DeepgramMetadata> for DeepgramMetadataEvent {
+    fn from(metadata: DeepgramMetadata) -> Self {
+        let mut models: Vec<ModelInfo> = metadata.model_info.into_values().collect();
+        models.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            request_id: metadata.request_id,
+            models,
+            duration_secs: metadata.duration,
+            created: metadata.created,
+        }
+    }
+}
+
+/// What the stream was opened with, for error messages
+pub(crate) struct StreamSetup<'a> {
+    pub model: &'a str,
+    pub language: &'a str,
+}
+
+/// Turn a Deepgram failure into a message the user can act on.
+///
+/// `status` is the HTTP status of a rejected handshake, `code` the error code
+/// of an error frame or close reason (e.g. `DATA-0000`).
+pub(crate) fn actionable_error(status: Option<u16>, code: Option<&str>, detail: &str, setup: &StreamSetup) -> String {
+    let code = code.unwrap_or_default().to_ascii_uppercase();
+    let lower = detail.to_lowercase();
+
+    if matches!(status, Some(401 | 403)) || code.contains("AUTH") || lower.contains("invalid credentials") {
+        return "Deepgram rejected the API key. Check the Deepgram key in Settings.".to_string();
+    }
+    if status == Some(402)
+        || code.contains("PAYMENT")
+        || code.contains("QUOTA")
+        || lower.contains("credits")
+        || lower.contains("balance")
+    {
+        return "Deepgram quota exceeded: the project has run out of credits. \
+                Add credits in the Deepgram console or switch to local Whisper."
+            .to_string();
+    }
+    if status == Some(429) || code.contains("TOO_MANY") {
+        return "Deepgram is rate limiting this key (too many concurrent streams). Try again shortly."
+            .to_string();
+    }
+    if status == Some(400) && (lower.contains("language") || lower.contains("model") || lower.contains("combination")) {
+        return format!(
+            "Deepgram doesn't support language '{}' with model '{}'. \
+             Pick another language or turn on language detection.",
+            setup.language, setup.model
+        );
+    }
+    if code.starts_with("DATA-") {
+        return "Deepgram could not decode the audio stream.".to_string();
+    }
+    if code.starts_with("NET-") {
+        return "Deepgram closed the stream because no audio arrived in time.".to_string();
+    }
+
+    match status {
+        Some(status) => format!("Deepgram rejected the connection (HTTP {}): {}", status, detail),
+        None => format!("Deepgram error: {}", detail),
+    }
+}
+
+/// Split a close reason such as `"DATA-0000: payload cannot be decoded"` into code and text
+pub(crate) fn split_close_reason(reason: &str) -> (Option<&str>, &str) {
+    let is_code = |code: &str| {
+        code.len() >= 6
+            && code.contains('-')
+            && code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-')
+    };
+    let reason = reason.trim();
+    match reason.split_once(':') {
+        Some((code, rest)) if is_code(code) => (Some(code), rest.trim()),
+        _ if is_code(reason) => (Some(reason), ""),
+        _ => (None, reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETUP: StreamSetup = StreamSetup {
+        model: "nova-2",
+        language: "xx",
+    };
+
+    #[test]
+    fn test_parse_message_types() {
+        let results: DeepgramMessage = serde_json::from_str(
+            r#"{"type": "Results", "is_final": true, "speech_final": true, "start": 1.5, "duration": 2.0,
+                "channel_index": [0, 1], "metadata": {"request_id": "r1"},
+                "channel": {"alternatives": [{"transcript": "Hello", "confidence": 0.98, "words": []}]}}"#,
+        )
+        .unwrap();
+        match results {
+            DeepgramMessage::Results(r) => {
+                assert!(r.is_final);
+                assert_eq!(r.channel.alternatives[0].transcript, "Hello");
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let metadata: DeepgramMessage = serde_json::from_str(
+            r#"{"type": "Metadata", "transaction_key": "deprecated", "request_id": "r1",
+                "sha256": "abc", "created": "2026-10-18T09:00:00.000Z", "duration": 12.5, "channels": 1,
+                "models": ["m1"], "model_info": {"m1": {"name": "2-general-nova", "version": "2024-01-09", "arch": "nova-2"}}}"#,
+        )
+        .unwrap();
+        let DeepgramMessage::Metadata(metadata) = metadata else {
+            panic!("expected metadata");
+        };
+        let event = DeepgramMetadataEvent::from(metadata);
+        assert_eq!(event.request_id, "r1");
+        assert_eq!(event.duration_secs, Some(12.5));
+        assert_eq!(event.models[0].arch, "nova-2");
+
+        let started: DeepgramMessage =
+            serde_json::from_str(r#"{"type": "SpeechStarted", "channel": [0], "timestamp": 0.42}"#).unwrap();
+        assert!(matches!(started, DeepgramMessage::SpeechStarted(SpeechStarted { timestamp }) if timestamp == 0.42));
+
+        let utterance_end: DeepgramMessage =
+            serde_json::from_str(r#"{"type": "UtteranceEnd", "channel": [0, 1], "last_word_end": 3.1}"#).unwrap();
+        assert!(matches!(utterance_end, DeepgramMessage::UtteranceEnd(UtteranceEnd { last_word_end }) if last_word_end == 3.1));
+
+        let unknown: DeepgramMessage = serde_json::from_str(r#"{"type": "SomethingNew", "x": 1}"#).unwrap();
+        assert!(matches!(unknown, DeepgramMessage::Unknown));
+    }
+
+    #[test]
+    fn test_error_frames() {
+        let frame: DeepgramMessage = serde_json::from_str(
+            r#"{"type": "Error", "variant": "ASR_PAYMENT_REQUIRED", "description": "Project does not have enough credits"}"#,
+        )
+        .unwrap();
+        let DeepgramMessage::Error(frame) = frame else {
+            panic!("expected error frame");
+        };
+        assert!(actionable_error(None, frame.code(), frame.detail(), &SETUP).contains("quota exceeded"));
+
+        let legacy: DeepgramErrorFrame =
+            serde_json::from_str(r#"{"err_code": "DATA-0000", "err_msg": "bad audio"}"#).unwrap();
+        assert_eq!(legacy.detail(), "bad audio");
+        assert_eq!(
+            actionable_error(None, legacy.code(), legacy.detail(), &SETUP),
+            "Deepgram could not decode the audio stream."
+        );
+    }
+
+    #[test]
+    fn test_handshake_errors() {
+        assert!(actionable_error(Some(401), None, "Invalid credentials.", &SETUP).contains("API key"));
+        assert!(actionable_error(Some(402), None, "", &SETUP).contains("quota exceeded"));
+        assert!(actionable_error(Some(429), None, "", &SETUP).contains("rate limiting"));
+        assert_eq!(
+            actionable_error(Some(400), None, "No such model/language/tier combination found.", &SETUP),
+            "Deepgram doesn't support language 'xx' with model 'nova-2'. \
+             Pick another language or turn on language detection."
+        );
+        assert_eq!(
+            actionable_error(Some(500), None, "oops", &SETUP),
+            "Deepgram rejected the connection (HTTP 500): oops"
+        );
+    }
+
+    #[test]
+    fn test_control_messages() {
+        assert_eq!(DeepgramControl::CloseStream.to_json(), r#"{"type":"CloseStream"}"#);
+    }
+
+    #[test]
+    fn test_split_close_reason() {
+        assert_eq!(
+            split_close_reason("DATA-0000: The payload cannot be decoded as audio."),
+            (Some("DATA-0000"), "The payload cannot be decoded as audio.")
+        );
+        assert_eq!(split_close_reason("NET-0001"), (Some("NET-0001"), ""));
+        assert_eq!(split_close_reason("Note: bye"), (None, "Note: bye"));
+    }
+
+    #[test]
+    fn test_segment_language() {
+        let message: DeepgramMessage = serde_json::from_str(
+            r#"{"type": "Results", "is_final": true, "channel": {"alternatives": [{
+                "transcript": "Das Deployment is done",
+                "confidence": 0.9,
+                "languages": ["en", "de"],
+                "words": [
+                    {"word": "das", "start": 0.0, "end": 0.2, "confidence": 0.9, "language": "de"},
+                    {"word": "deployment", "start": 0.2, "end": 0.8, "confidence": 0.9, "language": "de"},
+                    {"word": "is", "start": 0.8, "end": 0.9, "confidence": 0.9, "language": "en"}
+                ]
+            }]}}"#,
+        )
+        .unwrap();
+        let DeepgramMessage::Results(results) = message else {
+            panic!("expected results");
+        };
+        assert_eq!(results.channel.alternatives[0].language().as_deref(), Some("de"));
+
+        let without_words = DeepgramAlternative {
+            transcript: "Hello".to_string(),
+            confidence: 0.9,
+            words: Vec::new(),
+            languages: vec!["en".to_string()],
+        };
+        assert_eq!(without_words.language().as_deref(), Some("en"));
+    }
+}
@@ -0,0 +1,445 @@
+//! Transcript export: SRT and WebVTT subtitles, plain text, Markdown and a
+//! lossless JSON format that can be read back in.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// Bumped when the JSON export changes incompatibly
+const JSON_FORMAT_VERSION: u32 = 1;
+
+/// Subtitle line length recommended by most broadcast style guides
+const MAX_LINE_CHARS: usize = 42;
+const MAX_CUE_LINES: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimedSegment {
+    /// Milliseconds from the start of the recording
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// ISO 639-1 code of the spoken language
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TimedTranscript {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub segments: Vec<TimedSegment>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Txt,
+    Md,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Txt => "txt",
+            Self::Md => "md",
+            Self::Json => "json",
+        }
+    }
+
+    fn filter_name(self) -> &'static str {
+        match self {
+            Self::Srt => "SubRip Subtitles",
+            Self::Vtt => "WebVTT Subtitles",
+            Self::Txt => "Text",
+            Self::Md => "Markdown",
+            Self::Json => "JSON",
+        }
+    }
+}
+
+/// Lossless export wrapper, so future versions can still read old files
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonExport {
+    format_version: u32,
+    #[serde(flatten)]
+    transcript: TimedTranscript,
+}
+
+/// `HH:MM:SS` followed by `separator` and milliseconds
+fn timestamp(ms: i64, separator: char) -> String {
+    let ms = ms.max(0);
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// `MM:SS`, or `H:MM:SS` past the first hour
+fn short_timestamp(ms: i64) -> String {
+    let secs = ms.max(0) / 1000;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Greedy word wrap at `width` characters
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+struct Cue {
+    start_ms: i64,
+    end_ms: i64,
+    lines: Vec<String>,
+    speaker: Option<String>,
+    /// Set only where it differs from the transcript's main language
+    language: Option<String>,
+}
+
+/// Split segments into readable cues of at most two wrapped lines, sharing
+/// each segment's time span in proportion to text length
+fn subtitle_cues(transcript: &TimedTranscript) -> Vec<Cue> {
+    let mut cues = Vec::new();
+
+    for segment in &transcript.segments {
+        let lines = wrap(&segment.text, MAX_LINE_CHARS);
+        if lines.is_empty() {
+            continue;
+        }
+
+        let groups: Vec<Vec<String>> = lines.chunks(MAX_CUE_LINES).map(<[String]>::to_vec).collect();
+        let total_chars: usize = groups.iter().flatten().map(|l| l.chars().count()).sum();
+        let duration = (segment.end_ms - segment.start_ms).max(0);
+
+        let mut consumed = 0;
+        for group in groups {
+            let chars: usize = group.iter().map(|l| l.chars().count()).sum();
+            let start_ms = segment.start_ms + duration * consumed as i64 / total_chars.max(1) as i64;
+            consumed += chars;
+            let end_ms = segment.start_ms + duration * consumed as i64 / total_chars.max(1) as i64;
+            cues.push(Cue {
+                start_ms,
+                end_ms,
+                lines: group,
+                speaker: segment.speaker.clone(),
+                language: segment
+                    .language
+                    .clone()
+                    .filter(|l| transcript.language.as_ref() != Some(l)),
+            });
+        }
+    }
+
+    cues
+}
+
+pub fn to_srt(transcript: &TimedTranscript) -> String {
+    let mut out = String::new();
+    for (i, cue) in subtitle_cues(transcript).iter().enumerate() {
+        let text = match &cue.speaker {
+            Some(speaker) => format!("{}: {}", speaker, cue.lines.join("\n")),
+            None => cue.lines.join("\n"),
+        };
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            timestamp(cue.start_ms, ','),
+            timestamp(cue.end_ms, ','),
+            text
+        ));
+    }
+    out
+}
+
+/// Escape text for a WebVTT cue, where `&`, `<` and `>` start markup; this
+/// also keeps "-->" from ending the cue timing line early
+fn vtt_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub fn to_vtt(transcript: &TimedTranscript) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in subtitle_cues(transcript) {
+        let text = vtt_escape(&cue.lines.join("\n"));
+        let text = match &cue.language {
+            Some(language) => format!("<lang {}>{}</lang>", vtt_escape(language), text),
+            None => text,
+        };
+        let text = match &cue.speaker {
+            Some(speaker) => format!("<v {}>{}", vtt_escape(speaker), text),
+            None => text,
+        };
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(cue.start_ms, '.'),
+            timestamp(cue.end_ms, '.'),
+            text
+        ));
+    }
+    out
+}
+
+pub fn to_txt(transcript: &TimedTranscript) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current_speaker: Option<&str> = None;
+
+    for segment in &transcript.segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match segment.speaker.as_deref() {
+            Some(speaker) if current_speaker != Some(speaker) => {
+                paragraphs.push(format!("{}: {}", speaker, text));
+                current_speaker = Some(speaker);
+            }
+            _ if paragraphs.is_empty() => paragraphs.push(text.to_string()),
+            _ => {
+                let last = paragraphs.last_mut().expect("checked above");
+                last.push(' ');
+                last.push_str(text);
+            }
+        }
+    }
+
+    let mut out = paragraphs.join("\n\n");
+    out.push('\n');
+    out
+}
+
+pub fn to_markdown(transcript: &TimedTranscript) -> String {
+    let mut out = format!("# {}\n\n", transcript.title.as_deref().unwrap_or("Transcript"));
+    if let Some(language) = &transcript.language {
+        out.push_str(&format!("*Language: {}*\n\n", language));
+    }
+
+    let mut current_speaker: Option<&str> = None;
+    for segment in &transcript.segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(speaker) = segment.speaker.as_deref() {
+            if current_speaker != Some(speaker) {
+                out.push_str(&format!("## {}\n\n", speaker));
+                current_speaker = Some(speaker);
+            }
+        }
+        out.push_str(&format!("**[{}]** {}\n\n", short_timestamp(segment.start_ms), text));
+    }
+
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+pub fn to_json(transcript: &TimedTranscript) -> Result<String, String> {
+    serde_json::to_string_pretty(&JsonExport {
+        format_version: JSON_FORMAT_VERSION,
+        transcript: transcript.clone(),
+    })
+    .map_err(|e| format!("Failed to serialize transcript: {}", e))
+}
+
+/// Read back a transcript written by `to_json`
+pub fn from_json(json: &str) -> Result<TimedTranscript, String> {
+    let export: JsonExport =
+        serde_json::from_str(json).map_err(|e| format!("Invalid transcript file: {}", e))?;
+    if export.format_version > JSON_FORMAT_VERSION {
+        return Err(format!(
+            "Transcript file version {} is newer than supported ({})",
+            export.format_version, JSON_FORMAT_VERSION
+        ));
+    }
+    Ok(export.transcript)
+}
+
+pub fn render(transcript: &TimedTranscript, format: ExportFormat) -> Result<String, String> {
+    match format {
+        ExportFormat::Srt => Ok(to_srt(transcript)),
+        ExportFormat::Vtt => Ok(to_vtt(transcript)),
+        ExportFormat::Txt => Ok(to_txt(transcript)),
+        ExportFormat::Md => Ok(to_markdown(transcript)),
+        ExportFormat::Json => to_json(transcript),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Export the transcript to a file chosen in a save dialog
+#[tauri::command]
+pub async fn export_transcript(
+    app: AppHandle,
+    transcript: TimedTranscript,
+    format: ExportFormat,
+    filename: Option<String>,
+) -> Result<String, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    if transcript.segments.iter().all(|s| s.text.trim().is_empty()) {
+        return Err("No transcript to export.".to_string());
+    }
+
+    let content = render(&transcript, format)?;
+
+    let default_name = filename.unwrap_or_else(|| {
+        let now = chrono::Local::now();
+        format!("transcript-{}.{}", now.format("%Y-%m-%d-%H%M"), format.extension())
+    });
+
+    let file_path = app
+        .dialog()
+        .file()
+        .set_file_name(&default_name)
+        .add_filter(format.filter_name(), &[format.extension()])
+        .blocking_save_file();
+
+    match file_path {
+        Some(path) => {
+            let path_str = path.to_string();
+            std::fs::write(&path_str, content).map_err(|e| format!("Failed to write file: {}", e))?;
+            tracing::info!("Transcript exported as {:?} to {}", format, path_str);
+            Ok(format!("Transcript saved to {}", path_str))
+        }
+        None => Err("Export cancelled.".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: i64, end_ms: i64, text: &str, speaker: Option<&str>) -> TimedSegment {
+        TimedSegment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            speaker: speaker.map(String::from),
+            confidence: None,
+            language: None,
+        }
+    }
+
+    fn transcript() -> TimedTranscript {
+        TimedTranscript {
+            title: Some("Standup".to_string()),
+            language: Some("en".to_string()),
+            segments: vec![
+                segment(0, 2500, "Good morning everyone.", Some("Anna")),
+                segment(2500, 5000, "Let's start with the release.", Some("Anna")),
+                segment(3_725_040, 3_727_000, "Sounds good.", Some("Ben")),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_timestamps() {
+        assert_eq!(timestamp(3_725_040, ','), "01:02:05,040");
+        assert_eq!(timestamp(999, '.'), "00:00:00.999");
+        assert_eq!(short_timestamp(65_000), "01:05");
+        assert_eq!(short_timestamp(3_725_040), "1:02:05");
+    }
+
+    #[test]
+    fn test_srt() {
+        let srt = to_srt(&transcript());
+        assert!(srt.starts_with("1\n00:00:00,000 --> 00:00:02,500\nAnna: Good morning everyone.\n\n2\n"));
+        assert!(srt.contains("3\n01:02:05,040 --> 01:02:07,000\nBen: Sounds good.\n\n"));
+    }
+
+    #[test]
+    fn test_vtt() {
+        let vtt = to_vtt(&transcript());
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:02.500\n<v Anna>Good morning everyone.\n\n"));
+
+        // Code-switched segments are tagged; the main language is not
+        let mut mixed = transcript();
+        mixed.segments[0].language = Some("en".to_string());
+        mixed.segments[1].language = Some("de".to_string());
+        mixed.segments[1].text = "Fangen wir mit dem Release an.".to_string();
+        let vtt = to_vtt(&mixed);
+        assert!(vtt.contains("\n<v Anna>Good morning everyone.\n"));
+        assert!(vtt.contains("\n<v Anna><lang de>Fangen wir mit dem Release an.</lang>\n"));
+    }
+
+    #[test]
+    fn test_vtt_escapes_markup() {
+        let mut escaped = transcript();
+        escaped.segments.truncate(1);
+        escaped.segments[0].text = "R&D says a < b --> c.".to_string();
+        escaped.segments[0].speaker = Some("<Anna>".to_string());
+        let vtt = to_vtt(&escaped);
+        assert!(vtt.ends_with("\n<v &lt;Anna&gt;>R&amp;D says a &lt; b --&gt; c.\n\n"), "{}", vtt);
+        assert_eq!(vtt.matches("-->").count(), 1);
+    }
+
+    #[test]
+    fn test_long_segments_are_split_into_cues() {
+        let text = "This is a rather long sentence that will never fit on a single subtitle line \
+                    and therefore has to be split across several cues by the exporter.";
+        let long = TimedTranscript {
+            segments: vec![segment(0, 10_000, text, None)],
+            ..Default::default()
+        };
+        let cues = subtitle_cues(&long);
+        assert!(cues.len() >= 2);
+        assert!(cues.iter().all(|c| c.lines.len() <= MAX_CUE_LINES));
+        assert!(cues.iter().flat_map(|c| &c.lines).all(|l| l.chars().count() <= MAX_LINE_CHARS));
+        assert_eq!(cues.first().unwrap().start_ms, 0);
+        assert_eq!(cues.last().unwrap().end_ms, 10_000);
+        assert!(cues.windows(2).all(|w| w[0].end_ms == w[1].start_ms));
+    }
+
+    #[test]
+    fn test_txt_and_markdown_group_by_speaker() {
+        assert_eq!(
+            to_txt(&transcript()),
+            "Anna: Good morning everyone. Let's start with the release.\n\nBen: Sounds good.\n"
+        );
+
+        let md = to_markdown(&transcript());
+        assert!(md.starts_with("# Standup\n\n*Language: en*\n\n## Anna\n\n**[00:00]** Good morning everyone.\n\n"));
+        assert!(md.ends_with("## Ben\n\n**[1:02:05]** Sounds good.\n"));
+        assert_eq!(md.matches("## Anna").count(), 1);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut original = transcript();
+        original.segments[0].confidence = Some(0.93);
+        let json = to_json(&original).unwrap();
+        assert!(json.contains("\"format_version\": 1"));
+        assert_eq!(from_json(&json).unwrap(), original);
+
+        let future = json.replace("\"format_version\": 1", "\"format_version\": 99");
+        assert!(from_json(&future).is_err());
+    }
+}
@@ -0,0 +1,428 @@
+//! Hybrid transcription policy: live Deepgram when possible, local Whisper
+//! otherwise.
+//!
+//! The live provider is picked when a session starts, from the stored
+//! Deepgram key and whether the Deepgram API is reachable. If the cloud
+//! stream drops mid-session the session continues on local Whisper. After
+//! the session the full recording can optionally be re-transcribed with a
+//! slower, more accurate provider (desktop only).
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::watch;
+
+use super::assemblyai::{cancelled, AssemblyAiClient, JobError, JobOptions, JobProgress, JobStatus};
+use super::language::LanguageConfig;
+use super::openai_compatible::{OpenAiSttClient, OpenAiSttConfig};
+use super::usage::{record_usage, UsageProvider};
+use super::vocabulary::Vocabulary;
+use super::{
+    encode_wav, low_confidence_spans, open_deepgram_stream, samples_to_ms, stop_live_session,
+    whisper_transcribe, TranscriptEvent, TranscriptionManager,
+};
+use crate::audio;
+use crate::platform::secrets::stored_key;
+use crate::storage;
+use crate::whisper::settings::{DecodingStrategy, WhisperSettings};
+use crate::whisper::streaming::open_local_whisper_stream;
+use crate::whisper::WhisperManager;
+
+const CONFIG_FILE: &str = "hybrid-transcription.json";
+
+const DEEPGRAM_HOST: &str = "api.deepgram.com";
+/// A TCP handshake slower than this is treated as offline
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Re-transcription doesn't need to keep up with real time, so trade speed for accuracy
+const RETRANSCRIBE_BEAM_SIZE: i32 = 5;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveProvider {
+    Deepgram,
+    LocalWhisper,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetranscribeProvider {
+    LocalWhisper,
+    AssemblyAi,
+    OpenAiCompatible,
+}
+
+impl RetranscribeProvider {
+    fn source(self) -> &'static str {
+        match self {
+            Self::LocalWhisper => "whisper-local",
+            Self::AssemblyAi => "assemblyai",
+            Self::OpenAiCompatible => "openai-compatible",
+        }
+    }
+
+    /// Ledger entry for paid providers
+    fn usage(self) -> Option<UsageProvider> {
+        match self {
+            Self::LocalWhisper => None,
+            Self::AssemblyAi => Some(UsageProvider::AssemblyAi),
+            Self::OpenAiCompatible => Some(UsageProvider::OpenAiCompatible),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HybridConfig {
+    /// Use Deepgram when a key is stored and the API is reachable
+    pub prefer_cloud: bool,
+    /// Continue on local Whisper if the Deepgram stream fails mid-session
+    pub fallback_to_local: bool,
+    /// Re-transcribe the whole recording once the session ends
+    pub retranscribe: Option<RetranscribeProvider>,
+}
+
+impl Default for HybridConfig {
+    fn default() -> Self {
+        Self {
+            prefer_cloud: true,
+            fallback_to_local: true,
+            retranscribe: None,
+        }
+    }
+}
+
+impl HybridConfig {
+    pub fn load(app: &AppHandle) -> Self {
+        storage::load_json(app, CONFIG_FILE)
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, CONFIG_FILE, self)
+    }
+}
+
+/// Payload of the `transcription-provider` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProviderSelection {
+    pub provider: LiveProvider,
+    pub reason: String,
+    /// True when this replaces a failed cloud stream mid-session
+    pub is_fallback: bool,
+}
+
+/// Pick the live provider for a new session
+pub fn choose_provider(config: &HybridConfig, has_deepgram_key: bool, deepgram_reachable: bool) -> ProviderSelection {
+    let (provider, reason) = if !config.prefer_cloud {
+        (LiveProvider::LocalWhisper, "Cloud transcription disabled")
+    } else if !has_deepgram_key {
+        (LiveProvider::LocalWhisper, "No Deepgram API key")
+    } else if !deepgram_reachable {
+        (LiveProvider::LocalWhisper, "Deepgram is unreachable")
+    } else {
+        (LiveProvider::Deepgram, "Deepgram available")
+    };
+
+    ProviderSelection {
+        provider,
+        reason: reason.to_string(),
+        is_fallback: false,
+    }
+}
+
+/// Whether a TCP connection to `host:port` can be opened within `timeout`
+pub async fn is_reachable(host: &str, port: u16, timeout: Duration) -> bool {
+    matches!(
+        tokio::time::timeout(timeout, tokio::net::TcpStream::connect((host, port))).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Continue a session on local Whisper after the Deepgram stream failed
+pub(crate) async fn fall_back_to_local(app: &AppHandle, state: &TranscriptionManager, reason: &str) {
+    let whisper = app.state::<WhisperManager>();
+    match open_local_whisper_stream(app, state, whisper.inner()).await {
+        Ok(()) => {
+            tracing::info!("Switched to local Whisper: {}", reason);
+            let _ = app.emit(
+                "transcription-provider",
+                ProviderSelection {
+                    provider: LiveProvider::LocalWhisper,
+                    reason: reason.to_string(),
+                    is_fallback: true,
+                },
+            );
+        }
+        Err(e) => {
+            tracing::error!("Local Whisper fallback failed: {}", e);
+            let _ = app.emit("transcription-stream-failed", e);
+        }
+    }
+}
+
+/// Transcribe the recorded session with `provider`
+async fn retranscribe(
+    app: &AppHandle,
+    provider: RetranscribeProvider,
+    cancel: watch::Receiver<bool>,
+) -> Result<TranscriptEvent, JobError> {
+    let samples = audio::recorded_samples().map_err(JobError::Failed)?;
+    if samples.is_empty() {
+        return Err(JobError::Failed("No audio recorded".to_string()));
+    }
+    let vocabulary = Vocabulary::load(app);
+    let duration_ms = samples_to_ms(samples.len());
+
+    match provider {
+        RetranscribeProvider::LocalWhisper => {
+            let mut settings = WhisperSettings::load(app)
+                .with_vocabulary(&vocabulary)
+                .with_language(&LanguageConfig::load(app));
+            if matches!(settings.strategy, DecodingStrategy::Greedy { .. }) {
+                settings.strategy = DecodingStrategy::BeamSearch {
+                    beam_size: RETRANSCRIBE_BEAM_SIZE,
+                    patience: -1.0,
+                };
+            }
+            let whisper = app.state::<WhisperManager>();
+            tokio::select! {
+                result = whisper_transcribe(app, whisper.inner(), samples, settings, &vocabulary) => {
+                    result.map_err(JobError::Failed)
+                }
+                _ = cancelled(cancel) => Err(JobError::Cancelled),
+            }
+        }
+        RetranscribeProvider::AssemblyAi => {
+            let api_key = stored_key("assembly_ai")
+                .ok_or_else(|| JobError::Failed("No AssemblyAI API key".to_string()))?;
+            let wav = encode_wav(&samples).map_err(JobError::Failed)?;
+            let options = JobOptions {
+                word_boost: vocabulary.assemblyai_word_boost(),
+                language: LanguageConfig::load(app).fixed_language().map(String::from),
+                ..Default::default()
+            };
+            let transcript = AssemblyAiClient::new(api_key)
+                .run_job(wav, &options, cancel, |_| {})
+                .await?;
+            let replacer = vocabulary.replacer();
+            Ok(TranscriptEvent {
+                text: replacer.apply(transcript.text.as_deref().unwrap_or_default()),
+                is_final: true,
+                confidence: transcript.overall_confidence(),
+                source: provider.source().to_string(),
+                low_confidence_spans: low_confidence_spans(&transcript.scored_words()),
+                start_ms: Some(0),
+                end_ms: Some(duration_ms),
+                language: transcript.language(),
+                segments: transcript.segments(&replacer),
+            })
+        }
+        RetranscribeProvider::OpenAiCompatible => {
+            let config = OpenAiSttConfig::load(app);
+            config.validate().map_err(JobError::Failed)?;
+            let prompt = vocabulary.whisper_prompt(None);
+            let transcript = OpenAiSttClient::new(config, stored_key("openai_compatible"))
+                .transcribe(&samples, prompt.as_deref(), cancel, |_, _| {})
+                .await?;
+            let replacer = vocabulary.replacer();
+            Ok(TranscriptEvent {
+                text: replacer.apply(&transcript.text),
+                is_final: true,
+                confidence: transcript.confidence.unwrap_or(0.0),
+                source: provider.source().to_string(),
+                low_confidence_spans: Vec::new(),
+                start_ms: Some(0),
+                end_ms: Some(duration_ms),
+                segments: transcript.timed_segments(&replacer),
+                language: transcript.language,
+            })
+        }
+    }
+}
+
+/// Run a re-transcription job, reporting through `transcription-job-progress`
+/// and delivering the result as `transcript-retranscribed`
+async fn run_retranscribe_job(
+    app: &AppHandle,
+    state: &TranscriptionManager,
+    provider: RetranscribeProvider,
+    job_id: String,
+) -> Result<String, String> {
+    let cancel_rx = state.lock().await.start_job(&job_id)?;
+
+    let started = Instant::now();
+    let emit_status = |status: JobStatus, error: Option<String>| {
+        let _ = app.emit(
+            "transcription-job-progress",
+            JobProgress {
+                job_id: job_id.clone(),
+                provider: provider.source().to_string(),
+                status,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                error,
+                completed_chunks: None,
+                total_chunks: None,
+            },
+        );
+    };
+
+    emit_status(JobStatus::Processing, None);
+    let result = retranscribe(app, provider, cancel_rx).await;
+    state.lock().await.transcription_jobs.remove(&job_id);
+
+    match result {
+        Ok(event) => {
+            emit_status(JobStatus::Completed, None);
+            if let (Some(usage), Some(end_ms)) = (provider.usage(), event.end_ms) {
+                record_usage(app, usage, &job_id, end_ms as f64 / 1000.0);
+            }
+            let _ = app.emit("transcript-retranscribed", event.clone());
+            Ok(event.text)
+        }
+        Err(e) => {
+            tracing::warn!("Re-transcription job {} ended: {}", job_id, e);
+            emit_status(e.status(), Some(e.to_string()));
+            Err(e.into())
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_hybrid_config(app: AppHandle) -> Result<HybridConfig, String> {
+    Ok(HybridConfig::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_hybrid_config(app: AppHandle, config: HybridConfig) -> Result<(), String> {
+    config.save(&app)?;
+    tracing::info!("Hybrid transcription configured: {:?}", config);
+    Ok(())
+}
+
+/// Start live transcription with the best available provider.
+///
+/// Falls back to local Whisper when Deepgram can't be used or its connection
+/// fails; the choice is also emitted as `transcription-provider`.
+#[tauri::command]
+pub async fn start_hybrid_transcription(
+    app: AppHandle,
+    state: tauri::State<'_, TranscriptionManager>,
+    whisper: tauri::State<'_, WhisperManager>,
+) -> Result<ProviderSelection, String> {
+    let config = HybridConfig::load(&app);
+    let api_key = if config.prefer_cloud { stored_key("deepgram") } else { None };
+    let reachable = match api_key {
+        Some(_) => is_reachable(DEEPGRAM_HOST, 443, REACHABILITY_TIMEOUT).await,
+        None => false,
+    };
+
+    let mut selection = choose_provider(&config, api_key.is_some(), reachable);
+
+    if let (LiveProvider::Deepgram, Some(key)) = (selection.provider, &api_key) {
+        match open_deepgram_stream(&app, key, state.inner()).await {
+            Ok(()) => {
+                state.lock().await.session.local_fallback = config.fallback_to_local;
+                tracing::info!("Hybrid session started on Deepgram");
+                let _ = app.emit("transcription-provider", selection.clone());
+                return Ok(selection);
+            }
+            Err(e) if config.fallback_to_local => {
+                tracing::warn!("{}, using local Whisper", e);
+                selection = ProviderSelection {
+                    provider: LiveProvider::LocalWhisper,
+                    reason: e,
+                    is_fallback: false,
+                };
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    open_local_whisper_stream(&app, state.inner(), whisper.inner()).await?;
+    tracing::info!("Hybrid session started on local Whisper: {}", selection.reason);
+    let _ = app.emit("transcription-provider", selection.clone());
+    Ok(selection)
+}
+
+/// Stop the live stream, whichever provider it runs on.
+///
+/// Returns the job ID of the re-transcription when one is configured.
+#[tauri::command]
+pub async fn stop_hybrid_transcription(
+    app: AppHandle,
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<Option<String>, String> {
+    // Waits for Deepgram's final results, so re-transcription and the UI see the whole session
+    stop_live_session(state.inner()).await;
+
+    let Some(provider) = HybridConfig::load(&app).retranscribe else {
+        return Ok(None);
+    };
+
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let manager = state.inner().clone();
+    let id = job_id.clone();
+    tokio::spawn(async move {
+        let _ = run_retranscribe_job(&app, &manager, provider, id).await;
+    });
+    Ok(Some(job_id))
+}
+
+/// Re-transcribe the current recording; cancel with `cancel_transcription_job`
+#[tauri::command]
+pub async fn retranscribe_recording(
+    app: AppHandle,
+    provider: Option<RetranscribeProvider>,
+    job_id: Option<String>,
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<String, String> {
+    let provider = provider
+        .or(HybridConfig::load(&app).retranscribe)
+        .unwrap_or(RetranscribeProvider::LocalWhisper);
+    let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    run_retranscribe_job(&app, state.inner(), provider, job_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_provider() {
+        let config = HybridConfig::default();
+        assert_eq!(choose_provider(&config, true, true).provider, LiveProvider::Deepgram);
+
+        let no_key = choose_provider(&config, false, true);
+        assert_eq!(no_key.provider, LiveProvider::LocalWhisper);
+        assert_eq!(no_key.reason, "No Deepgram API key");
+
+        let offline = choose_provider(&config, true, false);
+        assert_eq!(offline.provider, LiveProvider::LocalWhisper);
+        assert_eq!(offline.reason, "Deepgram is unreachable");
+
+        let local_only = HybridConfig { prefer_cloud: false, ..Default::default() };
+        assert_eq!(choose_provider(&local_only, true, true).provider, LiveProvider::LocalWhisper);
+    }
+
+    #[test]
+    fn test_config_defaults_and_serialization() {
+        let config: HybridConfig = serde_json::from_str(r#"{"retranscribe": "assembly_ai"}"#).unwrap();
+        assert!(config.prefer_cloud);
+        assert!(config.fallback_to_local);
+        assert_eq!(config.retranscribe, Some(RetranscribeProvider::AssemblyAi));
+    }
+
+    #[tokio::test]
+    async fn test_is_reachable() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(is_reachable("127.0.0.1", port, Duration::from_secs(1)).await);
+
+        drop(listener);
+        assert!(!is_reachable("127.0.0.1", port, Duration::from_secs(1)).await);
+    }
+}
@@ -0,0 +1,194 @@
+//! Spoken-language settings and language identification.
+//!
+//! With detection on, Deepgram streams in its multilingual mode and Whisper
+//! reports the language it identified for every window, so meetings that
+//! switch between German and English get a language tag per segment. With
+//! detection off, every provider is pinned to the configured language.
+//! Detection is opt-in: by default German is assumed, which keeps Deepgram
+//! on its configured model.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::storage;
+
+const LANGUAGE_FILE: &str = "transcription-language.json";
+
+/// Deepgram's code-switching mode; only available on Nova-3
+const DEEPGRAM_MULTILINGUAL: &str = "multi";
+const DEEPGRAM_MULTILINGUAL_MODEL: &str = "nova-3";
+
+/// Language names some providers report instead of ISO 639-1 codes
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("german", "de"),
+    ("deutsch", "de"),
+    ("spanish", "es"),
+    ("french", "fr"),
+    ("italian", "it"),
+    ("portuguese", "pt"),
+    ("dutch", "nl"),
+    ("russian", "ru"),
+    ("japanese", "ja"),
+    ("chinese", "zh"),
+    ("korean", "ko"),
+    ("arabic", "ar"),
+    ("polish", "pl"),
+    ("turkish", "tr"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LanguageConfig {
+    /// Identify the spoken language per segment instead of assuming `language`
+    pub detect: bool,
+    /// ISO 639-1 code used when detection is off
+    pub language: String,
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        Self::fixed("de")
+    }
+}
+
+impl LanguageConfig {
+    /// Pinned to a single language
+    pub fn fixed(language: &str) -> Self {
+        Self {
+            detect: false,
+            language: language.to_string(),
+        }
+    }
+
+    pub fn load(app: &AppHandle) -> Self {
+        let config: Self = storage::load_json(app, LANGUAGE_FILE);
+        if config.validate().is_ok() {
+            config
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, LANGUAGE_FILE, self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let code = self.language.as_str();
+        if !(2..=3).contains(&code.len()) || !code.bytes().all(|b| b.is_ascii_lowercase()) {
+            return Err(format!("Invalid language code: {:?} (expected e.g. \"de\")", code));
+        }
+        Ok(())
+    }
+
+    /// The language every segment is in, when detection is off
+    pub fn fixed_language(&self) -> Option<&str> {
+        (!self.detect).then_some(self.language.as_str())
+    }
+
+    /// Deepgram `(model, language)`: multilingual Nova-3 when detecting,
+    /// otherwise `default_model` pinned to the configured language
+    pub fn deepgram_model<'a>(&'a self, default_model: &'a str) -> (&'a str, &'a str) {
+        if self.detect {
+            (DEEPGRAM_MULTILINGUAL_MODEL, DEEPGRAM_MULTILINGUAL)
+        } else {
+            (default_model, self.language.as_str())
+        }
+    }
+}
+
+/// Normalize a provider's language label ("en-US", "English", "de") to an
+/// ISO 639-1 code
+pub fn normalize_language(label: &str) -> Option<String> {
+    let label = label.trim().to_lowercase();
+    let primary = label.split(['-', '_']).next().unwrap_or_default();
+
+    if primary.len() == 2 && primary.bytes().all(|b| b.is_ascii_lowercase()) {
+        return Some(primary.to_string());
+    }
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(name, _)| *name == label)
+        .map(|(_, code)| code.to_string())
+}
+
+/// Language most words are in; the earliest one wins ties
+pub fn dominant_language<'a>(labels: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for (position, label) in labels.into_iter().enumerate() {
+        if let Some(code) = normalize_language(label) {
+            counts.entry(code).or_insert((0, position)).0 += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .max_by(|(_, (count_a, first_a)), (_, (count_b, first_b))| {
+            count_a.cmp(count_b).then(first_b.cmp(first_a))
+        })
+        .map(|(code, _)| code)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_language_config(app: AppHandle) -> Result<LanguageConfig, String> {
+    Ok(LanguageConfig::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_language_config(app: AppHandle, config: LanguageConfig) -> Result<(), String> {
+    config.validate()?;
+    config.save(&app)?;
+    tracing::info!("Transcription language updated: {:?}", config);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(LanguageConfig::default().validate().is_ok());
+        assert!(LanguageConfig::fixed("en").validate().is_ok());
+        assert!(LanguageConfig::fixed("EN").validate().is_err());
+        assert!(LanguageConfig::fixed("german").validate().is_err());
+        assert!(LanguageConfig::fixed("").validate().is_err());
+    }
+
+    #[test]
+    fn test_deepgram_model() {
+        let detecting = LanguageConfig { detect: true, ..LanguageConfig::default() };
+        assert_eq!(detecting.deepgram_model("nova-2"), ("nova-3", "multi"));
+        assert_eq!(LanguageConfig::default().deepgram_model("nova-2"), ("nova-2", "de"));
+        assert_eq!(LanguageConfig::fixed("en").deepgram_model("nova-2"), ("nova-2", "en"));
+        assert_eq!(detecting.fixed_language(), None);
+        assert_eq!(LanguageConfig::default().fixed_language(), Some("de"));
+        assert_eq!(LanguageConfig::fixed("en").fixed_language(), Some("en"));
+    }
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("de").as_deref(), Some("de"));
+        assert_eq!(normalize_language("en-US").as_deref(), Some("en"));
+        assert_eq!(normalize_language("pt_BR").as_deref(), Some("pt"));
+        assert_eq!(normalize_language("English").as_deref(), Some("en"));
+        assert_eq!(normalize_language(" german ").as_deref(), Some("de"));
+        assert_eq!(normalize_language("klingon"), None);
+        assert_eq!(normalize_language(""), None);
+    }
+
+    #[test]
+    fn test_dominant_language() {
+        assert_eq!(dominant_language(["de", "en", "de"]).as_deref(), Some("de"));
+        // Ties go to the language heard first
+        assert_eq!(dominant_language(["en", "de", "de", "en"]).as_deref(), Some("en"));
+        assert_eq!(dominant_language(["en-US", "en", "de"]).as_deref(), Some("en"));
+        assert_eq!(dominant_language(std::iter::empty()), None);
+    }
+}
@@ -0,0 +1,722 @@
+pub mod assemblyai;
+pub mod deepgram;
+pub mod export;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub mod hybrid;
+pub mod language;
+pub mod openai_compatible;
+pub mod postprocess;
+pub mod session;
+pub mod usage;
+pub mod vocabulary;
+
+#[cfg(test)]
+mod test_support;
+
+use async_tungstenite::{tokio::connect_async, tungstenite::Message};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{mpsc, watch, Mutex};
+
+use crate::platform::TARGET_SAMPLE_RATE;
+use deepgram::{
+    actionable_error, split_close_reason, DeepgramControl, DeepgramMessage, DeepgramMetadataEvent,
+    StreamSetup,
+};
+use export::TimedSegment;
+use language::{dominant_language, LanguageConfig};
+use session::{begin_session, AudioIngress, LiveEngine, LiveSession, SessionPhase};
+use usage::UsageProvider;
+use vocabulary::{Replacer, Vocabulary};
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+use crate::whisper::{
+    convert_i16_to_f32, join_segments, load_context, run_inference, segment_spans,
+    settings::WhisperSettings, timed_segments, transcript_confidence, WhisperManager,
+};
+
+const DEEPGRAM_WS_URL: &str = "wss://api.deepgram.com/v1/listen";
+const DEEPGRAM_MODEL: &str = "nova-2";
+/// Streamed audio is added to the usage ledger in steps of this many samples
+const USAGE_FLUSH_SAMPLES: usize = TARGET_SAMPLE_RATE as usize * 60;
+/// How long stopping a stream waits for Deepgram's final results
+const FINALIZE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEvent {
+    pub text: String,
+    pub is_final: bool,
+    pub confidence: f32,
+    pub source: String,
+    /// Stretches of the transcript worth double-checking
+    #[serde(default)]
+    pub low_confidence_spans: Vec<LowConfidenceSpan>,
+    /// Position in the recording (ms), when the provider reports it
+    #[serde(default)]
+    pub start_ms: Option<i64>,
+    #[serde(default)]
+    pub end_ms: Option<i64>,
+    /// Spoken language (ISO 639-1), detected or configured
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Timed pieces of a batch transcript, so subtitles get real cue
+    /// boundaries; empty for live results, which are a single piece
+    #[serde(default)]
+    pub segments: Vec<TimedSegment>,
+}
+
+/// Duration of `samples` 16kHz samples in milliseconds
+pub(crate) fn samples_to_ms(samples: usize) -> i64 {
+    (samples as u64 * 1000 / TARGET_SAMPLE_RATE as u64) as i64
+}
+
+/// Words below this confidence are surfaced as low-confidence spans
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.5;
+
+/// A recognized word with timing (ms from the start of the audio) and confidence
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredWord {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub confidence: f32,
+}
+
+/// A run of consecutive words the recognizer was unsure about
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LowConfidenceSpan {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Lowest word confidence within the span
+    pub confidence: f32,
+}
+
+/// Silence between words that starts a new segment
+const SEGMENT_PAUSE_MS: i64 = 800;
+/// Segments from word timings end at a sentence or after this long
+const MAX_SEGMENT_MS: i64 = 10_000;
+
+/// Group timed words into segments, breaking at sentence ends, pauses and
+/// `MAX_SEGMENT_MS`; the segment texts get the vocabulary replacements
+pub(crate) fn segments_from_words(words: &[ScoredWord], language: Option<&str>, replacer: &Replacer) -> Vec<TimedSegment> {
+    let mut groups: Vec<&[ScoredWord]> = Vec::new();
+    let mut start = 0;
+    for i in 1..=words.len() {
+        let ends = i == words.len()
+            || words[i - 1].text.ends_with(['.', '!', '?'])
+            || words[i].start_ms - words[i - 1].end_ms >= SEGMENT_PAUSE_MS
+            || words[i].end_ms - words[start].start_ms > MAX_SEGMENT_MS;
+        if ends && i > start {
+            groups.push(&words[start..i]);
+            start = i;
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let text: Vec<&str> = group.iter().map(|w| w.text.as_str()).collect();
+            TimedSegment {
+                start_ms: group[0].start_ms,
+                end_ms: group[group.len() - 1].end_ms,
+                text: replacer.apply(&text.join(" ")),
+                speaker: None,
+                confidence: Some(group.iter().map(|w| w.confidence).sum::<f32>() / group.len() as f32),
+                language: language.map(String::from),
+            }
+        })
+        .collect()
+}
+
+/// Merge consecutive words below `LOW_CONFIDENCE_THRESHOLD` into spans
+pub fn low_confidence_spans(words: &[ScoredWord]) -> Vec<LowConfidenceSpan> {
+    let mut spans: Vec<LowConfidenceSpan> = Vec::new();
+    let mut extending = false;
+
+    for word in words {
+        if word.confidence >= LOW_CONFIDENCE_THRESHOLD {
+            extending = false;
+            continue;
+        }
+
+        match spans.last_mut() {
+            Some(span) if extending => {
+                span.text.push(' ');
+                span.text.push_str(&word.text);
+                span.end_ms = word.end_ms;
+                span.confidence = span.confidence.min(word.confidence);
+            }
+            _ => spans.push(LowConfidenceSpan {
+                text: word.text.clone(),
+                start_ms: word.start_ms,
+                end_ms: word.end_ms,
+                confidence: word.confidence,
+            }),
+        }
+        extending = true;
+    }
+
+    spans
+}
+
+#[derive(Default)]
+pub struct TranscriptionState {
+    /// The live stream (Deepgram or local Whisper); audio reaches it through `AudioIngress`
+    pub session: LiveSession,
+    /// Cancel switches of running batch jobs, keyed by job ID
+    pub transcription_jobs: HashMap<String, watch::Sender<bool>>,
+}
+
+impl TranscriptionState {
+    /// Register batch job `job_id`; returns the receiver of its cancel switch
+    pub fn start_job(&mut self, job_id: &str) -> Result<watch::Receiver<bool>, String> {
+        if self.transcription_jobs.contains_key(job_id) {
+            return Err(format!("Transcription job {} is already running", job_id));
+        }
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        self.transcription_jobs.insert(job_id.to_string(), cancel_tx);
+        Ok(cancel_rx)
+    }
+}
+
+pub type TranscriptionManager = Arc<Mutex<TranscriptionState>>;
+
+/// Deepgram live URL for our audio format, with the custom vocabulary as keywords
+fn deepgram_url(vocabulary: &Vocabulary, language: &LanguageConfig) -> Result<String, String> {
+    let (model, language) = language.deepgram_model(DEEPGRAM_MODEL);
+    let mut url = reqwest::Url::parse_with_params(
+        DEEPGRAM_WS_URL,
+        [
+            ("model", model),
+            ("language", language),
+            ("encoding", "linear16"),
+            ("sample_rate", "16000"),
+            ("channels", "1"),
+            ("interim_results", "true"),
+            ("punctuate", "true"),
+            ("smart_format", "true"),
+            ("endpointing", "300"),
+            ("vad_events", "true"),
+            ("utterance_end_ms", "1000"),
+        ],
+    )
+    .map_err(|e| format!("Invalid Deepgram URL: {}", e))?;
+
+    url.query_pairs_mut()
+        .extend_pairs(vocabulary.deepgram_params(model));
+    Ok(url.to_string())
+}
+
+/// Encode 16kHz mono PCM as an in-memory WAV file for upload
+pub(crate) fn encode_wav(samples: &[i16]) -> Result<Vec<u8>, String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: TARGET_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut cursor = std::io::Cursor::new(Vec::with_capacity(44 + samples.len() * 2));
+    let mut writer = hound::WavWriter::new(&mut cursor, spec)
+        .map_err(|e| format!("Failed to create WAV: {}", e))?;
+    for sample in samples {
+        writer
+            .write_sample(*sample)
+            .map_err(|e| format!("Failed to write sample: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV: {}", e))?;
+
+    Ok(cursor.into_inner())
+}
+
+#[tauri::command]
+pub async fn start_deepgram_stream(
+    app: AppHandle,
+    api_key: String,
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<(), String> {
+    open_deepgram_stream(&app, &api_key, state.inner()).await
+}
+
+/// Connect to Deepgram and route captured audio to it.
+///
+/// If the connection drops while the session is still streaming, it
+/// switches to local Whisper when `local_fallback` is armed.
+pub(crate) async fn open_deepgram_stream(
+    app: &AppHandle,
+    api_key: &str,
+    state: &TranscriptionManager,
+) -> Result<(), String> {
+    usage::ensure_within_budget(app)?;
+
+    // Claims the live slot, so concurrent starts can't open duplicate connections
+    let session_id = begin_session(app, state, LiveEngine::Deepgram).await?;
+    let result = connect_deepgram(app, api_key, state, &session_id).await;
+    if let Err(e) = &result {
+        tracing::warn!("{}", e);
+        let mut state_guard = state.lock().await;
+        // Stopped while connecting: the slot is free again
+        state_guard.session.finish(&session_id);
+        state_guard.session.fail(&session_id, e);
+    }
+    result
+}
+
+async fn connect_deepgram(
+    app: &AppHandle,
+    api_key: &str,
+    state: &TranscriptionManager,
+    session_id: &str,
+) -> Result<(), String> {
+    let vocabulary = Vocabulary::load(app);
+    let language = LanguageConfig::load(app);
+    let url = deepgram_url(&vocabulary, &language)?;
+
+    let request = async_tungstenite::tungstenite::http::Request::builder()
+        .uri(&url)
+        .header("Authorization", format!("Token {}", api_key))
+        .header("Sec-WebSocket-Key", async_tungstenite::tungstenite::handshake::client::generate_key())
+        .header("Sec-WebSocket-Version", "13")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Host", "api.deepgram.com")
+        .body(())
+        .map_err(|e| format!("Failed to build request: {}", e))?;
+
+    let (model, language_param) = language.deepgram_model(DEEPGRAM_MODEL);
+    let setup = StreamSetup {
+        model,
+        language: language_param,
+    };
+
+    let (ws_stream, _) = match connect_async(request).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            return Err(match e {
+                async_tungstenite::tungstenite::Error::Http(response) => {
+                    let detail = response
+                        .body()
+                        .as_deref()
+                        .map(String::from_utf8_lossy)
+                        .unwrap_or_default();
+                    let code = response
+                        .headers()
+                        .get("dg-error")
+                        .and_then(|v| v.to_str().ok());
+                    actionable_error(Some(response.status().as_u16()), code, &detail, &setup)
+                }
+                e => format!("Failed to connect to Deepgram: {}", e),
+            });
+        }
+    };
+
+    let (mut write, mut read) = ws_stream.split();
+    let (tx, mut rx) = mpsc::channel::<Vec<i16>>(100);
+    // Dropping `write` closes the connection if the session was stopped meanwhile
+    state.lock().await.session.start_streaming(session_id, tx)?;
+
+    let app_clone = app.clone();
+    let state_clone = state.clone();
+    let replacer = vocabulary.replacer();
+    let fixed_language = language.fixed_language().map(String::from);
+    let (model, language_param) = (setup.model.to_string(), setup.language.to_string());
+    let reader_session = session_id.to_string();
+
+    // Spawn task to receive transcripts
+    let reader = tokio::spawn(async move {
+        let mut failure = None;
+
+        while let Some(msg) = read.next().await {
+            match msg {
+                Ok(Message::Text(text)) => match serde_json::from_str::<DeepgramMessage>(&text) {
+                    Ok(DeepgramMessage::Results(results)) => {
+                        let Some(alt) = results.channel.alternatives.first() else {
+                            continue;
+                        };
+                        if alt.transcript.is_empty() {
+                            continue;
+                        }
+                        let text = if results.is_final {
+                            replacer.apply(&alt.transcript)
+                        } else {
+                            alt.transcript.clone()
+                        };
+                        let _ = app_clone.emit(
+                            "transcript",
+                            TranscriptEvent {
+                                text,
+                                is_final: results.is_final,
+                                confidence: alt.confidence,
+                                source: "deepgram".to_string(),
+                                low_confidence_spans: low_confidence_spans(&alt.scored_words()),
+                                start_ms: results.start.map(|s| (s * 1000.0) as i64),
+                                end_ms: results
+                                    .start
+                                    .zip(results.duration)
+                                    .map(|(s, d)| ((s + d) * 1000.0) as i64),
+                                language: alt.language().or_else(|| fixed_language.clone()),
+                                segments: Vec::new(),
+                            },
+                        );
+                    }
+                    Ok(DeepgramMessage::Metadata(metadata)) => {
+                        let event = DeepgramMetadataEvent::from(metadata);
+                        tracing::info!("Deepgram request {} ({:?})", event.request_id, event.models);
+                        let _ = app_clone.emit("deepgram-metadata", event);
+                    }
+                    Ok(DeepgramMessage::SpeechStarted(started)) => {
+                        let _ = app_clone.emit("deepgram-speech-started", (started.timestamp * 1000.0) as i64);
+                    }
+                    Ok(DeepgramMessage::UtteranceEnd(end)) => {
+                        let _ = app_clone.emit("deepgram-utterance-end", (end.last_word_end * 1000.0) as i64);
+                    }
+                    Ok(DeepgramMessage::Error(frame)) => {
+                        let setup = StreamSetup {
+                            model: &model,
+                            language: &language_param,
+                        };
+                        failure = Some(actionable_error(None, frame.code(), frame.detail(), &setup));
+                        tracing::error!("Deepgram error frame: {:?}", frame);
+                        break;
+                    }
+                    Ok(DeepgramMessage::Unknown) => {
+                        tracing::debug!("Ignoring unknown Deepgram message: {}", text);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to parse Deepgram message: {}", e);
+                    }
+                },
+                Ok(Message::Close(frame)) => {
+                    tracing::debug!("Deepgram connection closed: {:?}", frame);
+                    failure = Some(match frame {
+                        Some(frame) if !frame.reason.is_empty() => {
+                            let (code, detail) = split_close_reason(&frame.reason);
+                            let setup = StreamSetup {
+                                model: &model,
+                                language: &language_param,
+                            };
+                            actionable_error(None, code, detail, &setup)
+                        }
+                        Some(frame) => format!("Deepgram closed the connection (code {})", u16::from(frame.code)),
+                        None => "Deepgram closed the connection".to_string(),
+                    });
+                    break;
+                }
+                Ok(_) => {} // Ignore ping/pong/binary
+                Err(e) => {
+                    tracing::error!("Deepgram WebSocket error: {}", e);
+                    failure = Some(format!("Deepgram WebSocket error: {}", e));
+                    break;
+                }
+            }
+        }
+
+        let mut state_guard = state_clone.lock().await;
+        let session = &mut state_guard.session;
+        if !session.is_current(&reader_session) {
+            return;
+        }
+        // Draining means we were asked to stop and the final results are in
+        if session.phase == SessionPhase::Draining {
+            session.finish(&reader_session);
+            return;
+        }
+
+        let reason = failure.unwrap_or_else(|| "Deepgram stream ended unexpectedly".to_string());
+        let fallback = session.local_fallback;
+        if !session.fail(&reader_session, &reason) {
+            return;
+        }
+        drop(state_guard);
+
+        tracing::warn!("{}", reason);
+        let _ = app_clone.emit("transcription-stream-failed", reason.clone());
+
+        if fallback {
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            hybrid::fall_back_to_local(&app_clone, &state_clone, &reason).await;
+        }
+    });
+    {
+        let mut state_guard = state.lock().await;
+        if state_guard.session.is_current(session_id) {
+            state_guard.session.deepgram_reader = Some(reader);
+        }
+    }
+
+    // Spawn task to send audio
+    let app_clone = app.clone();
+    let session_id = session_id.to_string();
+    tokio::spawn(async move {
+        // Sent but not yet recorded in the usage ledger
+        let mut unrecorded = 0;
+        while let Some(samples) = rx.recv().await {
+            let bytes: Vec<u8> = samples
+                .iter()
+                .flat_map(|s| s.to_le_bytes())
+                .collect();
+
+            if let Err(e) = write.send(Message::Binary(bytes)).await {
+                tracing::error!("Failed to send audio to Deepgram: {}", e);
+                break;
+            }
+
+            unrecorded += samples.len();
+            if unrecorded >= USAGE_FLUSH_SAMPLES {
+                let secs = unrecorded as f64 / TARGET_SAMPLE_RATE as f64;
+                usage::record_usage(&app_clone, UsageProvider::Deepgram, &session_id, secs);
+                unrecorded = 0;
+            }
+        }
+        // All senders are gone: ask Deepgram to flush what it still holds.
+        // It closes the connection itself once the final results are out.
+        if let Err(e) = write.send(Message::Text(DeepgramControl::CloseStream.to_json())).await {
+            tracing::debug!("Failed to send CloseStream: {}", e);
+        }
+        let secs = unrecorded as f64 / TARGET_SAMPLE_RATE as f64;
+        usage::record_usage(&app_clone, UsageProvider::Deepgram, &session_id, secs);
+    });
+
+    app.emit("deepgram-connected", ()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Audio captured by the frontend (mobile); queued like the native capture
+#[tauri::command]
+pub async fn send_audio_to_deepgram(
+    samples: Vec<i16>,
+    state: tauri::State<'_, TranscriptionManager>,
+    ingress: tauri::State<'_, AudioIngress>,
+) -> Result<(), String> {
+    if !state.lock().await.session.is_streaming_on(LiveEngine::Deepgram) {
+        return Err("Deepgram stream not active".to_string());
+    }
+    ingress.push(samples);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_deepgram_stream(
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<(), String> {
+    // A local Whisper stream is stopped via stop_local_whisper_stream
+    if state.lock().await.session.engine != Some(LiveEngine::Deepgram) {
+        return Ok(());
+    }
+    stop_live_session(state.inner()).await;
+    Ok(())
+}
+
+/// Stop routing audio to the live session, whichever engine it runs on.
+///
+/// For Deepgram this waits (up to `FINALIZE_TIMEOUT`) for the final results
+/// of what it already received; local Whisper finalizes in the background.
+pub(crate) async fn stop_live_session(state: &TranscriptionManager) {
+    let (session_id, reader) = {
+        let mut state_guard = state.lock().await;
+        let session = &mut state_guard.session;
+        // Dropping the sink lets the writer drain queued audio and send CloseStream
+        if !session.drain() {
+            return; // Already stopped
+        }
+        (session.id.clone().unwrap_or_default(), session.deepgram_reader.take())
+    };
+
+    if let Some(mut reader) = reader {
+        if tokio::time::timeout(FINALIZE_TIMEOUT, &mut reader).await.is_err() {
+            tracing::warn!(
+                "Deepgram did not finish within {:?}; dropping outstanding results",
+                FINALIZE_TIMEOUT
+            );
+            reader.abort();
+        }
+        state.lock().await.session.finish(&session_id);
+    }
+}
+
+#[tauri::command]
+pub async fn is_deepgram_streaming(
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<bool, String> {
+    let state_guard = state.lock().await;
+    Ok(state_guard.session.is_streaming_on(LiveEngine::Deepgram))
+}
+
+// ============================================================================
+// Local Whisper Transcription (Desktop Only)
+// ============================================================================
+
+/// Transcribe audio using local Whisper model (Desktop only)
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+#[tauri::command]
+pub async fn transcribe_local_whisper(
+    app: AppHandle,
+    audio_data: Vec<i16>,
+    whisper: tauri::State<'_, WhisperManager>,
+) -> Result<String, String> {
+    let vocabulary = Vocabulary::load(&app);
+    let settings = WhisperSettings::load(&app)
+        .with_vocabulary(&vocabulary)
+        .with_language(&LanguageConfig::load(&app));
+    let event = whisper_transcribe(&app, whisper.inner(), audio_data, settings, &vocabulary).await?;
+
+    // Emit transcript event
+    let _ = app.emit("transcript", event.clone());
+
+    tracing::info!("Local Whisper transcription complete: {} chars", event.text.len());
+    Ok(event.text)
+}
+
+/// Run one local Whisper pass over a whole recording and build the final transcript
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub(crate) async fn whisper_transcribe(
+    app: &AppHandle,
+    whisper: &WhisperManager,
+    audio_data: Vec<i16>,
+    settings: WhisperSettings,
+    vocabulary: &Vocabulary,
+) -> Result<TranscriptEvent, String> {
+    // Loads (and downloads, if needed) the model on first use only
+    let (ctx, metrics) = load_context(app, whisper).await?;
+
+    // Convert audio to f32
+    let audio_data_len = audio_data.len();
+    let audio_f32 = convert_i16_to_f32(&audio_data);
+
+    // Run inference in a blocking task to not block the async runtime
+    let output = tokio::task::spawn_blocking(move || {
+        let mut state = ctx
+            .create_state()
+            .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+
+        run_inference(&ctx, &mut state, &audio_f32, &settings)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    if let Some(pass_metrics) = &output.metrics {
+        if let Ok(mut totals) = metrics.lock() {
+            totals.record(pass_metrics);
+        }
+        let _ = app.emit("whisper-metrics", pass_metrics.clone());
+    }
+
+    let replacer = vocabulary.replacer();
+    Ok(TranscriptEvent {
+        text: replacer.apply(&join_segments(&output.segments)),
+        is_final: true,
+        confidence: transcript_confidence(&output.segments),
+        source: "whisper-local".to_string(),
+        low_confidence_spans: segment_spans(&output.segments),
+        start_ms: Some(0),
+        end_ms: Some(samples_to_ms(audio_data_len)),
+        segments: timed_segments(&output.segments, output.language.as_deref(), &replacer),
+        language: output.language,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, confidence: f32, start_ms: i64) -> ScoredWord {
+        ScoredWord {
+            text: text.to_string(),
+            start_ms,
+            end_ms: start_ms + 300,
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_segments_from_words_break_at_sentences_and_pauses() {
+        let words = [
+            word("Ship", 0.9, 0),
+            word("it.", 0.7, 300),
+            word("Then", 0.9, 700),
+            word("review", 0.9, 1000),
+            // Long pause
+            word("later", 0.9, 3000),
+        ];
+        let segments = segments_from_words(&words, Some("en"), &Replacer::default());
+
+        let cues: Vec<(&str, i64, i64)> = segments.iter().map(|s| (s.text.as_str(), s.start_ms, s.end_ms)).collect();
+        assert_eq!(cues, [("Ship it.", 0, 600), ("Then review", 700, 1300), ("later", 3000, 3300)]);
+        assert!((segments[0].confidence.unwrap() - 0.8).abs() < 1e-6);
+        assert_eq!(segments[0].language.as_deref(), Some("en"));
+
+        let monologue: Vec<ScoredWord> = (0..60).map(|i| word("word", 0.9, i * 400)).collect();
+        let segments = segments_from_words(&monologue, None, &Replacer::default());
+        assert!(segments.len() > 1);
+        assert!(segments.iter().all(|s| s.end_ms - s.start_ms <= MAX_SEGMENT_MS));
+        assert!(segments_from_words(&[], None, &Replacer::default()).is_empty());
+    }
+
+    #[test]
+    fn test_low_confidence_spans_merge_consecutive_words() {
+        let words = [
+            word("deploy", 0.95, 0),
+            word("the", 0.4, 300),
+            word("kubelet", 0.2, 600),
+            word("today", 0.9, 900),
+            word("please", 0.3, 1200),
+        ];
+        let spans = low_confidence_spans(&words);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "the kubelet");
+        assert_eq!((spans[0].start_ms, spans[0].end_ms), (300, 900));
+        assert!((spans[0].confidence - 0.2).abs() < f32::EPSILON);
+        assert_eq!(spans[1].text, "please");
+    }
+
+    #[test]
+    fn test_deepgram_url_includes_vocabulary() {
+        let german = LanguageConfig::fixed("de");
+        let plain = deepgram_url(&Vocabulary::default(), &german).unwrap();
+        assert!(plain.starts_with("wss://api.deepgram.com/v1/listen?model=nova-2&language=de"));
+        assert!(!plain.contains("keywords"));
+
+        let vocabulary = Vocabulary {
+            terms: vec![vocabulary::VocabularyTerm {
+                term: "Aurus Hub".to_string(),
+                boost: Some(2.0),
+                replacements: Vec::new(),
+            }],
+        };
+        let url = deepgram_url(&vocabulary, &german).unwrap();
+        assert!(url.ends_with("&keywords=Aurus+Hub%3A2"), "{}", url);
+
+        // Language detection switches to Nova-3, which takes keyterms
+        let detecting = LanguageConfig { detect: true, ..LanguageConfig::default() };
+        let url = deepgram_url(&vocabulary, &detecting).unwrap();
+        assert!(url.starts_with("wss://api.deepgram.com/v1/listen?model=nova-3&language=multi"));
+        assert!(url.ends_with("&keyterm=Aurus+Hub"), "{}", url);
+    }
+
+    #[test]
+    fn test_duplicate_job_id_is_rejected() {
+        let mut state = TranscriptionState::default();
+        let cancel = state.start_job("job-1").unwrap();
+        assert!(state.start_job("job-1").unwrap_err().contains("already running"));
+
+        // The first job can still be cancelled
+        let _ = state.transcription_jobs.remove("job-1").unwrap().send(true);
+        assert!(*cancel.borrow());
+        assert!(state.start_job("job-1").is_ok());
+    }
+
+    #[test]
+    fn test_encode_wav() {
+        let wav = encode_wav(&[0, 1, -1, i16::MAX]).unwrap();
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(wav.len(), 44 + 8);
+    }
+}
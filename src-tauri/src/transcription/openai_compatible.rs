@@ -0,0 +1,608 @@
+//! Speech-to-text against any server implementing OpenAI's
+//! `POST /v1/audio/transcriptions` (OpenAI Whisper, faster-whisper-server,
+//! whisper.cpp server, LocalAI, ...).
+//!
+//! Long recordings are split into chunks below the upload limit. Each cut is
+//! placed at the quietest moment shortly before the chunk boundary so words
+//! aren't split in half.
+
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::watch;
+
+use super::assemblyai::{cancelled, JobError, JobProgress, JobStatus};
+use super::export::TimedSegment;
+use super::language::{dominant_language, normalize_language};
+use super::usage::{record_usage, UsageProvider};
+use super::vocabulary::{Replacer, Vocabulary};
+use super::{encode_wav, samples_to_ms, TranscriptEvent, TranscriptionManager};
+use crate::platform::TARGET_SAMPLE_RATE;
+use crate::storage;
+
+const CONFIG_FILE: &str = "openai-stt.json";
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "whisper-1";
+
+/// OpenAI rejects uploads over 25 MB; 10 minutes of 16kHz 16-bit mono WAV is ~19 MB
+const DEFAULT_CHUNK_SECS: u32 = 600;
+const MAX_CHUNK_SECS: u32 = 780;
+
+/// How far before a chunk boundary to look for a pause
+const BOUNDARY_SEARCH_MS: usize = 5000;
+/// Granularity of the pause search
+const FRAME_MS: usize = 100;
+
+/// Segments are dropped as silence above this no-speech probability
+/// (when the decode is also unsure), mirroring whisper.cpp's defaults
+const NO_SPEECH_THRESHOLD: f64 = 0.6;
+const LOGPROB_THRESHOLD: f64 = -1.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OpenAiSttConfig {
+    /// API root including the version, e.g. `http://localhost:8000/v1`
+    pub base_url: String,
+    pub model: String,
+    /// ISO-639-1 code; `None` lets the server detect the language
+    pub language: Option<String>,
+    /// Maximum audio length per upload
+    pub chunk_secs: u32,
+    /// Timeout for a single chunk request
+    pub request_timeout_secs: u64,
+}
+
+impl Default for OpenAiSttConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            language: None,
+            chunk_secs: DEFAULT_CHUNK_SECS,
+            request_timeout_secs: 300,
+        }
+    }
+}
+
+impl OpenAiSttConfig {
+    pub fn load(app: &AppHandle) -> Self {
+        storage::load_json(app, CONFIG_FILE)
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, CONFIG_FILE, self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let url = reqwest::Url::parse(&self.base_url)
+            .map_err(|e| format!("Invalid base URL '{}': {}", self.base_url, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("Base URL must start with http:// or https://".to_string());
+        }
+        if self.model.trim().is_empty() {
+            return Err("Model must not be empty".to_string());
+        }
+        if !(30..=MAX_CHUNK_SECS).contains(&self.chunk_secs) {
+            return Err(format!("Chunk length must be between 30 and {} seconds", MAX_CHUNK_SECS));
+        }
+        if self.request_timeout_secs == 0 {
+            return Err("Request timeout must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}/audio/transcriptions", self.base_url.trim_end_matches('/'))
+    }
+
+    /// `verbose_json` carries per-segment scores, but the GPT-4o transcribe
+    /// models only support plain `json`
+    fn response_format(&self) -> &'static str {
+        if self.model.starts_with("gpt-4o") {
+            "json"
+        } else {
+            "verbose_json"
+        }
+    }
+}
+
+/// Split audio into chunks of at most `max_samples`, cutting each at the
+/// quietest frame within `search_samples` before the limit (at most half a chunk)
+pub fn plan_chunks(samples: &[i16], max_samples: usize, search_samples: usize) -> Vec<Range<usize>> {
+    let frame = (TARGET_SAMPLE_RATE as usize * FRAME_MS / 1000).max(1);
+    let search_samples = search_samples.min(max_samples / 2);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while samples.len() - start > max_samples {
+        let limit = start + max_samples;
+        let search_from = limit.saturating_sub(search_samples).max(start + 1);
+
+        let mut cut = limit;
+        let mut quietest = u64::MAX;
+        let mut frame_start = search_from;
+        while frame_start + frame <= limit {
+            let energy: u64 = samples[frame_start..frame_start + frame]
+                .iter()
+                .map(|s| (*s as i64).unsigned_abs())
+                .sum();
+            // Ties go to the later frame to keep chunks long
+            if energy <= quietest {
+                quietest = energy;
+                cut = frame_start + frame / 2;
+            }
+            frame_start += frame;
+        }
+
+        chunks.push(start..cut);
+        start = cut;
+    }
+
+    if start < samples.len() {
+        chunks.push(start..samples.len());
+    }
+    chunks
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+    /// Detected language; OpenAI reports names ("english"), others codes
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<ResponseSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseSegment {
+    text: String,
+    /// Seconds from the start of the uploaded chunk
+    #[serde(default)]
+    start: Option<f64>,
+    #[serde(default)]
+    end: Option<f64>,
+    #[serde(default)]
+    avg_logprob: Option<f64>,
+    #[serde(default)]
+    no_speech_prob: Option<f64>,
+}
+
+impl ResponseSegment {
+    fn is_silence(&self) -> bool {
+        matches!(
+            (self.no_speech_prob, self.avg_logprob),
+            (Some(no_speech), Some(logprob)) if no_speech > NO_SPEECH_THRESHOLD && logprob < LOGPROB_THRESHOLD
+        )
+    }
+
+    fn confidence(&self) -> Option<f32> {
+        self.avg_logprob.map(|logprob| logprob.exp().clamp(0.0, 1.0) as f32)
+    }
+}
+
+/// Transcript text plus confidence, language and segment timings, when the
+/// server reported them
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkTranscript {
+    pub text: String,
+    pub confidence: Option<f32>,
+    /// ISO 639-1 code
+    pub language: Option<String>,
+    /// Timed segments, in ms from the start of the recording
+    pub segments: Vec<TimedSegment>,
+}
+
+impl ChunkTranscript {
+    /// Segments with the vocabulary replacements applied
+    pub fn timed_segments(&self, replacer: &Replacer) -> Vec<TimedSegment> {
+        self.segments
+            .iter()
+            .map(|segment| TimedSegment {
+                text: replacer.apply(&segment.text),
+                ..segment.clone()
+            })
+            .collect()
+    }
+}
+
+impl From<TranscriptionResponse> for ChunkTranscript {
+    fn from(response: TranscriptionResponse) -> Self {
+        let language = response.language.as_deref().and_then(normalize_language);
+        if response.segments.is_empty() {
+            return Self {
+                text: response.text.trim().to_string(),
+                confidence: None,
+                language,
+                segments: Vec::new(),
+            };
+        }
+
+        let kept: Vec<&ResponseSegment> = response.segments.iter().filter(|s| !s.is_silence()).collect();
+        let text = kept
+            .iter()
+            .map(|s| s.text.trim())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let scores: Vec<f32> = kept.iter().filter_map(|s| s.confidence()).collect();
+        let confidence = (!scores.is_empty()).then(|| scores.iter().sum::<f32>() / scores.len() as f32);
+
+        let segments = kept
+            .iter()
+            .filter(|s| !s.text.trim().is_empty())
+            .filter_map(|s| {
+                Some(TimedSegment {
+                    start_ms: (s.start? * 1000.0).round() as i64,
+                    end_ms: (s.end? * 1000.0).round() as i64,
+                    text: s.text.trim().to_string(),
+                    speaker: None,
+                    confidence: s.confidence(),
+                    language: language.clone(),
+                })
+            })
+            .collect();
+
+        Self {
+            text,
+            confidence,
+            language,
+            segments,
+        }
+    }
+}
+
+pub struct OpenAiSttClient {
+    http: reqwest::Client,
+    config: OpenAiSttConfig,
+    api_key: Option<String>,
+}
+
+impl OpenAiSttClient {
+    /// `api_key` may be `None` for self-hosted servers without auth
+    pub fn new(config: OpenAiSttConfig, api_key: Option<String>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.request_timeout_secs))
+            .build()
+            .unwrap_or_default();
+        Self {
+            http,
+            config,
+            api_key: api_key.filter(|k| !k.is_empty()),
+        }
+    }
+
+    async fn transcribe_chunk(&self, wav: Vec<u8>, prompt: Option<&str>) -> Result<ChunkTranscript, String> {
+        let file = reqwest::multipart::Part::bytes(wav)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| format!("Failed to build upload: {}", e))?;
+
+        let mut form = reqwest::multipart::Form::new()
+            .part("file", file)
+            .text("model", self.config.model.clone())
+            .text("response_format", self.config.response_format());
+        if let Some(language) = &self.config.language {
+            form = form.text("language", language.clone());
+        }
+        if let Some(prompt) = prompt {
+            form = form.text("prompt", prompt.to_string());
+        }
+
+        let mut request = self.http.post(self.config.endpoint()).multipart(form);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Transcription request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v["error"]["message"].as_str().map(String::from))
+                .unwrap_or(body);
+            return Err(format!("Transcription failed: HTTP {}: {}", status, message));
+        }
+
+        let parsed: TranscriptionResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse transcription response: {}", e))?;
+        Ok(parsed.into())
+    }
+
+    /// Transcribe a recording chunk by chunk.
+    ///
+    /// `on_chunk(done, total)` is called after each chunk completes.
+    pub async fn transcribe(
+        &self,
+        samples: &[i16],
+        prompt: Option<&str>,
+        cancel: watch::Receiver<bool>,
+        mut on_chunk: impl FnMut(usize, usize),
+    ) -> Result<ChunkTranscript, JobError> {
+        let max_samples = TARGET_SAMPLE_RATE as usize * self.config.chunk_secs as usize;
+        let search_samples = TARGET_SAMPLE_RATE as usize * BOUNDARY_SEARCH_MS / 1000;
+        let chunks = plan_chunks(samples, max_samples, search_samples);
+
+        let work = async {
+            let mut parts = Vec::with_capacity(chunks.len());
+            for (i, range) in chunks.iter().enumerate() {
+                let wav = encode_wav(&samples[range.clone()]).map_err(JobError::Failed)?;
+                parts.push(self.transcribe_chunk(wav, prompt).await.map_err(JobError::Failed)?);
+                on_chunk(i + 1, chunks.len());
+            }
+            Ok(parts)
+        };
+
+        let parts = tokio::select! {
+            result = work => result?,
+            _ = cancelled(cancel) => return Err(JobError::Cancelled),
+        };
+
+        let text = parts
+            .iter()
+            .map(|p| p.text.as_str())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let scores: Vec<f32> = parts.iter().filter_map(|p| p.confidence).collect();
+        let confidence = (!scores.is_empty()).then(|| scores.iter().sum::<f32>() / scores.len() as f32);
+        let language = dominant_language(parts.iter().filter_map(|p| p.language.as_deref()))
+            .or_else(|| self.config.language.clone());
+        // Segment timings are relative to their chunk
+        let segments = parts
+            .iter()
+            .zip(&chunks)
+            .flat_map(|(part, range)| {
+                let offset_ms = samples_to_ms(range.start);
+                part.segments.iter().map(move |segment| TimedSegment {
+                    start_ms: segment.start_ms + offset_ms,
+                    end_ms: segment.end_ms + offset_ms,
+                    ..segment.clone()
+                })
+            })
+            .collect();
+
+        Ok(ChunkTranscript {
+            text,
+            confidence,
+            language,
+            segments,
+        })
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_openai_stt_config(app: AppHandle) -> Result<OpenAiSttConfig, String> {
+    Ok(OpenAiSttConfig::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_openai_stt_config(app: AppHandle, config: OpenAiSttConfig) -> Result<(), String> {
+    config.validate()?;
+    config.save(&app)?;
+    tracing::info!("OpenAI-compatible STT configured: {} ({})", config.base_url, config.model);
+    Ok(())
+}
+
+/// Transcribe a recording with the configured OpenAI-compatible endpoint.
+///
+/// Progress is reported per chunk through `transcription-job-progress`;
+/// the job can be stopped with `cancel_transcription_job`.
+#[tauri::command]
+pub async fn transcribe_with_openai_compatible(
+    app: AppHandle,
+    audio_data: Vec<i16>,
+    api_key: Option<String>,
+    job_id: Option<String>,
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<String, String> {
+    let config = OpenAiSttConfig::load(&app);
+    config.validate()?;
+
+    let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let vocabulary = Vocabulary::load(&app);
+    let prompt = vocabulary.whisper_prompt(None);
+
+    let cancel_rx = state.lock().await.start_job(&job_id)?;
+
+    let started = Instant::now();
+    let emit_progress = |status: JobStatus, chunks: Option<(usize, usize)>, error: Option<String>| {
+        let _ = app.emit(
+            "transcription-job-progress",
+            JobProgress {
+                job_id: job_id.clone(),
+                provider: "openai_compatible".to_string(),
+                status,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                error,
+                completed_chunks: chunks.map(|(done, _)| done),
+                total_chunks: chunks.map(|(_, total)| total),
+            },
+        );
+    };
+
+    emit_progress(JobStatus::Uploading, None, None);
+    let result = OpenAiSttClient::new(config, api_key)
+        .transcribe(&audio_data, prompt.as_deref(), cancel_rx, |done, total| {
+            emit_progress(JobStatus::Processing, Some((done, total)), None)
+        })
+        .await;
+
+    state.lock().await.transcription_jobs.remove(&job_id);
+
+    let transcript = match result {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            tracing::warn!("OpenAI-compatible job {} ended: {}", job_id, e);
+            emit_progress(e.status(), None, Some(e.to_string()));
+            return Err(e.into());
+        }
+    };
+    emit_progress(JobStatus::Completed, None, None);
+    let audio_secs = samples_to_ms(audio_data.len()) as f64 / 1000.0;
+    record_usage(&app, UsageProvider::OpenAiCompatible, &job_id, audio_secs);
+
+    let replacer = vocabulary.replacer();
+    let text = replacer.apply(&transcript.text);
+    let _ = app.emit(
+        "transcript",
+        TranscriptEvent {
+            text: text.clone(),
+            is_final: true,
+            confidence: transcript.confidence.unwrap_or(0.0),
+            source: "openai-compatible".to_string(),
+            low_confidence_spans: Vec::new(),
+            start_ms: Some(0),
+            end_ms: Some(samples_to_ms(audio_data.len())),
+            segments: transcript.timed_segments(&replacer),
+            language: transcript.language,
+        },
+    );
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::test_support::stand_in;
+
+    #[test]
+    fn test_plan_chunks_short_audio_is_single_chunk() {
+        let samples = vec![100i16; 1000];
+        assert_eq!(plan_chunks(&samples, 2000, 500), vec![0..1000]);
+        assert!(plan_chunks(&[], 2000, 500).is_empty());
+    }
+
+    #[test]
+    fn test_plan_chunks_cuts_at_pause() {
+        // 3 "seconds" of loud audio with a pause around 2.5s
+        let rate = TARGET_SAMPLE_RATE as usize;
+        let mut samples = vec![8000i16; rate * 3];
+        let pause = rate * 5 / 2;
+        samples[pause..pause + rate / 5].fill(0);
+
+        let chunks = plan_chunks(&samples, rate * 2 + rate * 3 / 4, rate);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].end >= pause && chunks[0].end <= pause + rate / 5, "{:?}", chunks);
+        assert_eq!(chunks[1].start, chunks[0].end);
+        assert_eq!(chunks[1].end, samples.len());
+    }
+
+    #[test]
+    fn test_plan_chunks_respects_limit_without_pause() {
+        let samples = vec![8000i16; 10_000];
+        let chunks = plan_chunks(&samples, 3000, 800);
+        assert!(chunks.iter().all(|c| c.len() <= 3000));
+        assert_eq!(chunks.first().unwrap().start, 0);
+        assert_eq!(chunks.last().unwrap().end, 10_000);
+        assert!(chunks.windows(2).all(|w| w[0].end == w[1].start));
+    }
+
+    #[test]
+    fn test_response_drops_silent_segments() {
+        let response: TranscriptionResponse = serde_json::from_str(
+            r#"{"text": "Hello team. Thank you.", "language": "english", "segments": [
+                {"text": " Hello team.", "start": 0.0, "end": 1.52, "avg_logprob": -0.1, "no_speech_prob": 0.01},
+                {"text": " Thank you.", "start": 1.52, "end": 3.0, "avg_logprob": -1.4, "no_speech_prob": 0.9}
+            ]}"#,
+        )
+        .unwrap();
+        let transcript = ChunkTranscript::from(response);
+        assert_eq!(transcript.text, "Hello team.");
+        assert_eq!(transcript.language.as_deref(), Some("en"));
+        assert!((transcript.confidence.unwrap() - (-0.1f64).exp() as f32).abs() < 1e-6);
+        assert_eq!(transcript.segments.len(), 1);
+        assert_eq!((transcript.segments[0].start_ms, transcript.segments[0].end_ms), (0, 1520));
+        assert_eq!(transcript.segments[0].text, "Hello team.");
+        assert_eq!(transcript.segments[0].language.as_deref(), Some("en"));
+
+        let plain: TranscriptionResponse = serde_json::from_str(r#"{"text": " Hi "}"#).unwrap();
+        assert_eq!(
+            ChunkTranscript::from(plain),
+            ChunkTranscript { text: "Hi".to_string(), confidence: None, language: None, segments: Vec::new() }
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(OpenAiSttConfig::default().validate().is_ok());
+        let local = OpenAiSttConfig {
+            base_url: "http://localhost:8000/v1/".to_string(),
+            model: "Systran/faster-whisper-small".to_string(),
+            ..Default::default()
+        };
+        assert!(local.validate().is_ok());
+        assert_eq!(local.endpoint(), "http://localhost:8000/v1/audio/transcriptions");
+        assert_eq!(local.response_format(), "verbose_json");
+
+        let gpt = OpenAiSttConfig { model: "gpt-4o-transcribe".to_string(), ..Default::default() };
+        assert_eq!(gpt.response_format(), "json");
+
+        assert!(OpenAiSttConfig { base_url: "ftp://x".to_string(), ..Default::default() }.validate().is_err());
+        assert!(OpenAiSttConfig { chunk_secs: 5, ..Default::default() }.validate().is_err());
+        assert!(OpenAiSttConfig { model: " ".to_string(), ..Default::default() }.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_long_recording_is_uploaded_in_chunks() {
+        let server = stand_in(&[(
+            "POST /v1/audio/transcriptions",
+            &[
+                (200, r#"{"text": "First part.", "segments": [{"text": "First part.", "start": 0.0, "end": 0.8, "avg_logprob": -0.2, "no_speech_prob": 0.0}]}"#),
+                (200, r#"{"text": "Second part.", "segments": [{"text": "Second part.", "start": 0.1, "end": 0.4, "avg_logprob": -0.2, "no_speech_prob": 0.0}]}"#),
+            ],
+        )])
+        .await;
+
+        let config = OpenAiSttConfig {
+            base_url: format!("{}/v1", server.url),
+            model: "whisper-large-v3".to_string(),
+            language: Some("de".to_string()),
+            chunk_secs: 1,
+            ..Default::default()
+        };
+        let client = OpenAiSttClient::new(config, None);
+        let samples = vec![1000i16; TARGET_SAMPLE_RATE as usize * 3 / 2];
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let mut progress = Vec::new();
+
+        let transcript = client
+            .transcribe(&samples, Some("Glossary: Aurus."), cancel_rx, |done, total| {
+                progress.push((done, total))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(transcript.text, "First part. Second part.");
+        // The server didn't report a language, so the requested one applies
+        assert_eq!(transcript.language.as_deref(), Some("de"));
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+        // Timings of the second chunk start where it was cut
+        let search_samples = TARGET_SAMPLE_RATE as usize * BOUNDARY_SEARCH_MS / 1000;
+        let second_start = samples_to_ms(plan_chunks(&samples, TARGET_SAMPLE_RATE as usize, search_samples)[1].start);
+        let timings: Vec<(i64, i64)> = transcript.segments.iter().map(|s| (s.start_ms, s.end_ms)).collect();
+        assert_eq!(timings, vec![(0, 800), (second_start + 100, second_start + 400)]);
+
+        let requests = server.requests("POST /v1/audio/transcriptions");
+        assert_eq!(requests.len(), 2);
+        let form = requests[0].body_text();
+        assert!(form.contains("name=\"model\"\r\n\r\nwhisper-large-v3"));
+        assert!(form.contains("name=\"language\"\r\n\r\nde"));
+        assert!(form.contains("name=\"prompt\"\r\n\r\nGlossary: Aurus."));
+        assert!(form.contains("filename=\"audio.wav\""));
+    }
+}
@@ -0,0 +1,815 @@
+//! Optional clean-up of transcripts before they are sent to agents.
+//!
+//! Stages run in a fixed order: filler removal, number/date normalization,
+//! PII redaction. Redaction swaps each email, phone number, IBAN or listed
+//! name for a placeholder like `[EMAIL_1]`; the returned redaction list lets
+//! agent output be re-hydrated locally, so the originals never leave the
+//! device.
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use super::language::{normalize_language, LanguageConfig};
+use crate::storage;
+
+const CONFIG_FILE: &str = "postprocess.json";
+
+const ENGLISH_FILLERS: &[&str] = &["um", "umm", "uh", "uhh", "uhm", "erm", "hmm", "mhm", "hm", "you know"];
+/// No "um": in German it is a preposition ("um 10 Uhr", "um … zu")
+const GERMAN_FILLERS: &[&str] = &["äh", "ähm", "öhm", "hmm", "mhm", "hm"];
+
+/// Fillers that are also real words ("Do you know…", "um 10 Uhr"), so they
+/// are only removed where they stand alone
+const AMBIGUOUS_FILLERS: &[&str] = &["um", "you know", "hm"];
+
+static EMAIL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b").unwrap());
+/// Candidate phone numbers; checked for a plausible digit count afterwards
+static PHONE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:\+|\b)\d[\d \t/().-]{5,}\d\b").unwrap());
+/// Candidate IBANs, with or without 4-character grouping; checksummed afterwards
+static IBAN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b").unwrap());
+static NUMERIC_DATE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(\d{1,2})\.(\d{1,2})\.(\d{4})\b").unwrap());
+static WORD: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\p{L}]+").unwrap());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FillerConfig {
+    pub enabled: bool,
+    /// Fillers removed in addition to the built-in list
+    pub extra_fillers: Vec<String>,
+}
+
+impl Default for FillerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            extra_fillers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NumberConfig {
+    /// Spelled-out numbers (English and German) to digits
+    pub numbers: bool,
+    /// Dates like "March 5, 2024" or "05.03.2024" to ISO 8601
+    pub dates: bool,
+}
+
+impl Default for NumberConfig {
+    fn default() -> Self {
+        Self {
+            numbers: true,
+            dates: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    pub emails: bool,
+    pub phone_numbers: bool,
+    pub ibans: bool,
+    /// Names redacted wherever they appear (whole words, case-insensitive)
+    pub names: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            emails: true,
+            phone_numbers: true,
+            ibans: true,
+            names: Vec::new(),
+        }
+    }
+}
+
+/// Pipeline configuration; nothing runs until `enabled` is set
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PostProcessConfig {
+    pub enabled: bool,
+    pub fillers: FillerConfig,
+    pub numbers: NumberConfig,
+    pub redaction: RedactionConfig,
+}
+
+impl PostProcessConfig {
+    pub fn load(app: &AppHandle) -> Self {
+        storage::load_json(app, CONFIG_FILE)
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, CONFIG_FILE, self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.fillers.extra_fillers.iter().any(|f| f.trim().is_empty()) {
+            return Err("Fillers must not be empty".to_string());
+        }
+        if self.redaction.names.iter().any(|n| n.trim().is_empty()) {
+            return Err("Redacted names must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Run all enabled stages; `language` (ISO 639-1) picks the built-in
+    /// fillers and number words, `None` uses those of all supported languages
+    pub fn apply(&self, text: &str, language: Option<&str>) -> ProcessedTranscript {
+        if !self.enabled {
+            return ProcessedTranscript {
+                text: text.to_string(),
+                redactions: Vec::new(),
+            };
+        }
+
+        let mut text = text.to_string();
+        if self.fillers.enabled {
+            text = remove_fillers(&text, language, &self.fillers.extra_fillers);
+        }
+        if self.numbers.dates {
+            text = normalize_dates(&text);
+        }
+        if self.numbers.numbers {
+            text = normalize_numbers(&text, language);
+        }
+
+        let mut redactor = Redactor::default();
+        if self.redaction.enabled {
+            text = redactor.redact(&text, &self.redaction);
+        }
+
+        ProcessedTranscript {
+            text,
+            redactions: redactor.redactions,
+        }
+    }
+}
+
+// ============================================================================
+// Filler removal
+// ============================================================================
+
+/// Built-in fillers of `language`; all of them when it's unknown
+fn builtin_fillers(language: Option<&str>) -> Vec<&'static str> {
+    match language {
+        Some("en") => ENGLISH_FILLERS.to_vec(),
+        Some("de") => GERMAN_FILLERS.to_vec(),
+        Some(_) => Vec::new(),
+        None => {
+            let mut fillers = ENGLISH_FILLERS.to_vec();
+            fillers.extend(GERMAN_FILLERS.iter().filter(|f| !ENGLISH_FILLERS.contains(f)));
+            fillers
+        }
+    }
+}
+
+/// Whether the filler at `start..end` is set off from the sentence: after
+/// its start, a comma or a semicolon, and followed by a comma, a semicolon
+/// or its end (`end` is past any comma and spaces after it)
+fn stands_alone(text: &str, start: usize, filler: &str, end: usize) -> bool {
+    let before = text[..start].trim_end();
+    let rest = text[end..].trim_start();
+    let opens = before.is_empty() || before.ends_with(['.', '!', '?', ',', ';']);
+    let closes = filler.ends_with([',', ';']) || rest.is_empty() || rest.starts_with(['.', '!', '?']);
+    opens && closes
+}
+
+/// Remove filler words plus the comma that usually follows them, and
+/// re-capitalize sentences that started with a filler. Ambiguous fillers
+/// are only removed where they stand alone.
+pub fn remove_fillers(text: &str, language: Option<&str>, extra: &[String]) -> String {
+    let mut fillers: Vec<&str> = builtin_fillers(language)
+        .into_iter()
+        .chain(extra.iter().map(|f| f.trim()))
+        .filter(|f| !f.is_empty())
+        .collect();
+    if fillers.is_empty() {
+        return text.to_string();
+    }
+    // Multi-word fillers first so "you know" wins over a single-word entry
+    fillers.sort_by_key(|f| std::cmp::Reverse(f.len()));
+
+    let alternatives: Vec<String> = fillers.iter().map(|f| regex::escape(f)).collect();
+    let Ok(pattern) = Regex::new(&format!(r"(?i)\b(?:{})\b[,;]?\s*", alternatives.join("|"))) else {
+        return text.to_string();
+    };
+
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut capitalize_next = false;
+    for m in pattern.find_iter(text) {
+        let filler = m.as_str().trim_end();
+        let word = filler.trim_end_matches([',', ';']).to_lowercase();
+        let listed_by_user = extra.iter().any(|f| f.trim().eq_ignore_ascii_case(&word));
+        if AMBIGUOUS_FILLERS.contains(&word.as_str())
+            && !listed_by_user
+            && !stands_alone(text, m.start(), filler, m.end())
+        {
+            continue;
+        }
+
+        let before = &text[last..m.start()];
+        result.push_str(&take_capitalized(before, &mut capitalize_next));
+        let trimmed = result.trim_end();
+        if trimmed.is_empty() || trimmed.ends_with(['.', '!', '?']) {
+            capitalize_next = true;
+        } else if before.ends_with(", ")
+            && (m.as_str().ends_with(", ") || text[m.end()..].starts_with(['.', '!', '?']))
+        {
+            // "so, um, we" -> "so we", "it, you know." -> "it.": the commas
+            // only bracketed the filler
+            result.truncate(result.len() - 2);
+            result.push(' ');
+        }
+        last = m.end();
+    }
+    result.push_str(&take_capitalized(&text[last..], &mut capitalize_next));
+
+    tidy_spacing(&result)
+}
+
+fn take_capitalized(text: &str, capitalize: &mut bool) -> String {
+    if !*capitalize || text.is_empty() {
+        return text.to_string();
+    }
+    *capitalize = false;
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn tidy_spacing(text: &str) -> String {
+    static SPACE_BEFORE_PUNCT: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+([,.!?;:])").unwrap());
+    static MULTI_SPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[ \t]{2,}").unwrap());
+    let text = SPACE_BEFORE_PUNCT.replace_all(text, "$1");
+    MULTI_SPACE.replace_all(&text, " ").trim().to_string()
+}
+
+// ============================================================================
+// Numbers and dates
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberToken {
+    Value(u64),
+    Scale(u64),
+}
+
+fn english_number(word: &str) -> Option<NumberToken> {
+    const UNITS: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 8] = ["twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+
+    if let Some(n) = UNITS.iter().position(|u| *u == word) {
+        return Some(NumberToken::Value(n as u64));
+    }
+    if let Some(n) = TENS.iter().position(|t| *t == word) {
+        return Some(NumberToken::Value((n as u64 + 2) * 10));
+    }
+    match word {
+        "hundred" => Some(NumberToken::Scale(100)),
+        "thousand" => Some(NumberToken::Scale(1_000)),
+        "million" => Some(NumberToken::Scale(1_000_000)),
+        "billion" => Some(NumberToken::Scale(1_000_000_000)),
+        _ => None,
+    }
+}
+
+/// German numbers are written as one word ("dreihundertzweiundvierzig")
+fn german_number(word: &str) -> Option<u64> {
+    if word.is_empty() {
+        return None;
+    }
+    for (scale, value) in [("tausend", 1_000), ("hundert", 100)] {
+        if let Some(pos) = word.find(scale) {
+            let (left, right) = (&word[..pos], &word[pos + scale.len()..]);
+            let multiplier = if left.is_empty() { 1 } else { german_below_hundred(left, true)? };
+            let rest = if right.is_empty() { 0 } else { german_number(right)? };
+            if rest >= value {
+                return None;
+            }
+            return Some(multiplier * value + rest);
+        }
+    }
+    german_below_hundred(word, false)
+}
+
+fn german_below_hundred(word: &str, as_prefix: bool) -> Option<u64> {
+    const NUMBERS: [(&str, u64); 30] = [
+        ("null", 0), ("eins", 1), ("ein", 1), ("eine", 1), ("zwei", 2), ("drei", 3), ("vier", 4),
+        ("fünf", 5), ("sechs", 6), ("sieben", 7), ("acht", 8), ("neun", 9), ("zehn", 10),
+        ("elf", 11), ("zwölf", 12), ("dreizehn", 13), ("vierzehn", 14), ("fünfzehn", 15),
+        ("sechzehn", 16), ("siebzehn", 17), ("achtzehn", 18), ("neunzehn", 19), ("zwanzig", 20),
+        ("dreißig", 30), ("vierzig", 40), ("fünfzig", 50), ("sechzig", 60), ("siebzig", 70),
+        ("achtzig", 80), ("neunzig", 90),
+    ];
+    let lookup = |w: &str| NUMBERS.iter().find(|(name, _)| *name == w).map(|(_, n)| *n);
+
+    if let Some(n) = lookup(word) {
+        // "eins" only stands alone ("einhundert", not "einshundert")
+        return (!(as_prefix && word == "eins")).then_some(n);
+    }
+    let (unit, tens) = word.split_once("und")?;
+    let unit = lookup(unit).filter(|n| (1..10).contains(n) && unit != "eins")?;
+    let tens = lookup(tens).filter(|n| *n >= 20 && n % 10 == 0)?;
+    Some(tens + unit)
+}
+
+/// Number word of `language`, or of any supported language when it's unknown
+fn number_token(word: &str, language: Option<&str>) -> Option<NumberToken> {
+    let lower = word.to_lowercase();
+    let german = || german_number(&lower).map(NumberToken::Value);
+    match language {
+        Some("en") => english_number(&lower),
+        Some("de") => german(),
+        Some(_) => None,
+        None => english_number(&lower).or_else(german),
+    }
+}
+
+/// Accumulates a run of number words, refusing sequences that aren't one number
+#[derive(Default)]
+struct NumberRun {
+    total: u64,
+    current: u64,
+    words: usize,
+    /// Last token was a scale word, so "and" may follow
+    after_scale: bool,
+}
+
+impl NumberRun {
+    fn push(&mut self, token: NumberToken) -> bool {
+        match token {
+            NumberToken::Value(v) => {
+                let fits = self.current == 0
+                    || (self.current.is_multiple_of(100) && v < 100)
+                    || (self.current % 100 >= 20 && self.current.is_multiple_of(10) && v < 10);
+                if !fits || (self.words > 0 && self.current == 0 && self.total == 0) {
+                    return false;
+                }
+                self.current += v;
+                self.after_scale = false;
+            }
+            NumberToken::Scale(100) => {
+                if self.current >= 100 {
+                    return false;
+                }
+                self.current = self.current.max(1) * 100;
+                self.after_scale = true;
+            }
+            NumberToken::Scale(scale) => {
+                if self.total != 0 && self.total < scale * 1000 && self.current == 0 {
+                    return false;
+                }
+                self.total += self.current.max(1) * scale;
+                self.current = 0;
+                self.after_scale = true;
+            }
+        }
+        self.words += 1;
+        true
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+
+    /// Single small numbers stay spelled out ("one of the", "zwei Tage")
+    fn worth_converting(&self) -> bool {
+        self.words > 1 || self.value() >= 10
+    }
+}
+
+/// Replace spelled-out numbers of `language` with digits
+pub fn normalize_numbers(text: &str, language: Option<&str>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    let words: Vec<_> = WORD.find_iter(text).collect();
+    let mut i = 0;
+
+    while i < words.len() {
+        let Some(first) = number_token(words[i].as_str(), language) else {
+            i += 1;
+            continue;
+        };
+
+        let mut run = NumberRun::default();
+        run.push(first);
+        let mut end = i;
+        let mut j = i + 1;
+        while j < words.len() {
+            let gap = &text[words[j - 1].end()..words[j].start()];
+            if gap == " " || gap == "-" {
+                match number_token(words[j].as_str(), language) {
+                    Some(token) if run.push(token) => {
+                        end = j;
+                        j += 1;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            // "one hundred and five"
+            if gap == " " && run.after_scale && words[j].as_str().eq_ignore_ascii_case("and") && j + 1 < words.len() {
+                let next_gap = &text[words[j].end()..words[j + 1].start()];
+                if next_gap == " " {
+                    if let Some(token @ NumberToken::Value(_)) = number_token(words[j + 1].as_str(), language) {
+                        if run.push(token) {
+                            end = j + 1;
+                            j += 2;
+                            continue;
+                        }
+                    }
+                }
+            }
+            break;
+        }
+
+        if run.worth_converting() {
+            result.push_str(&text[last..words[i].start()]);
+            result.push_str(&run.value().to_string());
+            last = words[end].end();
+        }
+        i = end + 1;
+    }
+
+    result.push_str(&text[last..]);
+    result
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    const MONTHS: [(&str, &str, u32); 12] = [
+        ("january", "januar", 1), ("february", "februar", 2), ("march", "märz", 3),
+        ("april", "april", 4), ("may", "mai", 5), ("june", "juni", 6), ("july", "juli", 7),
+        ("august", "august", 8), ("september", "september", 9), ("october", "oktober", 10),
+        ("november", "november", 11), ("december", "dezember", 12),
+    ];
+    let name = name.to_lowercase();
+    MONTHS
+        .iter()
+        .find(|(en, de, _)| *en == name || *de == name)
+        .map(|(_, _, n)| *n)
+}
+
+fn iso_date(year: &str, month: u32, day: &str) -> Option<String> {
+    let day: u32 = day.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(format!("{}-{:02}-{:02}", year, month, day))
+}
+
+/// Rewrite dates as ISO 8601 (`2024-03-05`)
+pub fn normalize_dates(text: &str) -> String {
+    static MONTH_FIRST: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"\b(\p{L}+) (\d{1,2})(?:st|nd|rd|th)?,? (\d{4})\b").unwrap()
+    });
+    static DAY_FIRST: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"\b(\d{1,2})(?:\.|st|nd|rd|th)? (?:of )?(\p{L}+),? (\d{4})\b").unwrap()
+    });
+
+    let text = NUMERIC_DATE.replace_all(text, |c: &Captures| {
+        let month = c[2].parse().unwrap_or(0);
+        iso_date(&c[3], month, &c[1]).unwrap_or_else(|| c[0].to_string())
+    });
+    let text = MONTH_FIRST.replace_all(&text, |c: &Captures| {
+        month_number(&c[1])
+            .and_then(|month| iso_date(&c[3], month, &c[2]))
+            .unwrap_or_else(|| c[0].to_string())
+    });
+    DAY_FIRST
+        .replace_all(&text, |c: &Captures| {
+            month_number(&c[2])
+                .and_then(|month| iso_date(&c[3], month, &c[1]))
+                .unwrap_or_else(|| c[0].to_string())
+        })
+        .into_owned()
+}
+
+// ============================================================================
+// PII redaction
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Email,
+    Phone,
+    Iban,
+    Name,
+}
+
+impl PiiKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Email => "EMAIL",
+            Self::Phone => "PHONE",
+            Self::Iban => "IBAN",
+            Self::Name => "NAME",
+        }
+    }
+}
+
+/// A placeholder and the text it stands for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Redaction {
+    pub placeholder: String,
+    pub original: String,
+    pub kind: PiiKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProcessedTranscript {
+    pub text: String,
+    pub redactions: Vec<Redaction>,
+}
+
+/// IBAN mod-97 check (ISO 13616)
+fn is_valid_iban(candidate: &str) -> bool {
+    let compact: String = candidate.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let rearranged = compact[4..].chars().chain(compact[..4].chars());
+    let mut remainder: u32 = 0;
+    for c in rearranged {
+        let Some(value) = c.to_digit(36) else {
+            return false;
+        };
+        remainder = if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+fn is_phone_number(candidate: &str) -> bool {
+    let digits = candidate.chars().filter(|c| c.is_ascii_digit()).count();
+    // ISO dates and plain years/amounts are not phone numbers
+    (7..=15).contains(&digits) && !NUMERIC_ISO_DATE.is_match(candidate)
+}
+
+static NUMERIC_ISO_DATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap());
+
+/// Assigns stable placeholders: the same value always maps to the same one
+#[derive(Default)]
+struct Redactor {
+    redactions: Vec<Redaction>,
+    counters: HashMap<PiiKind, usize>,
+}
+
+impl Redactor {
+    fn placeholder(&mut self, kind: PiiKind, original: &str) -> String {
+        if let Some(existing) = self
+            .redactions
+            .iter()
+            .find(|r| r.kind == kind && r.original.eq_ignore_ascii_case(original))
+        {
+            return existing.placeholder.clone();
+        }
+        let n = self.counters.entry(kind).or_insert(0);
+        *n += 1;
+        let placeholder = format!("[{}_{}]", kind.label(), n);
+        self.redactions.push(Redaction {
+            placeholder: placeholder.clone(),
+            original: original.to_string(),
+            kind,
+        });
+        placeholder
+    }
+
+    fn replace(&mut self, text: &str, pattern: &Regex, kind: PiiKind, accept: impl Fn(&str) -> bool) -> String {
+        pattern
+            .replace_all(text, |c: &Captures| {
+                if accept(&c[0]) {
+                    self.placeholder(kind, &c[0])
+                } else {
+                    c[0].to_string()
+                }
+            })
+            .into_owned()
+    }
+
+    fn redact(&mut self, text: &str, config: &RedactionConfig) -> String {
+        let mut text = text.to_string();
+        // Most specific first, so an IBAN isn't half-eaten as a phone number
+        if config.emails {
+            text = self.replace(&text, &EMAIL, PiiKind::Email, |_| true);
+        }
+        if config.ibans {
+            text = self.replace(&text, &IBAN, PiiKind::Iban, is_valid_iban);
+        }
+        if config.phone_numbers {
+            text = self.replace(&text, &PHONE, PiiKind::Phone, is_phone_number);
+        }
+
+        let mut names: Vec<&str> = config.names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()).collect();
+        names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+        if !names.is_empty() {
+            let alternatives: Vec<String> = names.iter().map(|n| regex::escape(n)).collect();
+            if let Ok(pattern) = Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives.join("|"))) {
+                text = self.replace(&text, &pattern, PiiKind::Name, |_| true);
+            }
+        }
+        text
+    }
+}
+
+/// Put the original values back in place of their placeholders
+pub fn rehydrate(text: &str, redactions: &[Redaction]) -> String {
+    redactions.iter().fold(text.to_string(), |text, r| text.replace(&r.placeholder, &r.original))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_postprocess_config(app: AppHandle) -> Result<PostProcessConfig, String> {
+    Ok(PostProcessConfig::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_postprocess_config(app: AppHandle, config: PostProcessConfig) -> Result<(), String> {
+    config.validate()?;
+    config.save(&app)?;
+    tracing::info!("Transcript post-processing updated (enabled: {})", config.enabled);
+    Ok(())
+}
+
+/// Prepare a transcript for the agents with the saved pipeline configuration.
+/// `language` is the transcript's; without it the configured language is
+/// used unless detection is on.
+#[tauri::command]
+pub async fn process_transcript(
+    app: AppHandle,
+    text: String,
+    language: Option<String>,
+) -> Result<ProcessedTranscript, String> {
+    let language = match language.as_deref().and_then(normalize_language) {
+        Some(language) => Some(language),
+        None => LanguageConfig::load(&app).fixed_language().map(String::from),
+    };
+    Ok(PostProcessConfig::load(&app).apply(&text, language.as_deref()))
+}
+
+/// Restore redacted values in agent output
+#[tauri::command]
+pub async fn rehydrate_text(text: String, redactions: Vec<Redaction>) -> Result<String, String> {
+    Ok(rehydrate(&text, &redactions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_fillers() {
+        assert_eq!(remove_fillers("Um, so we shipped it.", None, &[]), "So we shipped it.");
+        assert_eq!(
+            remove_fillers("We, uh, you know, shipped it. Ähm das war gut.", None, &[]),
+            "We shipped it. Das war gut."
+        );
+        assert_eq!(remove_fillers("The umbrella is here", None, &[]), "The umbrella is here");
+        assert_eq!(
+            remove_fillers("It was like basically done", None, &["basically".to_string()]),
+            "It was like done"
+        );
+    }
+
+    #[test]
+    fn test_remove_fillers_keeps_real_words() {
+        // German "um" is a preposition
+        for text in ["Wir treffen uns um 10 Uhr.", "Ich rufe an, um das zu klären."] {
+            assert_eq!(remove_fillers(text, Some("de"), &[]), text);
+            assert_eq!(remove_fillers(text, None, &[]), text);
+        }
+        assert_eq!(
+            remove_fillers("Ähm, wir treffen uns um 10 Uhr.", Some("de"), &[]),
+            "Wir treffen uns um 10 Uhr."
+        );
+
+        for text in ["Do you know the answer?", "You know the answer.", "I said hm to that."] {
+            assert_eq!(remove_fillers(text, Some("en"), &[]), text);
+        }
+        assert_eq!(
+            remove_fillers("That was it, you know.", Some("en"), &[]),
+            "That was it."
+        );
+        assert_eq!(remove_fillers("Hm, let me think.", Some("en"), &[]), "Let me think.");
+        // English fillers aren't applied to other languages
+        assert_eq!(remove_fillers("Erm, ok", Some("fr"), &[]), "Erm, ok");
+    }
+
+    #[test]
+    fn test_normalize_numbers_english() {
+        assert_eq!(normalize_numbers("twenty-five people", Some("en")), "25 people");
+        assert_eq!(normalize_numbers("one hundred and five tickets", Some("en")), "105 tickets");
+        assert_eq!(
+            normalize_numbers("three thousand two hundred users", Some("en")),
+            "3200 users"
+        );
+        assert_eq!(normalize_numbers("one of the two options", Some("en")), "one of the two options");
+        assert_eq!(normalize_numbers("ten minutes", Some("en")), "10 minutes");
+        // Not a single number
+        assert_eq!(normalize_numbers("five twenty", Some("en")), "five 20");
+        // German number words stay as they are in English text
+        assert_eq!(normalize_numbers("an elf with zwanzig hats", Some("en")), "an elf with zwanzig hats");
+        assert_eq!(normalize_numbers("an elf", None), "an 11");
+    }
+
+    #[test]
+    fn test_normalize_numbers_german() {
+        assert_eq!(normalize_numbers("dreiundzwanzig Tickets", Some("de")), "23 Tickets");
+        assert_eq!(normalize_numbers("zweihundertfünf Nutzer", Some("de")), "205 Nutzer");
+        assert_eq!(normalize_numbers("eintausendeins", Some("de")), "1001");
+        assert_eq!(normalize_numbers("ein Tag und zwei Stunden", Some("de")), "ein Tag und zwei Stunden");
+        assert_eq!(normalize_numbers("Dreißig Minuten", Some("de")), "30 Minuten");
+        assert_eq!(normalize_numbers("twenty Tickets", Some("de")), "twenty Tickets");
+        assert_eq!(normalize_numbers("vingt minutes", Some("fr")), "vingt minutes");
+    }
+
+    #[test]
+    fn test_normalize_dates() {
+        assert_eq!(normalize_dates("due March 5th, 2024"), "due 2024-03-05");
+        assert_eq!(normalize_dates("am 5. März 2024 fertig"), "am 2024-03-05 fertig");
+        assert_eq!(normalize_dates("bis 05.03.2024"), "bis 2024-03-05");
+        assert_eq!(normalize_dates("the 3rd of May 2025"), "the 2025-05-03");
+        assert_eq!(normalize_dates("version 40.13.2024"), "version 40.13.2024");
+    }
+
+    #[test]
+    fn test_iban_checksum() {
+        assert!(is_valid_iban("DE89 3704 0044 0532 0130 00"));
+        assert!(is_valid_iban("GB82WEST12345698765432"));
+        assert!(!is_valid_iban("DE89 3704 0044 0532 0130 01"));
+    }
+
+    #[test]
+    fn test_redaction_round_trip() {
+        let config = RedactionConfig {
+            names: vec!["Anna Schmidt".to_string()],
+            ..Default::default()
+        };
+        let text = "Mail anna@example.com or call +49 170 1234567. Pay to DE89 3704 0044 0532 0130 00. \
+                    Anna Schmidt agreed; anna@example.com is fine.";
+        let mut redactor = Redactor::default();
+        let redacted = redactor.redact(text, &config);
+
+        assert_eq!(
+            redacted,
+            "Mail [EMAIL_1] or call [PHONE_1]. Pay to [IBAN_1]. [NAME_1] agreed; [EMAIL_1] is fine."
+        );
+        assert_eq!(redactor.redactions.len(), 4);
+        assert_eq!(rehydrate(&redacted, &redactor.redactions), text);
+    }
+
+    #[test]
+    fn test_redaction_ignores_dates_and_short_numbers() {
+        let mut redactor = Redactor::default();
+        let text = "On 2024-03-05 we had 1500 users";
+        assert_eq!(redactor.redact(text, &RedactionConfig::default()), text);
+        assert!(redactor.redactions.is_empty());
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let disabled = PostProcessConfig::default();
+        assert_eq!(disabled.apply("um, twenty people", None).text, "um, twenty people");
+
+        let config = PostProcessConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let processed = config.apply("Um, twenty people wrote to bob@example.org on 05.03.2024.", Some("en"));
+        assert_eq!(processed.text, "20 people wrote to [EMAIL_1] on 2024-03-05.");
+        assert_eq!(processed.redactions[0].kind, PiiKind::Email);
+
+        let stages_off = PostProcessConfig {
+            enabled: true,
+            fillers: FillerConfig { enabled: false, ..Default::default() },
+            numbers: NumberConfig { numbers: false, dates: false },
+            redaction: RedactionConfig { enabled: false, ..Default::default() },
+        };
+        assert_eq!(stages_off.apply("um, twenty", None).text, "um, twenty");
+    }
+}
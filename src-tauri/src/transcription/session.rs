@@ -0,0 +1,366 @@
+//! Live transcription session lifecycle and audio routing.
+//!
+//! A live session moves `Idle → Connecting → Streaming → Draining → Idle`, or
+//! to `Failed` from any active phase; only one session runs at a time. Audio
+//! from the capture callback enters through [`AudioIngress`], which never
+//! takes a lock, so a busy `TranscriptionState` no longer silently loses
+//! chunks. A router task forwards the queued audio to the engine of the
+//! current session, and every chunk that can't be delivered is counted.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::TranscriptionManager;
+use crate::platform::TARGET_SAMPLE_RATE;
+
+/// Chunks (~100ms each) the ingress holds while the router is busy
+const INGRESS_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionPhase {
+    #[default]
+    Idle,
+    /// Opening the engine; audio isn't routed yet
+    Connecting,
+    Streaming,
+    /// Audio input closed; the engine is finishing what it received
+    Draining,
+    Failed,
+}
+
+impl SessionPhase {
+    pub fn can_transition_to(self, next: SessionPhase) -> bool {
+        use SessionPhase::*;
+        matches!(
+            (self, next),
+            (Idle | Failed, Connecting)
+                | (Connecting, Streaming)
+                | (Connecting | Streaming, Draining)
+                | (Draining, Idle)
+                | (Connecting | Streaming | Draining, Failed)
+        )
+    }
+
+    /// A session occupies the live slot
+    pub fn is_busy(self) -> bool {
+        matches!(self, SessionPhase::Connecting | SessionPhase::Streaming | SessionPhase::Draining)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveEngine {
+    Deepgram,
+    LocalWhisper,
+}
+
+/// The current (or last) live session
+#[derive(Default)]
+pub struct LiveSession {
+    pub id: Option<String>,
+    pub engine: Option<LiveEngine>,
+    pub phase: SessionPhase,
+    /// Audio input of the engine while streaming
+    sink: Option<mpsc::Sender<Vec<i16>>>,
+    /// Switch to local Whisper if the Deepgram stream fails (hybrid sessions)
+    pub local_fallback: bool,
+    /// Task receiving Deepgram results; ends once the connection is closed
+    pub deepgram_reader: Option<JoinHandle<()>>,
+    pub last_error: Option<String>,
+}
+
+impl LiveSession {
+    fn transition(&mut self, next: SessionPhase) -> Result<(), String> {
+        if !self.phase.can_transition_to(next) {
+            return Err(format!(
+                "Invalid session transition from {:?} to {:?}",
+                self.phase, next
+            ));
+        }
+        tracing::debug!("Live session {:?}: {:?} -> {:?}", self.id, self.phase, next);
+        self.phase = next;
+        Ok(())
+    }
+
+    /// Claim the live slot for a new session on `engine`; returns its ID
+    pub fn begin(&mut self, engine: LiveEngine) -> Result<String, String> {
+        match self.phase {
+            SessionPhase::Draining => {
+                return Err("The previous transcription session is still finishing".to_string())
+            }
+            phase if phase.is_busy() => {
+                return Err("A live transcription session is already active".to_string())
+            }
+            _ => {}
+        }
+        self.transition(SessionPhase::Connecting)?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        self.id = Some(id.clone());
+        self.engine = Some(engine);
+        self.sink = None;
+        self.local_fallback = false;
+        self.deepgram_reader = None;
+        self.last_error = None;
+        Ok(id)
+    }
+
+    pub fn is_current(&self, id: &str) -> bool {
+        self.id.as_deref() == Some(id)
+    }
+
+    /// The engine is ready: route audio to `sink`
+    pub fn start_streaming(&mut self, id: &str, sink: mpsc::Sender<Vec<i16>>) -> Result<(), String> {
+        if !self.is_current(id) || self.phase != SessionPhase::Connecting {
+            return Err("Transcription session was stopped while connecting".to_string());
+        }
+        self.transition(SessionPhase::Streaming)?;
+        self.sink = Some(sink);
+        Ok(())
+    }
+
+    /// Stop routing audio. Dropping the sink lets the engine finish what it
+    /// already received; returns false when nothing was running.
+    pub fn drain(&mut self) -> bool {
+        if self.transition(SessionPhase::Draining).is_err() {
+            return false;
+        }
+        self.sink = None;
+        self.local_fallback = false;
+        true
+    }
+
+    /// The engine of session `id` delivered its last results
+    pub fn finish(&mut self, id: &str) {
+        if self.is_current(id) && self.phase == SessionPhase::Draining {
+            let _ = self.transition(SessionPhase::Idle);
+            self.deepgram_reader = None;
+        }
+    }
+
+    /// Session `id` broke; returns false if it had already ended or been replaced
+    pub fn fail(&mut self, id: &str, error: &str) -> bool {
+        if !self.is_current(id) || self.transition(SessionPhase::Failed).is_err() {
+            return false;
+        }
+        self.sink = None;
+        self.deepgram_reader = None;
+        self.last_error = Some(error.to_string());
+        true
+    }
+
+    /// Streaming on `engine` right now
+    pub fn is_streaming_on(&self, engine: LiveEngine) -> bool {
+        self.phase == SessionPhase::Streaming && self.engine == Some(engine)
+    }
+}
+
+// ============================================================================
+// Audio Ingress
+// ============================================================================
+
+#[derive(Default)]
+struct IngressCounters {
+    forwarded: AtomicU64,
+    dropped: AtomicU64,
+    forwarded_samples: AtomicU64,
+}
+
+impl IngressCounters {
+    fn reset(&self) {
+        self.forwarded.store(0, Ordering::Relaxed);
+        self.dropped.store(0, Ordering::Relaxed);
+        self.forwarded_samples.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Lock-free entry point for captured audio (managed as app state)
+#[derive(Clone)]
+pub struct AudioIngress {
+    tx: mpsc::Sender<Vec<i16>>,
+    counters: Arc<IngressCounters>,
+}
+
+impl AudioIngress {
+    /// Create the ingress and spawn the task routing it to the live session
+    pub fn start(state: TranscriptionManager) -> Self {
+        let (tx, rx) = mpsc::channel(INGRESS_CAPACITY);
+        let counters = Arc::new(IngressCounters::default());
+        tauri::async_runtime::spawn(route_audio(rx, state, counters.clone()));
+        Self { tx, counters }
+    }
+
+    /// Queue a chunk without blocking; counted as dropped if the queue is full
+    pub fn push(&self, samples: Vec<i16>) {
+        if self.tx.try_send(samples).is_err() {
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Start counting for a new session
+    pub fn reset_counters(&self) {
+        self.counters.reset();
+    }
+
+    fn queued_chunks(&self) -> usize {
+        self.tx.max_capacity() - self.tx.capacity()
+    }
+}
+
+/// Forward queued audio to the current session's engine. Waits for a full
+/// engine instead of dropping, so the ingress queue absorbs short stalls.
+async fn route_audio(
+    mut rx: mpsc::Receiver<Vec<i16>>,
+    state: TranscriptionManager,
+    counters: Arc<IngressCounters>,
+) {
+    while let Some(chunk) = rx.recv().await {
+        // Outside a session the audio only goes to the recording buffer
+        let Some(sink) = state.lock().await.session.sink.clone() else {
+            continue;
+        };
+        let samples = chunk.len() as u64;
+        if sink.send(chunk).await.is_ok() {
+            counters.forwarded.fetch_add(1, Ordering::Relaxed);
+            counters.forwarded_samples.fetch_add(samples, Ordering::Relaxed);
+        } else {
+            counters.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Claim the live slot for `engine` and reset the audio counters
+pub(crate) async fn begin_session(
+    app: &AppHandle,
+    state: &TranscriptionManager,
+    engine: LiveEngine,
+) -> Result<String, String> {
+    let id = state.lock().await.session.begin(engine)?;
+    if let Some(ingress) = app.try_state::<AudioIngress>() {
+        ingress.reset_counters();
+    }
+    tracing::info!("Live session {} starting on {:?}", id, engine);
+    Ok(id)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionStats {
+    pub session_id: Option<String>,
+    pub engine: Option<LiveEngine>,
+    pub phase: SessionPhase,
+    pub last_error: Option<String>,
+    /// Chunks delivered to the engine in this session
+    pub chunks_forwarded: u64,
+    /// Chunks lost because the ingress queue was full or the engine had gone
+    pub chunks_dropped: u64,
+    /// Chunks waiting in the ingress queue
+    pub chunks_queued: usize,
+    pub audio_secs: f64,
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_transcription_stats(
+    state: tauri::State<'_, TranscriptionManager>,
+    ingress: tauri::State<'_, AudioIngress>,
+) -> Result<TranscriptionStats, String> {
+    let state_guard = state.lock().await;
+    let session = &state_guard.session;
+    let counters = &ingress.counters;
+
+    Ok(TranscriptionStats {
+        session_id: session.id.clone(),
+        engine: session.engine,
+        phase: session.phase,
+        last_error: session.last_error.clone(),
+        chunks_forwarded: counters.forwarded.load(Ordering::Relaxed),
+        chunks_dropped: counters.dropped.load(Ordering::Relaxed),
+        chunks_queued: ingress.queued_chunks(),
+        audio_secs: counters.forwarded_samples.load(Ordering::Relaxed) as f64
+            / TARGET_SAMPLE_RATE as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions() {
+        use SessionPhase::*;
+        assert!(Idle.can_transition_to(Connecting));
+        assert!(Failed.can_transition_to(Connecting));
+        assert!(Connecting.can_transition_to(Streaming));
+        assert!(Streaming.can_transition_to(Draining));
+        assert!(Draining.can_transition_to(Idle));
+        assert!(Streaming.can_transition_to(Failed));
+
+        assert!(!Idle.can_transition_to(Streaming));
+        assert!(!Streaming.can_transition_to(Connecting));
+        assert!(!Draining.can_transition_to(Streaming));
+        assert!(!Idle.can_transition_to(Failed));
+    }
+
+    #[test]
+    fn test_session_lifecycle() {
+        let mut session = LiveSession::default();
+        let id = session.begin(LiveEngine::Deepgram).unwrap();
+        assert_eq!(session.phase, SessionPhase::Connecting);
+        assert!(session.begin(LiveEngine::LocalWhisper).is_err());
+
+        let (tx, _rx) = mpsc::channel(1);
+        session.start_streaming(&id, tx).unwrap();
+        assert!(session.is_streaming_on(LiveEngine::Deepgram));
+        assert!(!session.is_streaming_on(LiveEngine::LocalWhisper));
+
+        assert!(session.drain());
+        assert!(session.sink.is_none());
+        assert!(session.begin(LiveEngine::Deepgram).is_err());
+        session.finish("another-session");
+        assert_eq!(session.phase, SessionPhase::Draining);
+        session.finish(&id);
+        assert_eq!(session.phase, SessionPhase::Idle);
+        assert!(!session.drain());
+    }
+
+    #[test]
+    fn test_failure_and_replacement() {
+        let mut session = LiveSession::default();
+        let first = session.begin(LiveEngine::Deepgram).unwrap();
+        let (tx, _rx) = mpsc::channel(1);
+        session.start_streaming(&first, tx).unwrap();
+
+        assert!(session.fail(&first, "connection reset"));
+        assert_eq!(session.phase, SessionPhase::Failed);
+        assert_eq!(session.last_error.as_deref(), Some("connection reset"));
+        assert!(!session.fail(&first, "again"));
+
+        // The fallback starts a fresh session; the old one can't touch it
+        let second = session.begin(LiveEngine::LocalWhisper).unwrap();
+        assert_ne!(first, second);
+        assert!(session.last_error.is_none());
+        assert!(!session.fail(&first, "stale"));
+        let (tx, _rx) = mpsc::channel(1);
+        assert!(session.start_streaming(&first, tx).is_err());
+    }
+
+    #[test]
+    fn test_stop_while_connecting() {
+        let mut session = LiveSession::default();
+        let id = session.begin(LiveEngine::LocalWhisper).unwrap();
+        assert!(session.drain());
+
+        let (tx, _rx) = mpsc::channel(1);
+        assert!(session.start_streaming(&id, tx).is_err());
+        session.finish(&id);
+        assert_eq!(session.phase, SessionPhase::Idle);
+    }
+}
@@ -0,0 +1,131 @@
+//! Local HTTP stand-in for provider APIs, used by the transcription tests.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex as StdMutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+type Routes = HashMap<&'static str, VecDeque<(u16, &'static str)>>;
+
+/// A request received by the stand-in
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// "METHOD /path"
+    pub route: String,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    pub fn body_text(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+}
+
+pub struct StandIn {
+    pub url: String,
+    requests: Arc<StdMutex<Vec<RecordedRequest>>>,
+}
+
+impl StandIn {
+    /// Requests received so far for a route, in order
+    pub fn requests(&self, route: &str) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.route == route)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Minimal HTTP/1.1 server answering canned JSON responses.
+///
+/// Each route ("METHOD /path") answers with its queued responses in order;
+/// the last one is repeated once the queue is down to a single entry.
+pub async fn stand_in(routes: &[(&'static str, &[(u16, &'static str)])]) -> StandIn {
+    let routes: Routes = routes
+        .iter()
+        .map(|(route, responses)| (*route, responses.iter().copied().collect()))
+        .collect();
+    let routes = Arc::new(StdMutex::new(routes));
+    let requests = Arc::new(StdMutex::new(Vec::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let recorded = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let routes = routes.clone();
+            let recorded = recorded.clone();
+            tokio::spawn(async move {
+                let request = read_request(&mut socket).await;
+                let (status, body) = {
+                    let mut routes = routes.lock().unwrap();
+                    match routes.get_mut(request.route.as_str()) {
+                        Some(queue) if queue.len() > 1 => queue.pop_front().unwrap(),
+                        Some(queue) => queue[0],
+                        None => (404, r#"{"error": "not found"}"#),
+                    }
+                };
+                recorded.lock().unwrap().push(request);
+
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    StandIn {
+        url: format!("http://{}", addr),
+        requests,
+    }
+}
+
+async fn read_request(socket: &mut TcpStream) -> RecordedRequest {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 8192];
+    let mut body_start = None;
+    let mut content_length = 0;
+
+    loop {
+        let n = socket.read(&mut buf).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+
+        if body_start.is_none() {
+            if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                body_start = Some(pos + 4);
+                content_length = String::from_utf8_lossy(&data[..pos])
+                    .lines()
+                    .find_map(|l| {
+                        let (name, value) = l.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+            }
+        }
+        if let Some(start) = body_start {
+            if data.len() >= start + content_length {
+                break;
+            }
+        }
+    }
+
+    let start = body_start.unwrap_or(data.len());
+    let head = String::from_utf8_lossy(&data[..start]).to_string();
+    let mut parts = head.split_whitespace();
+    RecordedRequest {
+        route: format!("{} {}", parts.next().unwrap_or(""), parts.next().unwrap_or("")),
+        body: data[start..].to_vec(),
+    }
+}
@@ -0,0 +1,457 @@
+//! Local ledger of audio sent to paid transcription providers.
+//!
+//! Every cloud session or job adds the seconds of audio it sent, keyed by
+//! provider, session and day. Costs are estimated at report time from a
+//! user-editable price table, and optional daily/monthly budgets can stop
+//! new cloud streams from starting once they are used up.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::storage;
+
+const LEDGER_FILE: &str = "usage-ledger.json";
+const CONFIG_FILE: &str = "usage-config.json";
+
+/// Records older than this are dropped when the ledger is written
+const RETENTION_DAYS: i64 = 400;
+
+/// Serializes read-modify-write cycles on the ledger file
+static LEDGER_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageProvider {
+    Deepgram,
+    AssemblyAi,
+    #[serde(rename = "openai_compatible")]
+    OpenAiCompatible,
+}
+
+/// Estimated list prices in USD per minute of audio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PriceTable {
+    pub deepgram: f64,
+    pub assembly_ai: f64,
+    /// Set to 0 for self-hosted servers
+    pub openai_compatible: f64,
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        Self {
+            deepgram: 0.0077,
+            assembly_ai: 0.002,
+            openai_compatible: 0.006,
+        }
+    }
+}
+
+impl PriceTable {
+    pub fn per_minute(&self, provider: UsageProvider) -> f64 {
+        match provider {
+            UsageProvider::Deepgram => self.deepgram,
+            UsageProvider::AssemblyAi => self.assembly_ai,
+            UsageProvider::OpenAiCompatible => self.openai_compatible,
+        }
+    }
+
+    pub fn cost(&self, provider: UsageProvider, audio_secs: f64) -> f64 {
+        self.per_minute(provider) * audio_secs / 60.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UsageConfig {
+    pub prices: PriceTable,
+    /// Estimated spend allowed per day (USD); `None` for no limit
+    pub daily_budget: Option<f64>,
+    /// Estimated spend allowed per calendar month (USD); `None` for no limit
+    pub monthly_budget: Option<f64>,
+    /// Refuse to start cloud streams while a budget is exceeded
+    pub block_when_exceeded: bool,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self {
+            prices: PriceTable::default(),
+            daily_budget: None,
+            monthly_budget: None,
+            block_when_exceeded: true,
+        }
+    }
+}
+
+impl UsageConfig {
+    pub fn load(app: &AppHandle) -> Self {
+        storage::load_json(app, CONFIG_FILE)
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, CONFIG_FILE, self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let prices = [
+            ("Deepgram", self.prices.deepgram),
+            ("AssemblyAI", self.prices.assembly_ai),
+            ("OpenAI-compatible", self.prices.openai_compatible),
+        ];
+        for (name, price) in prices {
+            if !price.is_finite() || price < 0.0 {
+                return Err(format!("{} price must be zero or positive", name));
+            }
+        }
+        for budget in [self.daily_budget, self.monthly_budget].into_iter().flatten() {
+            if !budget.is_finite() || budget <= 0.0 {
+                return Err("Budgets must be positive".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Audio one session sent to one provider on one day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageRecord {
+    pub session_id: String,
+    pub provider: UsageProvider,
+    /// Local date, `YYYY-MM-DD`
+    pub date: String,
+    /// Unix seconds of the last update
+    pub updated_at: i64,
+    pub audio_secs: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageLedger {
+    #[serde(default)]
+    pub records: Vec<UsageRecord>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsagePeriod {
+    Daily,
+    Monthly,
+}
+
+impl UsagePeriod {
+    /// Bucket a `YYYY-MM-DD` date falls into
+    fn key(self, date: &str) -> &str {
+        match self {
+            Self::Daily => date,
+            Self::Monthly => date.get(..7).unwrap_or(date),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UsageRow {
+    /// `YYYY-MM-DD` or `YYYY-MM`
+    pub period: String,
+    pub provider: UsageProvider,
+    pub audio_secs: f64,
+    pub sessions: usize,
+    pub estimated_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BudgetStatus {
+    pub today_cost: f64,
+    pub month_cost: f64,
+    pub daily_budget: Option<f64>,
+    pub monthly_budget: Option<f64>,
+    pub exceeded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UsageReport {
+    pub period: UsagePeriod,
+    pub rows: Vec<UsageRow>,
+    pub total_audio_secs: f64,
+    pub total_cost: f64,
+    pub budget: BudgetStatus,
+}
+
+impl UsageLedger {
+    pub fn load(app: &AppHandle) -> Self {
+        storage::load_json(app, LEDGER_FILE)
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, LEDGER_FILE, self)
+    }
+
+    /// Add audio to the session's record for `date`, dropping expired records
+    pub fn record(&mut self, provider: UsageProvider, session_id: &str, date: &str, now: i64, audio_secs: f64) {
+        self.records
+            .retain(|r| now - r.updated_at < RETENTION_DAYS * 24 * 60 * 60);
+
+        let existing = self
+            .records
+            .iter_mut()
+            .rev()
+            .find(|r| r.session_id == session_id && r.provider == provider && r.date == date);
+        match existing {
+            Some(record) => {
+                record.audio_secs += audio_secs;
+                record.updated_at = now;
+            }
+            None => self.records.push(UsageRecord {
+                session_id: session_id.to_string(),
+                provider,
+                date: date.to_string(),
+                updated_at: now,
+                audio_secs,
+            }),
+        }
+    }
+
+    /// Estimated spend on `today` and in its month
+    pub fn budget_status(&self, config: &UsageConfig, today: &str) -> BudgetStatus {
+        let month = UsagePeriod::Monthly.key(today);
+        let (mut today_cost, mut month_cost) = (0.0, 0.0);
+        for record in &self.records {
+            let cost = config.prices.cost(record.provider, record.audio_secs);
+            if record.date == today {
+                today_cost += cost;
+            }
+            if UsagePeriod::Monthly.key(&record.date) == month {
+                month_cost += cost;
+            }
+        }
+
+        BudgetStatus {
+            today_cost,
+            month_cost,
+            daily_budget: config.daily_budget,
+            monthly_budget: config.monthly_budget,
+            exceeded: config.daily_budget.is_some_and(|b| today_cost >= b)
+                || config.monthly_budget.is_some_and(|b| month_cost >= b),
+        }
+    }
+
+    /// Aggregate per period and provider, oldest first; `from`/`to` are
+    /// inclusive `YYYY-MM-DD` bounds
+    pub fn report(
+        &self,
+        config: &UsageConfig,
+        period: UsagePeriod,
+        from: Option<&str>,
+        to: Option<&str>,
+        today: &str,
+    ) -> UsageReport {
+        let mut buckets: BTreeMap<(String, UsageProvider), (f64, HashSet<&str>)> = BTreeMap::new();
+        for record in &self.records {
+            let date = record.date.as_str();
+            if from.is_some_and(|f| date < f) || to.is_some_and(|t| date > t) {
+                continue;
+            }
+            let bucket = buckets
+                .entry((period.key(date).to_string(), record.provider))
+                .or_default();
+            bucket.0 += record.audio_secs;
+            bucket.1.insert(&record.session_id);
+        }
+
+        let rows: Vec<UsageRow> = buckets
+            .into_iter()
+            .map(|((period, provider), (audio_secs, sessions))| UsageRow {
+                period,
+                provider,
+                audio_secs,
+                sessions: sessions.len(),
+                estimated_cost: config.prices.cost(provider, audio_secs),
+            })
+            .collect();
+
+        UsageReport {
+            period,
+            total_audio_secs: rows.iter().map(|r| r.audio_secs).sum(),
+            total_cost: rows.iter().map(|r| r.estimated_cost).sum(),
+            rows,
+            budget: self.budget_status(config, today),
+        }
+    }
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// Add `audio_secs` of cloud audio to the ledger.
+///
+/// Emits `usage-budget-exceeded` when this pushes spend over a budget.
+pub fn record_usage(app: &AppHandle, provider: UsageProvider, session_id: &str, audio_secs: f64) {
+    if audio_secs <= 0.0 {
+        return;
+    }
+    let config = UsageConfig::load(app);
+    let today = today();
+
+    let status = {
+        let _guard = LEDGER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut ledger = UsageLedger::load(app);
+        let was_exceeded = ledger.budget_status(&config, &today).exceeded;
+        ledger.record(provider, session_id, &today, chrono::Utc::now().timestamp(), audio_secs);
+        if let Err(e) = ledger.save(app) {
+            tracing::warn!("Failed to save usage ledger: {}", e);
+        }
+        let status = ledger.budget_status(&config, &today);
+        (!was_exceeded && status.exceeded).then_some(status)
+    };
+
+    if let Some(status) = status {
+        tracing::warn!(
+            "Transcription budget exceeded: ${:.2} today, ${:.2} this month",
+            status.today_cost,
+            status.month_cost
+        );
+        let _ = app.emit("usage-budget-exceeded", status);
+    }
+}
+
+/// Refuse to start a cloud stream while a budget is used up
+pub fn ensure_within_budget(app: &AppHandle) -> Result<(), String> {
+    let config = UsageConfig::load(app);
+    if !config.block_when_exceeded {
+        return Ok(());
+    }
+
+    let status = {
+        let _guard = LEDGER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        UsageLedger::load(app).budget_status(&config, &today())
+    };
+    if status.exceeded {
+        return Err(format!(
+            "Transcription budget exceeded (${:.2} today, ${:.2} this month); cloud streams are blocked",
+            status.today_cost, status.month_cost
+        ));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_usage_config(app: AppHandle) -> Result<UsageConfig, String> {
+    Ok(UsageConfig::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_usage_config(app: AppHandle, config: UsageConfig) -> Result<(), String> {
+    config.validate()?;
+    config.save(&app)?;
+    tracing::info!("Usage budgets updated: {:?}", config);
+    Ok(())
+}
+
+/// Audio minutes and estimated cost per day or month and provider
+#[tauri::command]
+pub async fn get_usage_report(
+    app: AppHandle,
+    period: UsagePeriod,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<UsageReport, String> {
+    let config = UsageConfig::load(&app);
+    let _guard = LEDGER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(UsageLedger::load(&app).report(&config, period, from.as_deref(), to.as_deref(), &today()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn ledger() -> UsageLedger {
+        let mut ledger = UsageLedger::default();
+        ledger.record(UsageProvider::Deepgram, "s1", "2026-09-30", 0, 600.0);
+        ledger.record(UsageProvider::Deepgram, "s2", "2026-10-01", DAY, 300.0);
+        ledger.record(UsageProvider::Deepgram, "s2", "2026-10-01", DAY, 300.0);
+        ledger.record(UsageProvider::AssemblyAi, "job1", "2026-10-01", DAY, 1200.0);
+        ledger.record(UsageProvider::Deepgram, "s3", "2026-10-02", 2 * DAY, 60.0);
+        ledger
+    }
+
+    #[test]
+    fn test_record_merges_session_updates() {
+        let ledger = ledger();
+        assert_eq!(ledger.records.len(), 4);
+        let s2 = ledger.records.iter().find(|r| r.session_id == "s2").unwrap();
+        assert_eq!(s2.audio_secs, 600.0);
+    }
+
+    #[test]
+    fn test_old_records_are_dropped() {
+        let mut ledger = ledger();
+        ledger.record(UsageProvider::Deepgram, "s9", "2027-11-01", RETENTION_DAYS * DAY + DAY / 2, 1.0);
+        assert!(ledger.records.iter().all(|r| r.session_id != "s1"));
+        assert!(ledger.records.iter().any(|r| r.session_id == "s2"));
+    }
+
+    #[test]
+    fn test_daily_and_monthly_report() {
+        let config = UsageConfig::default();
+        let daily = ledger().report(&config, UsagePeriod::Daily, Some("2026-10-01"), None, "2026-10-02");
+        assert_eq!(daily.rows.len(), 3);
+        assert_eq!(daily.rows[0].period, "2026-10-01");
+        assert_eq!(daily.rows[0].provider, UsageProvider::Deepgram);
+        assert_eq!(daily.rows[0].sessions, 1);
+        assert!((daily.rows[0].estimated_cost - 10.0 * 0.0077).abs() < 1e-9);
+        assert_eq!(daily.total_audio_secs, 600.0 + 1200.0 + 60.0);
+
+        let monthly = ledger().report(&config, UsagePeriod::Monthly, None, None, "2026-10-02");
+        let periods: Vec<(&str, UsageProvider, usize)> = monthly
+            .rows
+            .iter()
+            .map(|r| (r.period.as_str(), r.provider, r.sessions))
+            .collect();
+        assert_eq!(
+            periods,
+            vec![
+                ("2026-09", UsageProvider::Deepgram, 1),
+                ("2026-10", UsageProvider::Deepgram, 2),
+                ("2026-10", UsageProvider::AssemblyAi, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_budget_status() {
+        let unlimited = ledger().budget_status(&UsageConfig::default(), "2026-10-01");
+        assert!(!unlimited.exceeded);
+        // 10 min Deepgram + 20 min AssemblyAI
+        assert!((unlimited.today_cost - (10.0 * 0.0077 + 20.0 * 0.002)).abs() < 1e-9);
+
+        let monthly = UsageConfig { monthly_budget: Some(0.1), ..Default::default() };
+        assert!(ledger().budget_status(&monthly, "2026-10-02").exceeded);
+
+        let daily = UsageConfig { daily_budget: Some(0.1), ..Default::default() };
+        assert!(!ledger().budget_status(&daily, "2026-10-02").exceeded);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(UsageConfig::default().validate().is_ok());
+        let negative = UsageConfig {
+            prices: PriceTable { deepgram: -1.0, ..Default::default() },
+            ..Default::default()
+        };
+        assert!(negative.validate().is_err());
+        assert!(UsageConfig { daily_budget: Some(0.0), ..Default::default() }.validate().is_err());
+
+        let parsed: UsageConfig = serde_json::from_str(r#"{"monthly_budget": 20}"#).unwrap();
+        assert_eq!(parsed.monthly_budget, Some(20.0));
+        assert!(parsed.block_when_exceeded);
+    }
+}
@@ -0,0 +1,311 @@
+//! Custom vocabulary: product names, colleagues' names and jargon the
+//! recognizers should prefer, plus replacements for the ways they still get
+//! them wrong.
+//!
+//! The same term list is translated per provider: Deepgram `keywords` /
+//! `keyterm`, AssemblyAI `word_boost` and a glossary appended to the Whisper
+//! initial prompt. Final transcripts then go through a find-and-replace pass.
+
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tauri::AppHandle;
+
+use crate::storage;
+
+const VOCABULARY_FILE: &str = "vocabulary.json";
+
+const MAX_TERMS: usize = 500;
+const MAX_TERM_CHARS: usize = 100;
+/// Deepgram accepts at most this many keywords / keyterms per request
+const DEEPGRAM_MAX_TERMS: usize = 100;
+/// AssemblyAI ignores boost phrases longer than this many words
+const ASSEMBLYAI_MAX_PHRASE_WORDS: usize = 6;
+/// Whisper's prompt window is ~224 tokens; keep the glossary well inside it
+const WHISPER_PROMPT_MAX_CHARS: usize = 600;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VocabularyTerm {
+    pub term: String,
+    /// Recognition boost (Deepgram keyword intensifier, -10.0 to 10.0);
+    /// `None` uses the provider default
+    #[serde(default)]
+    pub boost: Option<f32>,
+    /// Misrecognitions replaced by `term` in final transcripts
+    #[serde(default)]
+    pub replacements: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Vocabulary {
+    #[serde(default)]
+    pub terms: Vec<VocabularyTerm>,
+}
+
+impl Vocabulary {
+    pub fn load(app: &AppHandle) -> Self {
+        let vocabulary: Self = storage::load_json(app, VOCABULARY_FILE);
+        if vocabulary.validate().is_ok() {
+            vocabulary
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, VOCABULARY_FILE, self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.terms.len() > MAX_TERMS {
+            return Err(format!("Vocabulary is limited to {} terms", MAX_TERMS));
+        }
+
+        let mut seen = HashSet::new();
+        for entry in &self.terms {
+            let term = entry.term.trim();
+            if term.is_empty() {
+                return Err("Vocabulary terms must not be empty".to_string());
+            }
+            if term.chars().count() > MAX_TERM_CHARS {
+                return Err(format!("Term is too long (max {} characters): {}", MAX_TERM_CHARS, term));
+            }
+            // Terms end up in Whisper's prompt, which can't hold them
+            if entry.term.chars().any(char::is_control) {
+                return Err(format!("Term must not contain control characters: {:?}", term));
+            }
+            if !seen.insert(term.to_lowercase()) {
+                return Err(format!("Duplicate vocabulary term: {}", term));
+            }
+            if let Some(boost) = entry.boost {
+                if !(-10.0..=10.0).contains(&boost) {
+                    return Err(format!("Boost for '{}' must be between -10 and 10", term));
+                }
+            }
+            if entry.replacements.iter().any(|r| r.trim().is_empty()) {
+                return Err(format!("Replacements for '{}' must not be empty", term));
+            }
+            if entry.replacements.iter().any(|r| r.chars().any(char::is_control)) {
+                return Err(format!("Replacements for '{}' must not contain control characters", term));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Query parameters for the Deepgram live API.
+    ///
+    /// Nova-3 models take plain `keyterm` prompts; older models take
+    /// `keywords` with an optional `:intensifier`.
+    pub fn deepgram_params(&self, model: &str) -> Vec<(&'static str, String)> {
+        let terms = self.terms.iter().take(DEEPGRAM_MAX_TERMS);
+
+        if model.starts_with("nova-3") {
+            return terms.map(|t| ("keyterm", t.term.trim().to_string())).collect();
+        }
+
+        terms
+            .map(|t| {
+                let value = match t.boost {
+                    Some(boost) => format!("{}:{}", t.term.trim(), boost),
+                    None => t.term.trim().to_string(),
+                };
+                ("keywords", value)
+            })
+            .collect()
+    }
+
+    /// Phrases for AssemblyAI `word_boost`; negative boosts are skipped
+    pub fn assemblyai_word_boost(&self) -> Vec<String> {
+        self.terms
+            .iter()
+            .filter(|t| t.boost.is_none_or(|b| b > 0.0))
+            .map(|t| t.term.trim().to_string())
+            .filter(|t| t.split_whitespace().count() <= ASSEMBLYAI_MAX_PHRASE_WORDS)
+            .collect()
+    }
+
+    /// Whisper initial prompt: the user's prompt followed by a glossary
+    pub fn whisper_prompt(&self, base: Option<&str>) -> Option<String> {
+        let base = base.map(str::trim).filter(|b| !b.is_empty());
+
+        let mut glossary = String::new();
+        for entry in self.terms.iter().filter(|t| t.boost.is_none_or(|b| b > 0.0)) {
+            let term = entry.term.trim();
+            if glossary.len() + term.len() + 2 > WHISPER_PROMPT_MAX_CHARS {
+                break;
+            }
+            if !glossary.is_empty() {
+                glossary.push_str(", ");
+            }
+            glossary.push_str(term);
+        }
+
+        match (base, glossary.is_empty()) {
+            (base, true) => base.map(String::from),
+            (None, false) => Some(format!("Glossary: {}.", glossary)),
+            (Some(base), false) => Some(format!("{} Glossary: {}.", base, glossary)),
+        }
+    }
+
+    /// Compile the find-and-replace rules for final transcripts
+    pub fn replacer(&self) -> Replacer {
+        let rules = self
+            .terms
+            .iter()
+            .filter_map(|entry| {
+                let mut variants: Vec<&str> = entry
+                    .replacements
+                    .iter()
+                    .map(|r| r.trim())
+                    .filter(|r| !r.is_empty())
+                    .collect();
+                if variants.is_empty() {
+                    return None;
+                }
+                // Prefer the longest match when variants overlap
+                variants.sort_by_key(|v| std::cmp::Reverse(v.len()));
+
+                let alternatives: Vec<String> = variants.iter().map(|v| word_pattern(v)).collect();
+                let pattern = format!("(?i)(?:{})", alternatives.join("|"));
+                match Regex::new(&pattern) {
+                    Ok(regex) => Some((regex, entry.term.trim().to_string())),
+                    Err(e) => {
+                        tracing::warn!("Skipping replacements for '{}': {}", entry.term, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Replacer { rules }
+    }
+}
+
+/// Match `text` literally, anchored on word boundaries where it starts/ends with a word character
+fn word_pattern(text: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    format!(
+        "{}{}{}",
+        if is_word(text.chars().next()) { r"\b" } else { "" },
+        regex::escape(text),
+        if is_word(text.chars().last()) { r"\b" } else { "" },
+    )
+}
+
+/// Compiled replacement rules, built once per session
+#[derive(Debug, Clone, Default)]
+pub struct Replacer {
+    rules: Vec<(Regex, String)>,
+}
+
+impl Replacer {
+    pub fn apply(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for (regex, term) in &self.rules {
+            if regex.is_match(&result) {
+                result = regex.replace_all(&result, NoExpand(term)).into_owned();
+            }
+        }
+        result
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_vocabulary(app: AppHandle) -> Result<Vocabulary, String> {
+    Ok(Vocabulary::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_vocabulary(app: AppHandle, vocabulary: Vocabulary) -> Result<(), String> {
+    vocabulary.validate()?;
+    vocabulary.save(&app)?;
+    tracing::info!("Custom vocabulary updated: {} terms", vocabulary.terms.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(term: &str, boost: Option<f32>, replacements: &[&str]) -> VocabularyTerm {
+        VocabularyTerm {
+            term: term.to_string(),
+            boost,
+            replacements: replacements.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    fn vocabulary() -> Vocabulary {
+        Vocabulary {
+            terms: vec![
+                term("Aurus", Some(2.0), &["Taurus"]),
+                term("Kubernetes", None, &["cooper netties", "kuber nettis"]),
+                term("C++", None, &["C plus plus"]),
+                term("the", Some(-5.0), &[]),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(vocabulary().validate().is_ok());
+        assert!(Vocabulary { terms: vec![term("  ", None, &[])] }.validate().is_err());
+        assert!(Vocabulary { terms: vec![term("a", Some(11.0), &[])] }.validate().is_err());
+        assert!(Vocabulary { terms: vec![term("a", None, &[" "])] }.validate().is_err());
+        assert!(Vocabulary { terms: vec![term("Au\0rus", None, &[])] }.validate().is_err());
+        assert!(Vocabulary { terms: vec![term("Aurus", None, &["Au\nrus"])] }.validate().is_err());
+        assert!(Vocabulary { terms: vec![term("Aurus", None, &[]), term("aurus", None, &[])] }
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_deepgram_params() {
+        let vocab = vocabulary();
+        let nova2 = vocab.deepgram_params("nova-2");
+        assert_eq!(nova2[0], ("keywords", "Aurus:2".to_string()));
+        assert_eq!(nova2[1], ("keywords", "Kubernetes".to_string()));
+        assert_eq!(nova2[3], ("keywords", "the:-5".to_string()));
+
+        let nova3 = vocab.deepgram_params("nova-3-general");
+        assert!(nova3.iter().all(|(key, _)| *key == "keyterm"));
+        assert_eq!(nova3[0].1, "Aurus");
+    }
+
+    #[test]
+    fn test_assemblyai_word_boost_and_whisper_prompt() {
+        let vocab = vocabulary();
+        assert_eq!(vocab.assemblyai_word_boost(), vec!["Aurus", "Kubernetes", "C++"]);
+        assert_eq!(
+            vocab.whisper_prompt(Some("Team standup.")).as_deref(),
+            Some("Team standup. Glossary: Aurus, Kubernetes, C++.")
+        );
+        assert_eq!(
+            vocab.whisper_prompt(None).as_deref(),
+            Some("Glossary: Aurus, Kubernetes, C++.")
+        );
+        assert_eq!(
+            Vocabulary::default().whisper_prompt(Some("Hi")).as_deref(),
+            Some("Hi")
+        );
+        assert_eq!(Vocabulary::default().whisper_prompt(Some(" ")), None);
+    }
+
+    #[test]
+    fn test_replacements() {
+        let replacer = vocabulary().replacer();
+        assert_eq!(
+            replacer.apply("We deployed taurus to cooper netties today."),
+            "We deployed Aurus to Kubernetes today."
+        );
+        assert_eq!(replacer.apply("Written in c plus plus!"), "Written in C++!");
+        // Whole words only
+        assert_eq!(replacer.apply("Centaurus is a constellation"), "Centaurus is a constellation");
+        assert_eq!(Replacer::default().apply("unchanged"), "unchanged");
+    }
+}
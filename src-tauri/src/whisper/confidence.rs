@@ -0,0 +1,180 @@
+//! Confidence scoring and hallucination suppression for Whisper output.
+//!
+//! whisper.cpp already drops windows whose no-speech probability exceeds
+//! `no_speech_thold` while the decode's average log-probability stays below
+//! `logprob_thold`. On near-silent input it still tends to "hear" stock
+//! phrases ("Thank you.", "[BLANK_AUDIO]") that slip past that check, so
+//! segments are additionally filtered by audio energy and token probability.
+
+use crate::transcription::ScoredWord;
+
+/// Audio quieter than this RMS (about -50 dBFS) is treated as silence
+pub const SILENCE_RMS: f32 = 0.003;
+
+/// Known hallucination phrases are only dropped below this confidence
+const HALLUCINATION_MAX_CONFIDENCE: f32 = 0.6;
+
+/// Phrases Whisper commonly produces for silence or background noise
+const COMMON_HALLUCINATIONS: &[&str] = &[
+    "thank you",
+    "thank you very much",
+    "thanks for watching",
+    "thank you for watching",
+    "please subscribe",
+    "subtitles by the amara org community",
+    "you",
+    "bye",
+    "vielen dank",
+    "untertitel im auftrag des zdf",
+];
+
+/// A decoded text token with its probability and timing
+#[derive(Debug, Clone)]
+pub struct TokenInfo {
+    pub text: String,
+    pub probability: f32,
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+/// Root-mean-square level of normalized samples
+pub fn rms(audio: &[f32]) -> f32 {
+    if audio.is_empty() {
+        return 0.0;
+    }
+    (audio.iter().map(|s| s * s).sum::<f32>() / audio.len() as f32).sqrt()
+}
+
+pub fn is_silent(audio: &[f32]) -> bool {
+    rms(audio) < SILENCE_RMS
+}
+
+/// Mean token probability, used as the segment confidence
+pub fn mean_probability(tokens: &[TokenInfo]) -> f32 {
+    if tokens.is_empty() {
+        return 0.0;
+    }
+    tokens.iter().map(|t| t.probability).sum::<f32>() / tokens.len() as f32
+}
+
+/// Group sub-word tokens into words; a leading space starts a new word.
+///
+/// A word is only as certain as its least certain token.
+pub fn words_from_tokens(tokens: &[TokenInfo]) -> Vec<ScoredWord> {
+    let mut words: Vec<ScoredWord> = Vec::new();
+
+    for token in tokens {
+        let starts_word = token.text.starts_with(' ');
+        let piece = token.text.trim();
+        if piece.is_empty() {
+            continue;
+        }
+
+        match words.last_mut() {
+            Some(word) if !starts_word => {
+                word.text.push_str(piece);
+                word.end_ms = token.end_ms;
+                word.confidence = word.confidence.min(token.probability);
+            }
+            _ => words.push(ScoredWord {
+                text: piece.to_string(),
+                start_ms: token.start_ms,
+                end_ms: token.end_ms,
+                confidence: token.probability,
+            }),
+        }
+    }
+
+    words
+}
+
+/// Annotations like "[BLANK_AUDIO]", "(music)" or "♪♪" that aren't speech
+pub fn is_non_speech_marker(text: &str) -> bool {
+    let text = text.trim();
+    if text.is_empty() {
+        return true;
+    }
+    (text.starts_with('[') && text.ends_with(']'))
+        || (text.starts_with('(') && text.ends_with(')'))
+        || (text.starts_with('*') && text.ends_with('*'))
+        || text.chars().all(|c| matches!(c, '♪' | '♫' | ' ' | '.'))
+}
+
+fn is_common_hallucination(text: &str) -> bool {
+    let normalized = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    COMMON_HALLUCINATIONS.contains(&normalized.as_str())
+}
+
+/// Decide whether a decoded segment is most likely not real speech
+pub fn should_suppress(text: &str, confidence: f32, segment_audio: &[f32]) -> bool {
+    if is_non_speech_marker(text) {
+        return true;
+    }
+    if is_silent(segment_audio) {
+        return true;
+    }
+    confidence < HALLUCINATION_MAX_CONFIDENCE && is_common_hallucination(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, probability: f32, start_ms: i64) -> TokenInfo {
+        TokenInfo {
+            text: text.to_string(),
+            probability,
+            start_ms,
+            end_ms: start_ms + 100,
+        }
+    }
+
+    #[test]
+    fn test_words_from_tokens() {
+        let tokens = [
+            token(" Hello", 0.9, 0),
+            token(" Kub", 0.7, 100),
+            token("ernetes", 0.4, 200),
+            token(".", 0.95, 300),
+        ];
+        let words = words_from_tokens(&tokens);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!(words[1].text, "Kubernetes.");
+        assert_eq!(words[1].start_ms, 100);
+        assert_eq!(words[1].end_ms, 400);
+        assert!((words[1].confidence - 0.4).abs() < f32::EPSILON);
+        assert!((mean_probability(&tokens) - 0.7375).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_silence_detection() {
+        assert!(is_silent(&[]));
+        assert!(is_silent(&vec![0.001; 1600]));
+        assert!(!is_silent(&vec![0.1; 1600]));
+    }
+
+    #[test]
+    fn test_should_suppress() {
+        let speech = vec![0.1; 1600];
+        let silence = vec![0.0; 1600];
+
+        assert!(should_suppress("[BLANK_AUDIO]", 0.9, &speech));
+        assert!(should_suppress("(music)", 0.9, &speech));
+        assert!(should_suppress("♪♪", 0.9, &speech));
+        assert!(should_suppress("Meeting notes", 0.9, &silence));
+        assert!(should_suppress("Thank you.", 0.3, &speech));
+        assert!(should_suppress("Thanks for watching!", 0.5, &speech));
+
+        // Confident, audible speech is kept even if it's a stock phrase
+        assert!(!should_suppress("Thank you.", 0.9, &speech));
+        assert!(!should_suppress("Thank you for the update", 0.3, &speech));
+    }
+}
@@ -0,0 +1,465 @@
+//! Local Whisper inference (desktop only).
+//!
+//! The `WhisperContext` is loaded once and kept in managed state, so the model
+//! is read from disk a single time per app session. Both the one-shot
+//! `transcribe_local_whisper` command and the live sliding-window stream
+//! create their (cheap) inference state from that shared context.
+//! Load time and real-time factor of every pass are tracked in
+//! [`WhisperMetrics`].
+
+pub mod confidence;
+pub mod models;
+pub mod settings;
+pub mod streaming;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use whisper_rs::{WhisperContext, WhisperContextParameters, WhisperState};
+
+use crate::platform::TARGET_SAMPLE_RATE;
+use crate::transcription::export::TimedSegment;
+use crate::transcription::vocabulary::Replacer;
+use crate::transcription::{low_confidence_spans, LowConfidenceSpan, ScoredWord};
+use confidence::TokenInfo;
+use settings::WhisperSettings;
+
+/// whisper.cpp returns no segments for input shorter than one second,
+/// so shorter buffers are padded with silence up to this length.
+const MIN_INPUT_SAMPLES: usize = TARGET_SAMPLE_RATE as usize * 1050 / 1000;
+
+/// A transcribed segment, with timestamps relative to the start of the input audio
+#[derive(Debug, Clone)]
+pub struct WhisperSegment {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Mean probability of the segment's text tokens
+    pub confidence: f32,
+    pub words: Vec<ScoredWord>,
+}
+
+/// Timing of a single inference pass
+#[derive(Debug, Clone, Serialize)]
+pub struct InferenceMetrics {
+    pub audio_ms: u64,
+    pub inference_ms: u64,
+    /// Processing time divided by audio duration; below 1.0 is faster than real time
+    pub real_time_factor: f32,
+    pub threads: i32,
+}
+
+impl InferenceMetrics {
+    fn new(audio_samples: usize, elapsed: Duration, threads: i32) -> Self {
+        let audio_ms = (audio_samples as u64 * 1000) / TARGET_SAMPLE_RATE as u64;
+        let inference_ms = elapsed.as_millis() as u64;
+        Self {
+            audio_ms,
+            inference_ms,
+            real_time_factor: if audio_ms > 0 {
+                inference_ms as f32 / audio_ms as f32
+            } else {
+                0.0
+            },
+            threads,
+        }
+    }
+}
+
+/// Result of one inference pass
+#[derive(Debug, Clone)]
+pub struct InferenceOutput {
+    pub segments: Vec<WhisperSegment>,
+    /// `None` when the audio was silent and the model was not run
+    pub metrics: Option<InferenceMetrics>,
+    /// Segments dropped as probable hallucinations
+    pub suppressed_segments: usize,
+    /// Language the pass was decoded in (ISO 639-1), detected or pinned
+    pub language: Option<String>,
+}
+
+/// Accumulated performance numbers for the loaded model
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WhisperMetrics {
+    pub model_id: Option<String>,
+    pub model_load_ms: Option<u64>,
+    pub passes: u64,
+    pub total_audio_ms: u64,
+    pub total_inference_ms: u64,
+    pub average_real_time_factor: Option<f32>,
+    pub last_pass: Option<InferenceMetrics>,
+}
+
+impl WhisperMetrics {
+    pub fn record(&mut self, metrics: &InferenceMetrics) {
+        self.passes += 1;
+        self.total_audio_ms += metrics.audio_ms;
+        self.total_inference_ms += metrics.inference_ms;
+        if self.total_audio_ms > 0 {
+            self.average_real_time_factor =
+                Some(self.total_inference_ms as f32 / self.total_audio_ms as f32);
+        }
+        self.last_pass = Some(metrics.clone());
+    }
+}
+
+/// Shared handle so inference tasks can record metrics without the engine lock
+pub type MetricsHandle = Arc<StdMutex<WhisperMetrics>>;
+
+/// Long-lived Whisper model, loaded lazily on first use
+#[derive(Default)]
+pub struct WhisperEngine {
+    context: Option<Arc<WhisperContext>>,
+    loaded_model: Option<String>,
+    metrics: MetricsHandle,
+}
+
+pub type WhisperManager = Arc<Mutex<WhisperEngine>>;
+
+/// Serializes model loads, so concurrent callers wait for one download
+/// instead of racing for it, without holding the engine lock meanwhile
+static LOADING: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Context of the active model and the metrics handle, downloading and
+/// loading the model if needed. The engine is only locked to look up and
+/// install the context, so a multi-GB download doesn't block other commands.
+pub async fn load_context(
+    app: &AppHandle,
+    whisper: &WhisperManager,
+) -> Result<(Arc<WhisperContext>, MetricsHandle), String> {
+    let active_model = models::load_manifest(app)?.active_model_id().to_string();
+
+    if let Some(loaded) = whisper.lock().await.cached(&active_model) {
+        return Ok(loaded);
+    }
+
+    let _loading = LOADING.lock().await;
+    // Another caller may have loaded it while we waited
+    if let Some(loaded) = whisper.lock().await.cached(&active_model) {
+        return Ok(loaded);
+    }
+
+    let model_path = models::ensure_model(app, &active_model).await?;
+
+    let load_started = Instant::now();
+    let ctx = tokio::task::spawn_blocking(move || {
+        WhisperContext::new_with_params(
+            model_path.to_str().ok_or("Invalid model path")?,
+            WhisperContextParameters::default(),
+        )
+        .map_err(|e| format!("Failed to create Whisper context: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+    let load_ms = load_started.elapsed().as_millis() as u64;
+
+    let ctx = Arc::new(ctx);
+    let mut engine = whisper.lock().await;
+    engine.install(&active_model, ctx.clone(), load_ms);
+    tracing::info!("Whisper model {} loaded into memory in {}ms", active_model, load_ms);
+    Ok((ctx, engine.metrics()))
+}
+
+impl WhisperEngine {
+    /// The loaded context, if it is `model_id`'s
+    fn cached(&self, model_id: &str) -> Option<(Arc<WhisperContext>, MetricsHandle)> {
+        match &self.context {
+            Some(ctx) if self.loaded_model.as_deref() == Some(model_id) => Some((ctx.clone(), self.metrics())),
+            _ => None,
+        }
+    }
+
+    /// Hold `ctx` as the loaded model, resetting the metrics
+    fn install(&mut self, model_id: &str, ctx: Arc<WhisperContext>, load_ms: u64) {
+        self.context = Some(ctx);
+        self.loaded_model = Some(model_id.to_string());
+        if let Ok(mut metrics) = self.metrics.lock() {
+            *metrics = WhisperMetrics {
+                model_id: Some(model_id.to_string()),
+                model_load_ms: Some(load_ms),
+                ..Default::default()
+            };
+        }
+    }
+
+    pub fn metrics(&self) -> MetricsHandle {
+        self.metrics.clone()
+    }
+
+    /// Id of the model currently held in memory
+    pub fn loaded_model(&self) -> Option<&str> {
+        self.loaded_model.as_deref()
+    }
+
+    /// Drop the loaded model; running inference keeps its own reference
+    pub fn unload(&mut self) {
+        self.context = None;
+        self.loaded_model = None;
+    }
+}
+
+/// Language to decode with: English-only models are pinned to "en",
+/// multilingual models let whisper.cpp detect the spoken language.
+pub fn default_language(ctx: &WhisperContext) -> &'static str {
+    if ctx.is_multilingual() {
+        "auto"
+    } else {
+        "en"
+    }
+}
+
+/// Convert i16 PCM samples to f32 (normalized to -1.0 to 1.0)
+pub fn convert_i16_to_f32(samples: &[i16]) -> Vec<f32> {
+    samples
+        .iter()
+        .map(|&s| s as f32 / i16::MAX as f32)
+        .collect()
+}
+
+/// Run a full Whisper pass over 16kHz mono audio.
+///
+/// Blocking — call from `spawn_blocking`.
+pub fn run_inference(
+    ctx: &WhisperContext,
+    state: &mut WhisperState,
+    audio: &[f32],
+    settings: &WhisperSettings,
+) -> Result<InferenceOutput, String> {
+    // Silence is where Whisper hallucinates; don't even run the model
+    if confidence::is_silent(audio) {
+        return Ok(InferenceOutput {
+            segments: Vec::new(),
+            metrics: None,
+            suppressed_segments: 0,
+            language: None,
+        });
+    }
+
+    let language = match settings.language.as_deref() {
+        Some(language) if ctx.is_multilingual() => language,
+        _ => default_language(ctx),
+    };
+    let mut params = settings.full_params(language);
+
+    // Multi-segment output, nothing printed to stdout
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_single_segment(false);
+    params.set_no_context(true);
+    params.set_token_timestamps(true);
+
+    let mut input = Cow::Borrowed(audio);
+    if input.len() < MIN_INPUT_SAMPLES {
+        input.to_mut().resize(MIN_INPUT_SAMPLES, 0.0);
+    }
+
+    let started = Instant::now();
+    state
+        .full(params, &input)
+        .map_err(|e| format!("Whisper inference failed: {}", e))?;
+    let metrics = InferenceMetrics::new(audio.len(), started.elapsed(), settings.effective_threads());
+    let language = state
+        .full_lang_id_from_state()
+        .ok()
+        .and_then(whisper_rs::get_lang_str)
+        .map(String::from);
+
+    let num_segments = state
+        .full_n_segments()
+        .map_err(|e| format!("Failed to get segments: {}", e))?;
+
+    // Timestamp and control tokens all have ids from end-of-text upwards
+    let first_special_token = ctx.token_eot();
+
+    let mut segments = Vec::with_capacity(num_segments.max(0) as usize);
+    let mut suppressed_segments = 0;
+    for i in 0..num_segments {
+        let Ok(text) = state.full_get_segment_text(i) else {
+            continue;
+        };
+        let text = text.trim().to_string();
+        // Segment and token timestamps are reported in 10ms units
+        let start_ms = state.full_get_segment_t0(i).unwrap_or(0) * 10;
+        let end_ms = state.full_get_segment_t1(i).unwrap_or(0) * 10;
+
+        let mut tokens = Vec::new();
+        for j in 0..state.full_n_tokens(i).unwrap_or(0) {
+            let Ok(data) = state.full_get_token_data(i, j) else {
+                continue;
+            };
+            if data.id >= first_special_token {
+                continue;
+            }
+            let Ok(token_text) = state.full_get_token_text_lossy(i, j) else {
+                continue;
+            };
+            tokens.push(TokenInfo {
+                text: token_text,
+                probability: data.p,
+                start_ms: data.t0 * 10,
+                end_ms: data.t1 * 10,
+            });
+        }
+
+        let confidence = confidence::mean_probability(&tokens);
+        if confidence::should_suppress(&text, confidence, segment_audio(&input, start_ms, end_ms)) {
+            tracing::debug!("Suppressed probable hallucination: {:?} ({:.2})", text, confidence);
+            suppressed_segments += 1;
+            continue;
+        }
+
+        segments.push(WhisperSegment {
+            text,
+            start_ms,
+            end_ms,
+            confidence,
+            words: confidence::words_from_tokens(&tokens),
+        });
+    }
+
+    tracing::debug!(
+        "Whisper pass: {}ms audio in {}ms (RTF {:.2})",
+        metrics.audio_ms,
+        metrics.inference_ms,
+        metrics.real_time_factor
+    );
+
+    Ok(InferenceOutput {
+        segments,
+        metrics: Some(metrics),
+        suppressed_segments,
+        language,
+    })
+}
+
+/// Samples covered by a segment's timestamps; the whole input if they
+/// don't describe a usable range
+fn segment_audio(audio: &[f32], start_ms: i64, end_ms: i64) -> &[f32] {
+    let to_index = |ms: i64| {
+        ((ms.max(0) as usize) * TARGET_SAMPLE_RATE as usize / 1000).min(audio.len())
+    };
+    let (start, end) = (to_index(start_ms), to_index(end_ms));
+    if start < end {
+        &audio[start..end]
+    } else {
+        audio
+    }
+}
+
+/// Overall confidence of a transcript, weighting each segment by its text length
+pub fn transcript_confidence(segments: &[WhisperSegment]) -> f32 {
+    let total_chars: usize = segments.iter().map(|s| s.text.len()).sum();
+    if total_chars == 0 {
+        return 0.0;
+    }
+    segments
+        .iter()
+        .map(|s| s.confidence * s.text.len() as f32)
+        .sum::<f32>()
+        / total_chars as f32
+}
+
+/// Low-confidence spans across all segments
+pub fn segment_spans(segments: &[WhisperSegment]) -> Vec<LowConfidenceSpan> {
+    let words: Vec<ScoredWord> = segments.iter().flat_map(|s| s.words.iter().cloned()).collect();
+    low_confidence_spans(&words)
+}
+
+/// Segments with their timings, for subtitle export; texts get the
+/// vocabulary replacements
+pub fn timed_segments(segments: &[WhisperSegment], language: Option<&str>, replacer: &Replacer) -> Vec<TimedSegment> {
+    segments
+        .iter()
+        .filter(|s| !s.text.is_empty())
+        .map(|s| TimedSegment {
+            start_ms: s.start_ms,
+            end_ms: s.end_ms,
+            text: replacer.apply(&s.text),
+            speaker: None,
+            confidence: Some(s.confidence),
+            language: language.map(String::from),
+        })
+        .collect()
+}
+
+/// Join segment texts into a single transcript string
+pub fn join_segments(segments: &[WhisperSegment]) -> String {
+    segments
+        .iter()
+        .map(|s| s.text.as_str())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Performance numbers (model load time, real-time factor) for the loaded model
+#[tauri::command]
+pub async fn get_whisper_metrics(
+    whisper: tauri::State<'_, WhisperManager>,
+) -> Result<WhisperMetrics, String> {
+    let metrics = whisper.lock().await.metrics();
+    let snapshot = metrics
+        .lock()
+        .map_err(|_| "Failed to lock Whisper metrics".to_string())?
+        .clone();
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inference_metrics_real_time_factor() {
+        // 2s of audio processed in 500ms
+        let metrics = InferenceMetrics::new(32000, Duration::from_millis(500), 4);
+        assert_eq!(metrics.audio_ms, 2000);
+        assert_eq!(metrics.inference_ms, 500);
+        assert!((metrics.real_time_factor - 0.25).abs() < f32::EPSILON);
+
+        let empty = InferenceMetrics::new(0, Duration::from_millis(10), 4);
+        assert_eq!(empty.real_time_factor, 0.0);
+    }
+
+    #[test]
+    fn test_metrics_accumulate() {
+        let mut totals = WhisperMetrics::default();
+        totals.record(&InferenceMetrics::new(32000, Duration::from_millis(1000), 4));
+        totals.record(&InferenceMetrics::new(32000, Duration::from_millis(3000), 4));
+        assert_eq!(totals.passes, 2);
+        assert_eq!(totals.total_audio_ms, 4000);
+        assert_eq!(totals.average_real_time_factor, Some(1.0));
+        assert_eq!(totals.last_pass.unwrap().inference_ms, 3000);
+    }
+
+    fn segment(text: &str, confidence: f32) -> WhisperSegment {
+        WhisperSegment {
+            text: text.to_string(),
+            start_ms: 0,
+            end_ms: 1000,
+            confidence,
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_transcript_confidence_weights_by_length() {
+        assert_eq!(transcript_confidence(&[]), 0.0);
+        let segments = [segment("abc", 1.0), segment("a", 0.0)];
+        assert!((transcript_confidence(&segments) - 0.75).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_segment_audio_bounds() {
+        let audio = vec![0.0; 16000];
+        assert_eq!(segment_audio(&audio, 250, 500).len(), 4000);
+        assert_eq!(segment_audio(&audio, 500, 5000).len(), 8000);
+        assert_eq!(segment_audio(&audio, 500, 500).len(), 16000);
+    }
+}
@@ -0,0 +1,795 @@
+//! Whisper model manager: catalog, download, verification and on-disk cache.
+//!
+//! Models live in `<app data>/whisper-models/` next to a small `manifest.json`
+//! that records the active model and the SHA-256 of every verified file.
+//! Expected checksums come from the Hugging Face LFS metadata of the
+//! `ggerganov/whisper.cpp` repository, so a download is only accepted once
+//! its hash matches what the server advertises.
+
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::AsyncWriteExt;
+
+use super::WhisperManager;
+
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+const MODEL_TREE_URL: &str = "https://huggingface.co/api/models/ggerganov/whisper.cpp/tree/main";
+
+const MODELS_DIR: &str = "whisper-models";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Model used when the user hasn't picked one
+pub const DEFAULT_MODEL_ID: &str = "base.en";
+
+/// Emit a progress event at most every this many bytes
+const PROGRESS_INTERVAL_BYTES: u64 = 1024 * 1024;
+
+/// ggml model files start with the magic 0x67676d6c (little-endian)
+const GGML_MAGIC: [u8; 4] = [0x6c, 0x6d, 0x67, 0x67];
+
+/// A model available for download
+#[derive(Debug, Clone, Serialize)]
+pub struct WhisperModelInfo {
+    pub id: &'static str,
+    pub file_name: &'static str,
+    pub approx_size_mb: u32,
+    pub multilingual: bool,
+    pub quantized: bool,
+    pub description: &'static str,
+}
+
+const fn model(
+    id: &'static str,
+    file_name: &'static str,
+    approx_size_mb: u32,
+    multilingual: bool,
+    quantized: bool,
+    description: &'static str,
+) -> WhisperModelInfo {
+    WhisperModelInfo { id, file_name, approx_size_mb, multilingual, quantized, description }
+}
+
+/// All models the manager knows how to fetch
+pub const MODEL_CATALOG: &[WhisperModelInfo] = &[
+    model("tiny.en", "ggml-tiny.en.bin", 75, false, false, "Fastest, English only"),
+    model("tiny", "ggml-tiny.bin", 75, true, false, "Fastest, multilingual"),
+    model("tiny.en-q5_1", "ggml-tiny.en-q5_1.bin", 31, false, true, "Fastest, English only, quantized"),
+    model("base.en", "ggml-base.en.bin", 142, false, false, "Balanced default, English only"),
+    model("base", "ggml-base.bin", 142, true, false, "Balanced, multilingual"),
+    model("base.en-q5_1", "ggml-base.en-q5_1.bin", 57, false, true, "Balanced, English only, quantized"),
+    model("base-q5_1", "ggml-base-q5_1.bin", 57, true, true, "Balanced, multilingual, quantized"),
+    model("small.en", "ggml-small.en.bin", 466, false, false, "Accurate, English only"),
+    model("small", "ggml-small.bin", 466, true, false, "Accurate, multilingual"),
+    model("small.en-q5_1", "ggml-small.en-q5_1.bin", 181, false, true, "Accurate, English only, quantized"),
+    model("small-q5_1", "ggml-small-q5_1.bin", 181, true, true, "Accurate, multilingual, quantized"),
+    model("medium.en", "ggml-medium.en.bin", 1500, false, false, "Very accurate, English only"),
+    model("medium", "ggml-medium.bin", 1500, true, false, "Very accurate, multilingual"),
+    model("medium.en-q5_0", "ggml-medium.en-q5_0.bin", 514, false, true, "Very accurate, English only, quantized"),
+    model("medium-q5_0", "ggml-medium-q5_0.bin", 514, true, true, "Very accurate, multilingual, quantized"),
+    model("large-v3-turbo", "ggml-large-v3-turbo.bin", 1500, true, false, "Best quality/speed trade-off, multilingual"),
+    model("large-v3-turbo-q5_0", "ggml-large-v3-turbo-q5_0.bin", 547, true, true, "Best quality/speed trade-off, quantized"),
+    model("large-v3", "ggml-large-v3.bin", 2900, true, false, "Highest quality, slowest"),
+];
+
+/// Look up a catalog entry by id
+pub fn find_model(model_id: &str) -> Result<&'static WhisperModelInfo, String> {
+    MODEL_CATALOG
+        .iter()
+        .find(|m| m.id == model_id)
+        .ok_or_else(|| format!("Unknown Whisper model: {}", model_id))
+}
+
+// ============================================================================
+// Manifest
+// ============================================================================
+
+/// Persisted model manager state
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModelManifest {
+    /// Model used for local transcription
+    pub active_model: Option<String>,
+    /// SHA-256 (hex) of each verified model file, keyed by file name
+    #[serde(default)]
+    pub verified: HashMap<String, String>,
+}
+
+impl ModelManifest {
+    pub fn active_model_id(&self) -> &str {
+        self.active_model.as_deref().unwrap_or(DEFAULT_MODEL_ID)
+    }
+
+    /// Deleting the active model or one being downloaded would only have it
+    /// downloaded again
+    fn check_deletable(&self, model_id: &str) -> Result<(), String> {
+        if self.active_model_id() == model_id {
+            return Err(format!(
+                "Model {} is the active model; switch to another model before deleting it",
+                model_id
+            ));
+        }
+        // The download would recreate or rename its .part file under us
+        if is_downloading(model_id) {
+            return Err(format!("Model {} is being downloaded; wait for it to finish", model_id));
+        }
+        Ok(())
+    }
+
+    fn load(dir: &Path) -> Self {
+        std::fs::read_to_string(dir.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, dir: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize model manifest: {}", e))?;
+        std::fs::write(dir.join(MANIFEST_FILE), json)
+            .map_err(|e| format!("Failed to write model manifest: {}", e))
+    }
+}
+
+/// Get (and create) the directory holding downloaded models.
+///
+/// Models downloaded before the manager existed sit directly in the app data
+/// dir; they are moved in here on first use so they don't get re-downloaded.
+pub fn models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+
+    let dir = app_data_dir.join(MODELS_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create models dir: {}", e))?;
+
+    let legacy = app_data_dir.join("ggml-base.en.bin");
+    if legacy.exists() {
+        let target = dir.join("ggml-base.en.bin");
+        if !target.exists() {
+            if let Err(e) = std::fs::rename(&legacy, &target) {
+                tracing::warn!("Failed to migrate legacy Whisper model: {}", e);
+            }
+        }
+    }
+
+    Ok(dir)
+}
+
+/// Read the manifest from the models directory
+pub fn load_manifest(app: &AppHandle) -> Result<ModelManifest, String> {
+    Ok(ModelManifest::load(&models_dir(app)?))
+}
+
+// ============================================================================
+// Verification
+// ============================================================================
+
+/// Compute the SHA-256 of a file as lowercase hex (blocking)
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buf = vec![0u8; 1024 * 1024];
+
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        context.update(&buf[..n]);
+    }
+
+    Ok(to_hex(context.finish().as_ref()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Check that a file looks like a ggml model before handing it to whisper.cpp
+fn has_ggml_magic(path: &Path) -> Result<bool, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut magic = [0u8; 4];
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == GGML_MAGIC),
+        Err(_) => Ok(false),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HfTreeEntry {
+    path: String,
+    lfs: Option<HfLfsInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HfLfsInfo {
+    oid: String,
+}
+
+/// Fetch the SHA-256 advertised by Hugging Face for a model file
+async fn fetch_expected_sha256(client: &reqwest::Client, file_name: &str) -> Result<String, String> {
+    let entries: Vec<HfTreeEntry> = client
+        .get(MODEL_TREE_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch model checksums: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse model checksums: {}", e))?;
+
+    expected_sha256_from_tree(&entries, file_name)
+        .ok_or_else(|| format!("No checksum published for {}", file_name))
+}
+
+fn expected_sha256_from_tree(entries: &[HfTreeEntry], file_name: &str) -> Option<String> {
+    entries
+        .iter()
+        .find(|e| e.path == file_name)
+        .and_then(|e| e.lfs.as_ref())
+        .map(|lfs| lfs.oid.to_lowercase())
+}
+
+async fn hash_in_background(path: PathBuf) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || sha256_file(&path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ============================================================================
+// Download
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelDownloadStatus {
+    Downloading,
+    Verifying,
+    Completed,
+    Failed,
+}
+
+/// Payload of the `whisper-model-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDownloadProgress {
+    pub model_id: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub status: ModelDownloadStatus,
+    pub error: Option<String>,
+}
+
+/// Models currently being downloaded (prevents two writers on one .part file)
+static ACTIVE_DOWNLOADS: Lazy<StdMutex<HashSet<String>>> = Lazy::new(|| StdMutex::new(HashSet::new()));
+
+struct DownloadGuard(String);
+
+impl DownloadGuard {
+    fn acquire(model_id: &str) -> Result<Self, String> {
+        let mut active = ACTIVE_DOWNLOADS
+            .lock()
+            .map_err(|_| "Failed to lock download registry")?;
+        if !active.insert(model_id.to_string()) {
+            return Err(format!("Model {} is already being downloaded", model_id));
+        }
+        Ok(Self(model_id.to_string()))
+    }
+}
+
+/// Whether `model_id` is being downloaded or imported right now
+fn is_downloading(model_id: &str) -> bool {
+    ACTIVE_DOWNLOADS
+        .lock()
+        .map(|active| active.contains(model_id))
+        .unwrap_or(true)
+}
+
+impl Drop for DownloadGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE_DOWNLOADS.lock() {
+            active.remove(&self.0);
+        }
+    }
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    model_id: &str,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    status: ModelDownloadStatus,
+    error: Option<String>,
+) {
+    let _ = app.emit(
+        "whisper-model-progress",
+        ModelDownloadProgress {
+            model_id: model_id.to_string(),
+            downloaded_bytes,
+            total_bytes,
+            status,
+            error,
+        },
+    );
+}
+
+/// Download, verify and install a catalog model; resumes a previous partial download
+pub async fn download_model(app: &AppHandle, model_id: &str) -> Result<PathBuf, String> {
+    let result = download_model_inner(app, model_id).await;
+    if let Err(e) = &result {
+        emit_progress(app, model_id, 0, None, ModelDownloadStatus::Failed, Some(e.clone()));
+    }
+    result
+}
+
+async fn download_model_inner(app: &AppHandle, model_id: &str) -> Result<PathBuf, String> {
+    let info = find_model(model_id)?;
+    let _guard = DownloadGuard::acquire(model_id)?;
+
+    let dir = models_dir(app)?;
+    let final_path = dir.join(info.file_name);
+    let part_path = dir.join(format!("{}.part", info.file_name));
+
+    let client = reqwest::Client::new();
+    let expected_sha256 = fetch_expected_sha256(&client, info.file_name).await?;
+
+    let downloaded = std::fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+    let mut request = client.get(format!("{}/{}", MODEL_BASE_URL, info.file_name));
+    if downloaded > 0 {
+        tracing::info!("Resuming Whisper model {} at {} bytes", model_id, downloaded);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", downloaded));
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download model: {}", e))?;
+
+    let (downloaded, total_bytes) =
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && downloaded > 0 {
+            // The .part file is already complete: a previous run exited after
+            // writing it but before installing it. Verification decides.
+            tracing::info!("Whisper model {} was fully downloaded before, verifying", model_id);
+            (downloaded, Some(downloaded))
+        } else {
+            write_download(app, model_id, response, &part_path, downloaded).await?
+        };
+
+    emit_progress(app, model_id, downloaded, total_bytes, ModelDownloadStatus::Verifying, None);
+
+    let actual_sha256 = hash_in_background(part_path.clone()).await?;
+    if actual_sha256 != expected_sha256 {
+        let _ = std::fs::remove_file(&part_path);
+        return Err(format!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            info.file_name, expected_sha256, actual_sha256
+        ));
+    }
+
+    std::fs::rename(&part_path, &final_path)
+        .map_err(|e| format!("Failed to install model: {}", e))?;
+
+    let mut manifest = ModelManifest::load(&dir);
+    manifest.verified.insert(info.file_name.to_string(), actual_sha256);
+    manifest.save(&dir)?;
+
+    emit_progress(app, model_id, downloaded, total_bytes, ModelDownloadStatus::Completed, None);
+    tracing::info!("Whisper model {} downloaded and verified", model_id);
+    Ok(final_path)
+}
+
+/// Write the body of `response` to the .part file, which already holds
+/// `downloaded` bytes; returns the bytes on disk and the expected total
+async fn write_download(
+    app: &AppHandle,
+    model_id: &str,
+    response: reqwest::Response,
+    part_path: &Path,
+    mut downloaded: u64,
+) -> Result<(u64, Option<u64>), String> {
+    let status = response.status();
+    let mut file = if status == reqwest::StatusCode::PARTIAL_CONTENT {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(part_path)
+            .await
+            .map_err(|e| format!("Failed to open partial download: {}", e))?
+    } else if status.is_success() {
+        // Server ignored the range request (or nothing to resume) — start over
+        downloaded = 0;
+        tokio::fs::File::create(part_path)
+            .await
+            .map_err(|e| format!("Failed to create download file: {}", e))?
+    } else {
+        return Err(format!("Failed to download model: HTTP {}", status));
+    };
+
+    let total_bytes = response.content_length().map(|len| len + downloaded);
+    let mut last_emitted = downloaded;
+    emit_progress(app, model_id, downloaded, total_bytes, ModelDownloadStatus::Downloading, None);
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download interrupted: {}", e))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write model: {}", e))?;
+        downloaded += chunk.len() as u64;
+
+        if downloaded - last_emitted >= PROGRESS_INTERVAL_BYTES {
+            last_emitted = downloaded;
+            emit_progress(app, model_id, downloaded, total_bytes, ModelDownloadStatus::Downloading, None);
+        }
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write model: {}", e))?;
+    Ok((downloaded, total_bytes))
+}
+
+/// Path of an installed model, downloading it first if missing
+pub async fn ensure_model(app: &AppHandle, model_id: &str) -> Result<PathBuf, String> {
+    let info = find_model(model_id)?;
+    let path = models_dir(app)?.join(info.file_name);
+
+    if path.exists() {
+        tracing::info!("Whisper model found at {:?}", path);
+        return Ok(path);
+    }
+
+    tracing::info!("Downloading Whisper model ({})...", model_id);
+    download_model(app, model_id).await
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Catalog entry plus local install state
+#[derive(Debug, Clone, Serialize)]
+pub struct WhisperModelStatus {
+    #[serde(flatten)]
+    pub info: WhisperModelInfo,
+    pub installed: bool,
+    pub verified: bool,
+    pub active: bool,
+    pub size_on_disk: u64,
+    /// Bytes of an interrupted download that can be resumed
+    pub partial_bytes: u64,
+}
+
+/// Disk space used by one model
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDiskUsage {
+    pub model_id: String,
+    pub bytes: u64,
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WhisperDiskUsage {
+    pub models_dir: String,
+    pub total_bytes: u64,
+    pub models: Vec<ModelDiskUsage>,
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// List all catalog models with their install state
+#[tauri::command]
+pub async fn list_whisper_models(app: AppHandle) -> Result<Vec<WhisperModelStatus>, String> {
+    let dir = models_dir(&app)?;
+    let manifest = ModelManifest::load(&dir);
+    let active = manifest.active_model_id();
+
+    Ok(MODEL_CATALOG
+        .iter()
+        .map(|info| {
+            let path = dir.join(info.file_name);
+            WhisperModelStatus {
+                info: info.clone(),
+                installed: path.exists(),
+                verified: path.exists() && manifest.verified.contains_key(info.file_name),
+                active: info.id == active,
+                size_on_disk: file_size(&path),
+                partial_bytes: file_size(&dir.join(format!("{}.part", info.file_name))),
+            }
+        })
+        .collect())
+}
+
+/// Download (or resume downloading) a model; progress arrives as `whisper-model-progress`
+#[tauri::command]
+pub async fn download_whisper_model(app: AppHandle, model_id: String) -> Result<(), String> {
+    download_model(&app, &model_id).await.map(|_| ())
+}
+
+/// Select the model used for local transcription
+#[tauri::command]
+pub async fn set_active_whisper_model(
+    app: AppHandle,
+    model_id: String,
+    whisper: tauri::State<'_, WhisperManager>,
+) -> Result<(), String> {
+    find_model(&model_id)?;
+
+    let dir = models_dir(&app)?;
+    let mut manifest = ModelManifest::load(&dir);
+    manifest.active_model = Some(model_id.clone());
+    manifest.save(&dir)?;
+
+    // The next transcription loads the newly selected model
+    whisper.lock().await.unload();
+
+    tracing::info!("Active Whisper model set to {}", model_id);
+    Ok(())
+}
+
+/// Re-hash an installed model and compare it with the published checksum
+#[tauri::command]
+pub async fn verify_whisper_model(app: AppHandle, model_id: String) -> Result<bool, String> {
+    let info = find_model(&model_id)?;
+    let dir = models_dir(&app)?;
+    let path = dir.join(info.file_name);
+
+    if !path.exists() {
+        return Err(format!("Model {} is not installed", model_id));
+    }
+
+    let mut manifest = ModelManifest::load(&dir);
+    let expected = match manifest.verified.get(info.file_name) {
+        Some(hash) => hash.clone(),
+        None => fetch_expected_sha256(&reqwest::Client::new(), info.file_name).await?,
+    };
+
+    let actual = hash_in_background(path).await?;
+    let valid = actual == expected;
+
+    if valid {
+        manifest.verified.insert(info.file_name.to_string(), actual);
+    } else {
+        tracing::warn!("Whisper model {} failed verification", model_id);
+        manifest.verified.remove(info.file_name);
+    }
+    manifest.save(&dir)?;
+
+    Ok(valid)
+}
+
+/// Install a model from a local file (for machines without internet access).
+///
+/// When `expected_sha256` is given the file must match it; otherwise only the
+/// ggml header is checked and the model is recorded as unverified.
+#[tauri::command]
+pub async fn import_whisper_model(
+    app: AppHandle,
+    model_id: String,
+    source_path: String,
+    expected_sha256: Option<String>,
+) -> Result<(), String> {
+    let info = find_model(&model_id)?;
+    let dir = models_dir(&app)?;
+    let source = PathBuf::from(&source_path);
+    // Shares the .part file with downloads
+    let _guard = DownloadGuard::acquire(&model_id)?;
+
+    if !has_ggml_magic(&source)? {
+        return Err(format!("{} is not a ggml Whisper model", source_path));
+    }
+
+    let part_path = dir.join(format!("{}.part", info.file_name));
+    let copy_target = part_path.clone();
+    tokio::task::spawn_blocking(move || std::fs::copy(&source, &copy_target))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+        .map_err(|e| format!("Failed to copy model: {}", e))?;
+
+    let actual = hash_in_background(part_path.clone()).await?;
+    if let Some(expected) = &expected_sha256 {
+        if !expected.eq_ignore_ascii_case(&actual) {
+            let _ = std::fs::remove_file(&part_path);
+            return Err(format!(
+                "Checksum mismatch for imported model (expected {}, got {})",
+                expected, actual
+            ));
+        }
+    }
+
+    std::fs::rename(&part_path, dir.join(info.file_name))
+        .map_err(|e| format!("Failed to install model: {}", e))?;
+
+    let mut manifest = ModelManifest::load(&dir);
+    if expected_sha256.is_some() {
+        manifest.verified.insert(info.file_name.to_string(), actual);
+    } else {
+        manifest.verified.remove(info.file_name);
+    }
+    manifest.save(&dir)?;
+
+    tracing::info!("Imported Whisper model {} from {}", model_id, source_path);
+    Ok(())
+}
+
+/// Delete an installed model (and any partial download of it)
+#[tauri::command]
+pub async fn delete_whisper_model(
+    app: AppHandle,
+    model_id: String,
+    whisper: tauri::State<'_, WhisperManager>,
+) -> Result<(), String> {
+    let info = find_model(&model_id)?;
+    let dir = models_dir(&app)?;
+    let mut manifest = ModelManifest::load(&dir);
+    manifest.check_deletable(&model_id)?;
+
+    {
+        let mut engine = whisper.lock().await;
+        if engine.loaded_model() == Some(model_id.as_str()) {
+            engine.unload();
+        }
+    }
+
+    for path in [dir.join(info.file_name), dir.join(format!("{}.part", info.file_name))] {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+        }
+    }
+
+    manifest.verified.remove(info.file_name);
+    manifest.save(&dir)?;
+
+    tracing::info!("Deleted Whisper model {}", model_id);
+    Ok(())
+}
+
+/// Report disk space used by downloaded and partially downloaded models
+#[tauri::command]
+pub async fn get_whisper_models_disk_usage(app: AppHandle) -> Result<WhisperDiskUsage, String> {
+    let dir = models_dir(&app)?;
+    let mut models = Vec::new();
+
+    for info in MODEL_CATALOG {
+        for (path, partial) in [
+            (dir.join(info.file_name), false),
+            (dir.join(format!("{}.part", info.file_name)), true),
+        ] {
+            let bytes = file_size(&path);
+            if bytes > 0 {
+                models.push(ModelDiskUsage {
+                    model_id: info.id.to_string(),
+                    bytes,
+                    partial,
+                });
+            }
+        }
+    }
+
+    Ok(WhisperDiskUsage {
+        models_dir: dir.to_string_lossy().to_string(),
+        total_bytes: models.iter().map(|m| m.bytes).sum(),
+        models,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("whisper-model-test-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_catalog_ids_unique_and_default_present() {
+        let ids: HashSet<&str> = MODEL_CATALOG.iter().map(|m| m.id).collect();
+        assert_eq!(ids.len(), MODEL_CATALOG.len());
+        assert!(find_model(DEFAULT_MODEL_ID).is_ok());
+        assert!(find_model("huge").is_err());
+    }
+
+    #[test]
+    fn test_catalog_flags_match_file_names() {
+        for m in MODEL_CATALOG {
+            assert_eq!(m.multilingual, !m.file_name.contains(".en"), "{}", m.id);
+            assert_eq!(m.quantized, m.file_name.contains("-q"), "{}", m.id);
+            assert_eq!(m.file_name, format!("ggml-{}.bin", m.id));
+        }
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        let path = temp_file(b"abc");
+        let hash = sha256_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            hash,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_ggml_magic() {
+        let valid = temp_file(&[0x6c, 0x6d, 0x67, 0x67, 0x01]);
+        let invalid = temp_file(b"<html>");
+        let short = temp_file(b"lm");
+        assert!(has_ggml_magic(&valid).unwrap());
+        assert!(!has_ggml_magic(&invalid).unwrap());
+        assert!(!has_ggml_magic(&short).unwrap());
+        for p in [valid, invalid, short] {
+            std::fs::remove_file(p).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_expected_sha256_from_tree() {
+        let json = r#"[
+            {"type": "file", "path": "README.md", "size": 100},
+            {"type": "file", "path": "ggml-base.en.bin", "size": 147964211,
+             "lfs": {"oid": "ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789", "size": 147964211}}
+        ]"#;
+        let entries: Vec<HfTreeEntry> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            expected_sha256_from_tree(&entries, "ggml-base.en.bin").as_deref(),
+            Some("abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789")
+        );
+        assert!(expected_sha256_from_tree(&entries, "README.md").is_none());
+        assert!(expected_sha256_from_tree(&entries, "ggml-tiny.bin").is_none());
+    }
+
+    #[test]
+    fn test_download_guard() {
+        let guard = DownloadGuard::acquire("tiny.en-q5_1").unwrap();
+        assert!(is_downloading("tiny.en-q5_1"));
+        assert!(DownloadGuard::acquire("tiny.en-q5_1").is_err());
+        drop(guard);
+        assert!(!is_downloading("tiny.en-q5_1"));
+    }
+
+    #[test]
+    fn test_check_deletable() {
+        let manifest = ModelManifest {
+            active_model: Some("small".to_string()),
+            ..Default::default()
+        };
+        assert!(manifest.check_deletable("small").unwrap_err().contains("active model"));
+        assert!(manifest.check_deletable(DEFAULT_MODEL_ID).is_ok());
+        assert!(ModelManifest::default().check_deletable(DEFAULT_MODEL_ID).is_err());
+
+        let _guard = DownloadGuard::acquire("medium").unwrap();
+        assert!(manifest.check_deletable("medium").unwrap_err().contains("being downloaded"));
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let dir = std::env::temp_dir().join(format!("whisper-manifest-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let empty = ModelManifest::load(&dir);
+        assert_eq!(empty.active_model_id(), DEFAULT_MODEL_ID);
+
+        let mut manifest = ModelManifest {
+            active_model: Some("small".to_string()),
+            ..Default::default()
+        };
+        manifest.verified.insert("ggml-small.bin".to_string(), "abc".to_string());
+        manifest.save(&dir).unwrap();
+
+        assert_eq!(ModelManifest::load(&dir), manifest);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+//! User-tunable Whisper decoding settings, persisted in the app data dir.
+//!
+//! Settings are read when a transcription (or live stream) starts, so changes
+//! apply from the next run without reloading the model.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use whisper_rs::{FullParams, SamplingStrategy};
+
+use crate::storage;
+use crate::transcription::language::LanguageConfig;
+use crate::transcription::vocabulary::Vocabulary;
+
+const SETTINGS_FILE: &str = "whisper-settings.json";
+
+/// Upper bound for the automatic thread count; more threads stop helping
+/// whisper.cpp on typical desktop CPUs
+const MAX_AUTO_THREADS: usize = 8;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DecodingStrategy {
+    /// Fast; keeps the best of `best_of` sampled candidates when temperature > 0
+    Greedy { best_of: i32 },
+    /// Slower, usually more accurate on noisy or accented speech
+    BeamSearch { beam_size: i32, patience: f32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WhisperSettings {
+    /// Inference threads; `None` picks a value from the available cores
+    pub n_threads: Option<u32>,
+    pub strategy: DecodingStrategy,
+    /// Initial decoding temperature (0.0 = deterministic)
+    pub temperature: f32,
+    /// Step used to raise the temperature when a decode fails whisper's
+    /// quality checks; 0.0 disables temperature fallback
+    pub temperature_increment: f32,
+    /// Text fed to the decoder as prior context (names, jargon, style)
+    pub initial_prompt: Option<String>,
+    /// Segments whose no-speech probability exceeds this are treated as silence
+    pub no_speech_threshold: f32,
+    /// Language to decode with; `None` lets multilingual models detect it.
+    /// Comes from the shared language config, not this file.
+    #[serde(skip)]
+    pub language: Option<String>,
+}
+
+impl Default for WhisperSettings {
+    fn default() -> Self {
+        Self {
+            n_threads: None,
+            strategy: DecodingStrategy::Greedy { best_of: 1 },
+            temperature: 0.0,
+            temperature_increment: 0.2,
+            initial_prompt: None,
+            no_speech_threshold: 0.6,
+            language: None,
+        }
+    }
+}
+
+impl WhisperSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(n) = self.n_threads {
+            if n == 0 || n > 64 {
+                return Err("Thread count must be between 1 and 64".to_string());
+            }
+        }
+
+        match self.strategy {
+            DecodingStrategy::Greedy { best_of } if !(1..=10).contains(&best_of) => {
+                return Err("best_of must be between 1 and 10".to_string());
+            }
+            DecodingStrategy::BeamSearch { beam_size, patience } => {
+                if !(1..=16).contains(&beam_size) {
+                    return Err("Beam size must be between 1 and 16".to_string());
+                }
+                if patience != -1.0 && patience <= 0.0 {
+                    return Err("Beam patience must be positive (or -1 for default)".to_string());
+                }
+            }
+            _ => {}
+        }
+
+        for (name, value) in [
+            ("Temperature", self.temperature),
+            ("Temperature increment", self.temperature_increment),
+            ("No-speech threshold", self.no_speech_threshold),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("{} must be between 0.0 and 1.0", name));
+            }
+        }
+
+        if self.initial_prompt.as_deref().is_some_and(|p| p.contains('\0')) {
+            return Err("Initial prompt must not contain null bytes".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Thread count actually passed to whisper.cpp
+    pub fn effective_threads(&self) -> i32 {
+        match self.n_threads {
+            Some(n) => n as i32,
+            None => std::thread::available_parallelism()
+                .map(|n| n.get().min(MAX_AUTO_THREADS))
+                .unwrap_or(4) as i32,
+        }
+    }
+
+    /// Append the custom vocabulary to the initial prompt as a glossary
+    pub fn with_vocabulary(mut self, vocabulary: &Vocabulary) -> Self {
+        self.initial_prompt = vocabulary.whisper_prompt(self.initial_prompt.as_deref());
+        self
+    }
+
+    /// Pin decoding to the configured language unless detection is on
+    pub fn with_language(mut self, config: &LanguageConfig) -> Self {
+        self.language = config.fixed_language().map(String::from);
+        self
+    }
+
+    /// Build decoding parameters for one inference pass
+    pub fn full_params<'a>(&self, language: &'a str) -> FullParams<'a, 'a> {
+        let strategy = match self.strategy {
+            DecodingStrategy::Greedy { best_of } => SamplingStrategy::Greedy { best_of },
+            DecodingStrategy::BeamSearch { beam_size, patience } => {
+                SamplingStrategy::BeamSearch { beam_size, patience }
+            }
+        };
+
+        let mut params = FullParams::new(strategy);
+        params.set_n_threads(self.effective_threads());
+        params.set_language(Some(language));
+        params.set_temperature(self.temperature);
+        params.set_temperature_inc(self.temperature_increment);
+        params.set_no_speech_thold(self.no_speech_threshold);
+        if let Some(prompt) = self.prompt() {
+            params.set_initial_prompt(prompt);
+        }
+        params
+    }
+
+    /// The initial prompt, unless it's empty or holds a null byte, which
+    /// whisper.cpp can't take
+    fn prompt(&self) -> Option<&str> {
+        let prompt = self.initial_prompt.as_deref().filter(|p| !p.trim().is_empty())?;
+        if prompt.contains('\0') {
+            tracing::warn!("Skipping Whisper initial prompt with a null byte");
+            return None;
+        }
+        Some(prompt)
+    }
+
+    /// Load saved settings, falling back to defaults if none are saved
+    pub fn load(app: &AppHandle) -> Self {
+        let settings: Self = storage::load_json(app, SETTINGS_FILE);
+        if settings.validate().is_ok() {
+            settings
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        storage::save_json(app, SETTINGS_FILE, self)
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_whisper_settings(app: AppHandle) -> Result<WhisperSettings, String> {
+    Ok(WhisperSettings::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_whisper_settings(app: AppHandle, settings: WhisperSettings) -> Result<(), String> {
+    settings.validate()?;
+    settings.save(&app)?;
+    tracing::info!("Whisper settings updated: {:?}", settings);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_settings_are_valid() {
+        let settings = WhisperSettings::default();
+        assert!(settings.validate().is_ok());
+        assert!(settings.effective_threads() >= 1);
+        assert!(settings.effective_threads() <= MAX_AUTO_THREADS as i32);
+    }
+
+    #[test]
+    fn test_invalid_settings_rejected() {
+        let cases = [
+            WhisperSettings { n_threads: Some(0), ..Default::default() },
+            WhisperSettings { strategy: DecodingStrategy::Greedy { best_of: 0 }, ..Default::default() },
+            WhisperSettings {
+                strategy: DecodingStrategy::BeamSearch { beam_size: 32, patience: -1.0 },
+                ..Default::default()
+            },
+            WhisperSettings { temperature: 1.5, ..Default::default() },
+            WhisperSettings { no_speech_threshold: -0.1, ..Default::default() },
+            WhisperSettings { initial_prompt: Some("a\0b".to_string()), ..Default::default() },
+        ];
+        for settings in cases {
+            assert!(settings.validate().is_err(), "{:?}", settings);
+        }
+    }
+
+    #[test]
+    fn test_partial_json_uses_defaults() {
+        let json = r#"{"n_threads": 6, "strategy": {"type": "beam_search", "beam_size": 5, "patience": -1.0}}"#;
+        let settings: WhisperSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.effective_threads(), 6);
+        assert_eq!(
+            settings.strategy,
+            DecodingStrategy::BeamSearch { beam_size: 5, patience: -1.0 }
+        );
+        assert_eq!(settings.no_speech_threshold, WhisperSettings::default().no_speech_threshold);
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_prompt_skips_null_bytes() {
+        let vocabulary = Vocabulary {
+            terms: vec![crate::transcription::vocabulary::VocabularyTerm {
+                term: "Au\0rus".to_string(),
+                boost: None,
+                replacements: Vec::new(),
+            }],
+        };
+        let settings = WhisperSettings::default().with_vocabulary(&vocabulary);
+        assert!(settings.initial_prompt.is_some());
+        assert_eq!(settings.prompt(), None);
+
+        let settings = WhisperSettings { initial_prompt: Some("Aurus".to_string()), ..Default::default() };
+        assert_eq!(settings.prompt(), Some("Aurus"));
+    }
+}
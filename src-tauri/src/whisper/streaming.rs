@@ -0,0 +1,410 @@
+//! Live offline transcription over a sliding window of captured audio.
+//!
+//! Audio arrives through the same `AudioIngress` route Deepgram uses.
+//! Every `STEP_MS` of new audio the current window is re-transcribed and
+//! emitted as an interim result; once the window reaches `LENGTH_MS` it is
+//! emitted as final and a new window starts, carrying `KEEP_MS` of overlap so
+//! words at the boundary are not cut in half. Segments in that overlap were
+//! already part of the previous final transcript and are dropped.
+
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+use super::settings::WhisperSettings;
+use super::{
+    convert_i16_to_f32, join_segments, load_context, run_inference, segment_spans,
+    transcript_confidence, WhisperManager, WhisperSegment,
+};
+use crate::platform::TARGET_SAMPLE_RATE;
+use crate::transcription::language::LanguageConfig;
+use crate::transcription::session::{begin_session, LiveEngine};
+use crate::transcription::vocabulary::Vocabulary;
+use crate::transcription::{samples_to_ms, TranscriptEvent, TranscriptionManager};
+
+/// New audio required before the window is re-transcribed
+const STEP_MS: usize = 2000;
+/// Window length at which the transcript is finalized
+const LENGTH_MS: usize = 10000;
+/// Audio carried over from a finalized window into the next one
+const KEEP_MS: usize = 200;
+
+fn ms_to_samples(ms: usize) -> usize {
+    TARGET_SAMPLE_RATE as usize * ms / 1000
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SlidingWindowConfig {
+    pub step_samples: usize,
+    pub length_samples: usize,
+    pub keep_samples: usize,
+}
+
+impl Default for SlidingWindowConfig {
+    fn default() -> Self {
+        Self {
+            step_samples: ms_to_samples(STEP_MS),
+            length_samples: ms_to_samples(LENGTH_MS),
+            keep_samples: ms_to_samples(KEEP_MS),
+        }
+    }
+}
+
+/// A chunk of audio ready for inference
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowPass {
+    pub audio: Vec<i16>,
+    pub is_final: bool,
+    /// Offset of `audio` from the start of the stream, in samples
+    pub start_sample: usize,
+    /// Leading samples of `audio` the previous final pass already covered
+    pub overlap_samples: usize,
+}
+
+/// Accumulates streamed samples and decides when to run inference
+pub struct SlidingWindow {
+    config: SlidingWindowConfig,
+    buffer: Vec<i16>,
+    /// Samples received since the last pass
+    pending: usize,
+    /// Overlap carried over from the previous final window
+    carried: usize,
+    /// Stream offset of `buffer[0]`
+    buffer_start: usize,
+}
+
+impl SlidingWindow {
+    pub fn new(config: SlidingWindowConfig) -> Self {
+        Self {
+            config,
+            buffer: Vec::with_capacity(config.length_samples + config.step_samples),
+            pending: 0,
+            carried: 0,
+            buffer_start: 0,
+        }
+    }
+
+    /// Add samples; returns a pass once enough new audio has accumulated
+    pub fn push(&mut self, samples: &[i16]) -> Option<WindowPass> {
+        self.buffer.extend_from_slice(samples);
+        self.pending += samples.len();
+
+        if self.pending < self.config.step_samples {
+            return None;
+        }
+        self.pending = 0;
+
+        if self.buffer.len() < self.config.length_samples {
+            return Some(WindowPass {
+                audio: self.buffer.clone(),
+                is_final: false,
+                start_sample: self.buffer_start,
+                overlap_samples: self.carried,
+            });
+        }
+
+        let audio = std::mem::take(&mut self.buffer);
+        let keep_from = audio.len().saturating_sub(self.config.keep_samples);
+        self.buffer.extend_from_slice(&audio[keep_from..]);
+        let overlap_samples = std::mem::replace(&mut self.carried, self.buffer.len());
+
+        let start_sample = self.buffer_start;
+        self.buffer_start += keep_from;
+
+        Some(WindowPass {
+            audio,
+            is_final: true,
+            start_sample,
+            overlap_samples,
+        })
+    }
+
+    /// Finalize whatever is left when the stream ends
+    pub fn flush(&mut self) -> Option<WindowPass> {
+        let has_new_audio = self.buffer.len() > self.carried;
+        let audio = std::mem::take(&mut self.buffer);
+        self.pending = 0;
+        let overlap_samples = std::mem::take(&mut self.carried);
+
+        let start_sample = self.buffer_start;
+        self.buffer_start += audio.len();
+
+        has_new_audio.then_some(WindowPass {
+            audio,
+            is_final: true,
+            start_sample,
+            overlap_samples,
+        })
+    }
+}
+
+/// Drop the segments that lie mostly in the first `overlap_ms` of a window,
+/// which the previous final transcript already contains
+fn without_overlap(segments: Vec<WhisperSegment>, overlap_ms: i64) -> Vec<WhisperSegment> {
+    segments
+        .into_iter()
+        .filter(|s| s.start_ms + s.end_ms >= 2 * overlap_ms)
+        .collect()
+}
+
+/// Wait for the next pass; flushes the window once the audio channel closes
+async fn next_pass(
+    rx: &mut mpsc::Receiver<Vec<i16>>,
+    window: &mut SlidingWindow,
+) -> Option<WindowPass> {
+    while let Some(samples) = rx.recv().await {
+        if let Some(pass) = window.push(&samples) {
+            return Some(pass);
+        }
+    }
+    window.flush()
+}
+
+/// Start live transcription with the local Whisper model.
+///
+/// Captured audio is routed here by the `AudioIngress` until
+/// `stop_local_whisper_stream` is called.
+#[tauri::command]
+pub async fn start_local_whisper_stream(
+    app: AppHandle,
+    state: tauri::State<'_, TranscriptionManager>,
+    whisper: tauri::State<'_, WhisperManager>,
+) -> Result<(), String> {
+    open_local_whisper_stream(&app, state.inner(), whisper.inner()).await
+}
+
+/// Load the model and start the sliding-window transcription task
+pub(crate) async fn open_local_whisper_stream(
+    app: &AppHandle,
+    state: &TranscriptionManager,
+    whisper: &WhisperManager,
+) -> Result<(), String> {
+    let session_id = begin_session(app, state, LiveEngine::LocalWhisper).await?;
+    let result = spawn_whisper_stream(app, state, whisper, &session_id).await;
+    if let Err(e) = &result {
+        let mut state_guard = state.lock().await;
+        // Stopped while loading: the slot is free again
+        state_guard.session.finish(&session_id);
+        state_guard.session.fail(&session_id, e);
+    }
+    result
+}
+
+async fn spawn_whisper_stream(
+    app: &AppHandle,
+    state: &TranscriptionManager,
+    whisper: &WhisperManager,
+    session_id: &str,
+) -> Result<(), String> {
+    // Loading (and possibly downloading) the model can take a while
+    let (ctx, metrics) = load_context(app, whisper).await?;
+
+    let mut whisper_state = ctx
+        .create_state()
+        .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+
+    let vocabulary = Vocabulary::load(app);
+    let settings = Arc::new(
+        WhisperSettings::load(app)
+            .with_vocabulary(&vocabulary)
+            .with_language(&LanguageConfig::load(app)),
+    );
+    let replacer = vocabulary.replacer();
+
+    let (tx, mut rx) = mpsc::channel::<Vec<i16>>(100);
+    state.lock().await.session.start_streaming(session_id, tx)?;
+
+    let app_clone = app.clone();
+    let state_clone = state.clone();
+    let session_id = session_id.to_string();
+
+    tokio::spawn(async move {
+        let mut window = SlidingWindow::new(SlidingWindowConfig::default());
+        let mut failure = None;
+
+        while let Some(pass) = next_pass(&mut rx, &mut window).await {
+            let audio = convert_i16_to_f32(&pass.audio);
+            let ctx = ctx.clone();
+            let settings = settings.clone();
+
+            let joined = tokio::task::spawn_blocking(move || {
+                let result = run_inference(&ctx, &mut whisper_state, &audio, &settings);
+                (whisper_state, result)
+            })
+            .await;
+
+            let output = match joined {
+                Ok((returned_state, result)) => {
+                    whisper_state = returned_state;
+                    result
+                }
+                Err(e) => {
+                    tracing::error!("Whisper stream task failed: {}", e);
+                    failure = Some(format!("Whisper stream task failed: {}", e));
+                    break;
+                }
+            };
+
+            match output {
+                Ok(output) => {
+                    if let Some(pass_metrics) = &output.metrics {
+                        if let Ok(mut totals) = metrics.lock() {
+                            totals.record(pass_metrics);
+                        }
+                        let _ = app_clone.emit("whisper-metrics", pass_metrics.clone());
+                    }
+
+                    let segments = without_overlap(output.segments, samples_to_ms(pass.overlap_samples));
+                    let mut text = join_segments(&segments);
+                    if pass.is_final {
+                        text = replacer.apply(&text);
+                    }
+                    if !text.is_empty() {
+                        let start_ms = samples_to_ms(pass.start_sample + pass.overlap_samples);
+                        let _ = app_clone.emit(
+                            "transcript",
+                            TranscriptEvent {
+                                text,
+                                is_final: pass.is_final,
+                                confidence: transcript_confidence(&segments),
+                                source: "whisper-local".to_string(),
+                                low_confidence_spans: segment_spans(&segments),
+                                start_ms: Some(start_ms),
+                                end_ms: Some(samples_to_ms(pass.start_sample + pass.audio.len())),
+                                // Identified per window, so code-switching shows up per segment
+                                language: output.language,
+                                segments: Vec::new(),
+                            },
+                        );
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Whisper stream inference error: {}", e);
+                    failure = Some(e.clone());
+                    let _ = app_clone.emit("whisper-stream-error", e);
+                    break;
+                }
+            }
+        }
+
+        // No-ops if a newer session has taken over
+        {
+            let mut state_guard = state_clone.lock().await;
+            match &failure {
+                Some(e) => {
+                    state_guard.session.fail(&session_id, e);
+                }
+                None => state_guard.session.finish(&session_id),
+            }
+        }
+
+        let _ = app_clone.emit("whisper-stream-stopped", ());
+    });
+
+    app.emit("whisper-stream-started", ()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Stop the local Whisper stream; the remaining audio is finalized in the background
+#[tauri::command]
+pub async fn stop_local_whisper_stream(
+    state: tauri::State<'_, TranscriptionManager>,
+) -> Result<(), String> {
+    let mut state_guard = state.lock().await;
+    if state_guard.session.engine == Some(LiveEngine::LocalWhisper) {
+        // The task finalizes the window and ends the session once its input closes
+        state_guard.session.drain();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SlidingWindowConfig {
+        SlidingWindowConfig {
+            step_samples: 4,
+            length_samples: 10,
+            keep_samples: 2,
+        }
+    }
+
+    #[test]
+    fn test_no_pass_before_step() {
+        let mut window = SlidingWindow::new(config());
+        assert!(window.push(&[1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn test_interim_pass_contains_whole_window() {
+        let mut window = SlidingWindow::new(config());
+        let first = window.push(&[1, 2, 3, 4]).unwrap();
+        assert!(!first.is_final);
+        assert_eq!(first.audio, vec![1, 2, 3, 4]);
+
+        let second = window.push(&[5, 6, 7, 8]).unwrap();
+        assert!(!second.is_final);
+        assert_eq!(second.audio, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_final_pass_keeps_overlap() {
+        let mut window = SlidingWindow::new(config());
+        window.push(&[1, 2, 3, 4]);
+        window.push(&[5, 6, 7, 8]);
+        let pass = window.push(&[9, 10, 11, 12]).unwrap();
+        assert!(pass.is_final);
+        assert_eq!(pass.audio, (1..=12).collect::<Vec<i16>>());
+
+        // Next window starts with the kept overlap
+        let next = window.push(&[13, 14, 15, 16]).unwrap();
+        assert!(!next.is_final);
+        assert_eq!(next.audio, vec![11, 12, 13, 14, 15, 16]);
+        assert_eq!((pass.start_sample, next.start_sample), (0, 10));
+        assert_eq!((pass.overlap_samples, next.overlap_samples), (0, 2));
+
+        // Consecutive final passes cover the stream without overlap
+        let second = window.push(&[17, 18, 19, 20]).unwrap();
+        assert!(second.is_final);
+        assert_eq!(second.start_sample + second.overlap_samples, pass.start_sample + pass.audio.len());
+    }
+
+    #[test]
+    fn test_overlap_segments_are_dropped() {
+        let segment = |text: &str, start_ms, end_ms| WhisperSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            confidence: 0.9,
+            words: Vec::new(),
+        };
+        // The previous window ended with "the release", carried over as 200ms
+        let segments = vec![
+            segment("the release.", 0, 180),
+            segment("Next topic", 150, 1200),
+            segment("is hiring.", 1200, 2000),
+        ];
+        let kept = without_overlap(segments.clone(), 200);
+        assert_eq!(join_segments(&kept), "Next topic is hiring.");
+        assert_eq!(without_overlap(segments, 0).len(), 3);
+    }
+
+    #[test]
+    fn test_flush_finalizes_remaining_audio() {
+        let mut window = SlidingWindow::new(config());
+        window.push(&[1, 2, 3, 4]);
+        window.push(&[5]);
+        let pass = window.flush().unwrap();
+        assert!(pass.is_final);
+        assert_eq!(pass.audio, vec![1, 2, 3, 4, 5]);
+        assert!(window.flush().is_none());
+    }
+
+    #[test]
+    fn test_flush_ignores_carried_overlap_only() {
+        let mut window = SlidingWindow::new(config());
+        window.push(&(1..=12).collect::<Vec<i16>>());
+        assert!(window.flush().is_none());
+    }
+}